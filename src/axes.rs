@@ -0,0 +1,117 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{pick_list, row, slider, text, Row},
+    Element
+};
+
+use super::{Axis, Instance, Message, Variations};
+
+//*****************************************************************************
+pub struct Axes {
+    axes:      Vec<Axis>,
+    instances: Vec<Instance>,
+    instance:  Option<Instance>,
+    location:  Vec<f32>
+}
+
+impl Axes {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            axes:      Vec::new(),
+            instances: Vec::new(),
+            instance:  None,
+            location:  Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    pub fn view(&self) -> Element<'_, Message> {
+        // Fonts without variations have nothing to show.
+        if self.axes.is_empty() {
+            return Row::new().into();
+        }
+
+        let mut result = row![
+            text("Instance:"),
+            pick_list(self.instances.as_slice(), self.instance.clone(), Message::ChangeInstance)
+        ].padding(4)
+         .spacing(8);
+
+        for (i, (axis, &value)) in self.axes.iter().zip(self.location.iter()).enumerate() {
+            result = result.push(text(format!("{}:", axis.tag)))
+                .push(
+                    slider(axis.min..=axis.max, value, move |x|Message::ChangeAxis(i, x))
+                        .default(axis.default)
+                        .step(0.1)
+                        .width(160)
+                )
+                .push(text(format!("{:.1}", value)));
+        }
+
+        result.into()
+    }
+
+    //*************************************************************************
+    pub fn location(&self) -> &[f32] {
+        &self.location
+    }
+
+    //*************************************************************************
+    fn find_instance(&mut self) {
+        self.instance = self.instances.iter()
+            .find(|x|x.coordinates == self.location)
+            .cloned();
+    }
+
+    //*************************************************************************
+    pub fn handle_font(
+                &mut self,
+                variations: Option<&Variations>
+            ) {
+        self.axes      = variations.map(|x|x.axes().to_vec()).unwrap_or_default();
+        self.instances = variations.map(|x|x.instances().to_vec()).unwrap_or_default();
+        self.location  = self.axes.iter().map(|x|x.default).collect();
+        self.find_instance();
+    }
+
+    //*************************************************************************
+    pub fn handle_axis(
+                &mut self,
+                idx:   usize,
+                value: f32
+            ) {
+        self.location[idx] = value;
+        self.find_instance();
+    }
+
+    //*************************************************************************
+    pub fn handle_instance(
+                &mut self,
+                instance: Instance
+            ) {
+        self.location = instance.coordinates.clone();
+        self.location.resize(self.axes.len(), 0.0);
+        self.instance = Some(instance);
+    }
+}
@@ -0,0 +1,178 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::fmt::Display;
+
+use crate::sfnt::{read_u16, read_u8};
+
+//*****************************************************************************
+const NAMES: [&str; 0x90] = [
+    "SVTCA", "SVTCA", "SPVTCA", "SPVTCA", "SFVTCA", "SFVTCA", "SPVTL", "SPVTL",
+    "SFVTL", "SFVTL", "SPVFS", "SFVFS", "GPV", "GFV", "SFVTPV", "ISECT",
+    "SRP0", "SRP1", "SRP2", "SZP0", "SZP1", "SZP2", "SZPS", "SLOOP",
+    "RTG", "RTHG", "SMD", "ELSE", "JMPR", "SCVTCI", "SSWCI", "SSW",
+    "DUP", "POP", "CLEAR", "SWAP", "DEPTH", "CINDEX", "MINDEX", "ALIGNPTS",
+    "INS_28", "UTP", "LOOPCALL", "CALL", "FDEF", "ENDF", "MDAP", "MDAP",
+    "IUP", "IUP", "SHP", "SHP", "SHC", "SHC", "SHZ", "SHZ",
+    "SHPIX", "IP", "MSIRP", "MSIRP", "ALIGNRP", "RTDG", "MIAP", "MIAP",
+    "NPUSHB", "NPUSHW", "WS", "RS", "WCVTP", "RCVT", "GC", "GC",
+    "SCFS", "MD", "MD", "MPPEM", "MPS", "FLIPON", "FLIPOFF", "DEBUG",
+    "LT", "LTEQ", "GT", "GTEQ", "EQ", "NEQ", "ODD", "EVEN",
+    "IF", "EIF", "AND", "OR", "NOT", "DELTAP1", "SDB", "SDS",
+    "ADD", "SUB", "DIV", "MUL", "ABS", "NEG", "FLOOR", "CEILING",
+    "ROUND", "ROUND", "ROUND", "ROUND", "NROUND", "NROUND", "NROUND", "NROUND",
+    "WCVTF", "DELTAP2", "DELTAP3", "DELTAC1", "DELTAC2", "DELTAC3", "SROUND", "S45ROUND",
+    "JROT", "JROF", "ROFF", "INS_7B", "RUTG", "RDTG", "SANGW", "AA",
+    "FLIPPT", "FLIPRGON", "FLIPRGOFF", "INS_83", "INS_84", "SCANCTRL", "SDPVTL", "SDPVTL",
+    "GETINFO", "IDEF", "ROLL", "MAX", "MIN", "SCANTYPE", "INSTCTRL", "INS_8F"
+];
+
+//*****************************************************************************
+const DISTANCES: [&str; 4] = ["gray", "black", "white", "3"];
+
+//*****************************************************************************
+// One instruction with the values it pushes, indented by the IF and FDEF
+// blocks it sits in.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub offset: usize,
+    pub opcode: u8,
+    pub data:   Vec<i32>,
+    pub depth:  usize
+}
+
+impl Operation {
+    //*************************************************************************
+    pub fn name(&self) -> String {
+        let opcode = self.opcode;
+        let flag   = |set: &str, clear: &str| String::from(if opcode & 1 != 0 { set } else { clear });
+
+        // Most instructions keep their options in the low bits.
+        let (name, options) = match opcode {
+            0x00..=0x05               => (NAMES[opcode as usize], flag("x", "y")),
+            0x06..=0x09 | 0x86 | 0x87 => (NAMES[opcode as usize], flag("perp", "par")),
+            0x2E | 0x2F | 0x3E | 0x3F => (NAMES[opcode as usize], flag("rnd", "")),
+            0x30 | 0x31               => (NAMES[opcode as usize], flag("x", "y")),
+            0x32..=0x37               => (NAMES[opcode as usize], flag("rp1", "rp2")),
+            0x3A | 0x3B               => (NAMES[opcode as usize], flag("rp0", "")),
+            0x46 | 0x47               => (NAMES[opcode as usize], flag("orig", "cur")),
+            0x49 | 0x4A               => (NAMES[opcode as usize], flag("cur", "orig")),
+            0x68..=0x6F               => (NAMES[opcode as usize], String::from(DISTANCES[(opcode & 3) as usize])),
+            0xB0..=0xB7               => ("PUSHB", format!("{}", opcode - 0xB0 + 1)),
+            0xB8..=0xBF               => ("PUSHW", format!("{}", opcode - 0xB8 + 1)),
+            0xC0..=0xFF               => {
+                let name  = if opcode < 0xE0 { "MDRP" } else { "MIRP" };
+                let flags = [(0x10, "rp0"), (0x08, "min"), (0x04, "rnd")].iter()
+                    .filter(|(mask, _)|opcode & mask != 0)
+                    .map(|(_, x)|*x)
+                    .chain([DISTANCES[(opcode & 3) as usize]])
+                    .collect::<Vec<&str>>()
+                    .join(",");
+                (name, flags)
+            },
+            0x91                      => return String::from("GETVARIATION"),
+            0x92                      => return String::from("GETDATA"),
+            _ if opcode < 0x90        => return String::from(NAMES[opcode as usize]),
+            _                         => return format!("INS_{:02X}", opcode)
+        };
+
+        format!("{}[{}]", name, options)
+    }
+
+    //*************************************************************************
+    pub fn opens(&self) -> bool {
+        matches!(self.opcode, 0x58 | 0x1B | 0x2C | 0x89)
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name())?;
+
+        for value in self.data.iter() {
+            write!(f, " {}", value)?;
+        }
+
+        Ok(())
+    }
+}
+
+//*****************************************************************************
+pub fn decode(
+            code:   &[u8],
+            offset: usize
+        ) -> Result<(Operation, usize), String> {
+    let opcode       = read_u8(code, offset)?;
+    let mut position = offset + 1;
+
+    // The push instructions carry their values in the instruction stream.
+    let (count, words) = match opcode {
+        0x40 | 0x41 => {
+            let count = read_u8(code, position)? as usize;
+            position += 1;
+            (count, opcode == 0x41)
+        },
+        0xB0..=0xB7 => ((opcode - 0xB0 + 1) as usize, false),
+        0xB8..=0xBF => ((opcode - 0xB8 + 1) as usize, true),
+        _           => (0, false)
+    };
+
+    let mut data = Vec::with_capacity(count);
+    for _ in 0..count {
+        if words {
+            data.push(read_u16(code, position)? as i16 as i32);
+            position += 2;
+        } else {
+            data.push(read_u8(code, position)? as i32);
+            position += 1;
+        }
+    }
+
+    Ok((Operation { offset, opcode, data, depth: 0 }, position))
+}
+
+//*****************************************************************************
+pub fn disassemble(
+            code: &[u8]
+        ) -> Result<Vec<Operation>, String> {
+    let mut result   = Vec::new();
+    let mut position = 0;
+    let mut depth    = 0usize;
+
+    while position < code.len() {
+        let (mut operation, next) = decode(code, position)?;
+        position = next;
+
+        // Blocks close before their last instruction, ELSE sits level with
+        // its IF.
+        if matches!(operation.opcode, 0x59 | 0x2D | 0x1B) {
+            depth = depth.saturating_sub(1);
+        }
+
+        operation.depth = depth;
+        if operation.opens() {
+            depth += 1;
+        }
+        result.push(operation);
+    }
+
+    Ok(result)
+}
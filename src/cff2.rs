@@ -0,0 +1,486 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use font::opentype::postscript::type2::Operator;
+
+use crate::{charstrings::{Disassembler, Instruction}, sfnt::{read_u16, read_u32, read_u8}, variations::Store};
+
+//*****************************************************************************
+const CHAR_STRINGS: u16 = 17;
+const PRIVATE:      u16 = 18;
+const SUBRS:        u16 = 19;
+const VS_INDEX:     u16 = 22;
+const VSTORE:       u16 = 24;
+const FD_ARRAY:     u16 = 1236;
+const FD_SELECT:    u16 = 1237;
+
+// Deeper nesting than this is taken to be a loop.
+pub const MAX_CALLS: usize = 10;
+
+//*****************************************************************************
+fn read_index(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<Vec<Vec<u8>>, String> {
+    let count = read_u32(data, offset)? as usize;
+
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let size   = read_u8(data, offset + 4)? as usize;
+    let base   = offset + 5 + (count + 1) * size - 1;
+    let offset = |i: usize| -> Result<usize, String> {
+        let mut value = 0;
+
+        for k in 0..size {
+            value = (value << 8) | read_u8(data, offset + 5 + i * size + k)? as usize;
+        }
+
+        Ok(base + value)
+    };
+
+    (0..count)
+        .map(|i|{
+            let (start, end) = (offset(i)?, offset(i + 1)?);
+
+            data.get(start..end)
+                .map(|x|x.to_vec())
+                .ok_or_else(||format!("INDEX entry {} runs past the end of the table.", i))
+        })
+        .collect()
+}
+
+//*****************************************************************************
+fn read_dict(
+            data: &[u8]
+        ) -> Result<Vec<(u16, Vec<f32>)>, String> {
+    let mut result   = Vec::new();
+    let mut operands = Vec::new();
+    let mut position = 0;
+
+    while position < data.len() {
+        let b0 = data[position] as i32;
+
+        match b0 {
+            28 => {
+                operands.push(read_u16(data, position + 1)? as i16 as f32);
+                position += 3;
+            },
+            29 => {
+                operands.push(read_u32(data, position + 1)? as i32 as f32);
+                position += 5;
+            },
+            30 => {
+                let mut text = String::new();
+
+                // Nibbles spell out the number, 0xF ends it.
+                'real: loop {
+                    position += 1;
+                    let byte = read_u8(data, position)?;
+
+                    for nibble in [byte >> 4, byte & 0x0F] {
+                        match nibble {
+                            0..=9 => text.push((b'0' + nibble) as char),
+                            0x0A  => text.push('.'),
+                            0x0B  => text.push('E'),
+                            0x0C  => text.push_str("E-"),
+                            0x0E  => text.push('-'),
+                            0x0F  => break 'real,
+                            _     => return Err(String::from("Found a reserved nibble in a real number."))
+                        }
+                    }
+                }
+                position += 1;
+                operands.push(text.parse().map_err(|_|format!("Found a malformed real number {}.", text))?);
+            },
+            32..=246 => {
+                operands.push((b0 - 139) as f32);
+                position += 1;
+            },
+            247..=250 => {
+                operands.push(((b0 - 247) * 256 + read_u8(data, position + 1)? as i32 + 108) as f32);
+                position += 2;
+            },
+            251..=254 => {
+                operands.push((-(b0 - 251) * 256 - read_u8(data, position + 1)? as i32 - 108) as f32);
+                position += 2;
+            },
+            12 => {
+                result.push((1200 + read_u8(data, position + 1)? as u16, std::mem::take(&mut operands)));
+                position += 2;
+            },
+            0..=27 => {
+                result.push((b0 as u16, std::mem::take(&mut operands)));
+                position += 1;
+            },
+            _ => return Err(format!("Found a reserved DICT byte {}.", b0))
+        }
+    }
+
+    Ok(result)
+}
+
+//*****************************************************************************
+fn find(
+            dict:     &[(u16, Vec<f32>)],
+            operator: u16
+        ) -> Option<&[f32]> {
+    dict.iter()
+        .find(|x|x.0 == operator)
+        .map(|x|x.1.as_slice())
+}
+
+//*****************************************************************************
+pub fn bias(
+            count: usize
+        ) -> i32 {
+    if count < 1240 {
+        107
+    } else if count < 33900 {
+        1131
+    } else {
+        32768
+    }
+}
+
+//*****************************************************************************
+struct FontDict {
+    subroutines: Vec<Vec<u8>>,
+    vs_index:    usize
+}
+
+//*****************************************************************************
+pub struct Cff2 {
+    global:       Vec<Vec<u8>>,
+    char_strings: Vec<Vec<u8>>,
+    fonts:        Vec<FontDict>,
+    select:       Vec<usize>,
+    store:        Option<Store>
+}
+
+impl Cff2 {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let header_size = read_u8(data, 2)? as usize;
+        let top_length  = read_u16(data, 3)? as usize;
+        let top         = read_dict(
+            data.get(header_size..header_size + top_length).ok_or("Truncated top DICT.")?
+        )?;
+        let global      = read_index(data, header_size + top_length)?;
+
+        let offset = |operator: u16| find(&top, operator)
+            .and_then(|x|x.first())
+            .map(|&x|x as usize);
+
+        let char_strings = read_index(data, offset(CHAR_STRINGS).ok_or("Missing CharStrings.")?)?;
+
+        let mut fonts = Vec::new();
+
+        for font in read_index(data, offset(FD_ARRAY).ok_or("Missing FDArray.")?)?.iter() {
+            let font = read_dict(font)?;
+
+            let Some(&[size, start]) = find(&font, PRIVATE) else {
+                fonts.push(FontDict { subroutines: Vec::new(), vs_index: 0 });
+                continue;
+            };
+            let (size, start) = (size as usize, start as usize);
+            let private       = read_dict(data.get(start..start + size).ok_or("Truncated Private DICT.")?)?;
+
+            // Local subroutines are found relative to the Private DICT.
+            let subroutines = match find(&private, SUBRS).and_then(|x|x.first()) {
+                Some(&x) => read_index(data, start + x as usize)?,
+                None     => Vec::new()
+            };
+            let vs_index = find(&private, VS_INDEX)
+                .and_then(|x|x.first())
+                .map(|&x|x as usize)
+                .unwrap_or(0);
+
+            fonts.push(FontDict { subroutines, vs_index });
+        }
+
+        let select = match offset(FD_SELECT) {
+            Some(x) => Cff2::read_select(data, x, char_strings.len())?,
+            None    => Vec::new()
+        };
+        let store = match offset(VSTORE) {
+            Some(x) => Some(Store::read(data, x + 2)?),
+            None    => None
+        };
+
+        Ok(Self { global, char_strings, fonts, select, store })
+    }
+
+    //*************************************************************************
+    fn read_select(
+                data:   &[u8],
+                offset: usize,
+                count:  usize
+            ) -> Result<Vec<usize>, String> {
+        let mut result = vec![0; count];
+
+        match read_u8(data, offset)? {
+            0 => {
+                for (i, x) in result.iter_mut().enumerate() {
+                    *x = read_u8(data, offset + 1 + i)? as usize;
+                }
+            },
+            format @ (3 | 4) => {
+                // Ranges run from their first glyph to the next range's.
+                let (ranges, start, size, glyph, font) = if format == 3 {
+                    (read_u16(data, offset + 1)? as usize, offset + 3, 3, 2, 1)
+                } else {
+                    (read_u32(data, offset + 1)? as usize, offset + 5, 6, 4, 2)
+                };
+                let read = |position: usize, width: usize| -> Result<usize, String> {
+                    match width {
+                        1 => read_u8(data, position).map(|x|x as usize),
+                        2 => read_u16(data, position).map(|x|x as usize),
+                        _ => read_u32(data, position).map(|x|x as usize)
+                    }
+                };
+
+                for i in 0..ranges {
+                    let position = start + i * size;
+                    let first    = read(position, glyph)?;
+                    let next     = read(position + size, glyph)?;
+                    let font     = read(position + glyph, font)?;
+
+                    for x in result.iter_mut().take(next).skip(first) {
+                        *x = font;
+                    }
+                }
+            },
+            format => return Err(format!("Unknown FDSelect format {}.", format))
+        }
+
+        Ok(result)
+    }
+
+    //*************************************************************************
+    pub fn program<'l>(
+                &'l self,
+                id:          u16,
+                coordinates: &'l [f32]
+            ) -> Result<Program<'l>, String> {
+        let code = self.char_strings.get(id as usize).ok_or("Glyph ID out of range.")?;
+        let font = self.fonts.get(self.select.get(id as usize).copied().unwrap_or(0))
+            .ok_or("Glyph refers to a missing Font DICT.")?;
+
+        let mut program = Program {
+            cff2:     self,
+            local:    &font.subroutines,
+            routines: vec![(code, 0)],
+            stack:    Vec::new(),
+            scalars:  Vec::new(),
+            stems:    0,
+            coordinates
+        };
+        program.set_vs_index(font.vs_index);
+
+        Ok(program)
+    }
+
+    //*************************************************************************
+    pub fn disassemble(
+                &self,
+                id: u16
+            ) -> Result<Vec<Instruction>, String> {
+        let code = self.char_strings.get(id as usize).ok_or("Glyph ID out of range.")?;
+        let font = self.fonts.get(self.select.get(id as usize).copied().unwrap_or(0))
+            .ok_or("Glyph refers to a missing Font DICT.")?;
+
+        // The number of regions for each vsindex, to know how many deltas
+        // a blend takes.
+        let regions = match &self.store {
+            Some(store) => (0..).map_while(|x|store.scalars(x, &[]).map(|x|x.len())).collect(),
+            None        => Vec::new()
+        };
+
+        Disassembler::new(&self.global, &font.subroutines)
+            .with_regions(regions, font.vs_index)
+            .run(code)
+    }
+}
+
+//*****************************************************************************
+pub struct Program<'l> {
+    cff2:        &'l Cff2,
+    local:       &'l [Vec<u8>],
+    routines:    Vec<(&'l [u8], usize)>,
+    stack:       Vec<f32>,
+    scalars:     Vec<f32>,
+    stems:       usize,
+    coordinates: &'l [f32]
+}
+
+impl Program<'_> {
+    //*************************************************************************
+    fn set_vs_index(
+                &mut self,
+                vs_index: usize
+            ) {
+        self.scalars = self.cff2.store.as_ref()
+            .and_then(|x|x.scalars(vs_index, self.coordinates))
+            .unwrap_or_default();
+    }
+
+    //*************************************************************************
+    fn pop(&mut self) -> Result<f32, String> {
+        self.stack.pop().ok_or_else(||String::from("Expected an operand."))
+    }
+
+    //*************************************************************************
+    fn blend(&mut self) -> Result<(), String> {
+        let count  = self.pop()? as usize;
+        let deltas = self.scalars.len();
+        let needed = count * (deltas + 1);
+
+        if self.stack.len() < needed {
+            return Err(String::from("Expected more operands to blend."));
+        }
+
+        // The default values come first, then each value's deltas in turn.
+        let base = self.stack.len() - needed;
+
+        for i in 0..count {
+            let start = base + count + i * deltas;
+            let delta = self.stack[start..start + deltas].iter()
+                .zip(self.scalars.iter())
+                .map(|(&delta, &scalar)|delta * scalar)
+                .sum::<f32>();
+
+            self.stack[base + i] += delta;
+        }
+        self.stack.truncate(base + count);
+
+        Ok(())
+    }
+
+    //*************************************************************************
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<Option<(Operator, Vec<f32>)>, String> {
+        loop {
+            let Some(&(code, position)) = self.routines.last() else {
+                return Ok(None);
+            };
+
+            // Running off the end of a subroutine returns from it.
+            if position >= code.len() {
+                self.routines.pop();
+                continue;
+            }
+
+            let b0     = code[position] as i32;
+            let length = match b0 {
+                28        => 3,
+                32..=246  => 1,
+                247..=254 => 2,
+                255       => 5,
+                12        => 2,
+                _         => 1
+            };
+            if let Some(x) = self.routines.last_mut() {
+                x.1 += length;
+            }
+
+            match b0 {
+                28 => {
+                    self.stack.push(read_u16(code, position + 1)? as i16 as f32);
+                    continue;
+                },
+                32..=246 => {
+                    self.stack.push((b0 - 139) as f32);
+                    continue;
+                },
+                247..=250 => {
+                    self.stack.push(((b0 - 247) * 256 + read_u8(code, position + 1)? as i32 + 108) as f32);
+                    continue;
+                },
+                251..=254 => {
+                    self.stack.push((-(b0 - 251) * 256 - read_u8(code, position + 1)? as i32 - 108) as f32);
+                    continue;
+                },
+                255 => {
+                    self.stack.push(read_u32(code, position + 1)? as i32 as f32 / 65536.0);
+                    continue;
+                },
+                _ => {}
+            }
+
+            let operator = if b0 == 12 {
+                0x0C00 | read_u8(code, position + 1)? as u16
+            } else {
+                b0 as u16
+            };
+
+            match operator {
+                10 | 29 => {
+                    let subroutines = if operator == 10 { self.local } else { &self.cff2.global };
+                    let index       = self.pop()? as i32 + bias(subroutines.len());
+                    let routine     = usize::try_from(index).ok()
+                        .and_then(|x|subroutines.get(x))
+                        .ok_or_else(||format!("Found no subroutine {}.", index))?;
+
+                    if self.routines.len() > MAX_CALLS {
+                        return Err(String::from("Subroutines are nested too deeply."));
+                    }
+                    self.routines.push((routine, 0));
+                },
+                11 => {
+                    self.routines.pop();
+                },
+                14 => {
+                    self.routines.clear();
+                    return Ok(None);
+                },
+                15 => {
+                    let vs_index = self.pop()? as usize;
+                    self.set_vs_index(vs_index);
+                },
+                16 => {
+                    self.blend()?;
+                },
+                _ => {
+                    let operator = Operator::from(operator).map_err(|x|format!("{:?}", x))?;
+
+                    // Stems are counted to know how many bytes a mask takes,
+                    // a mask can also follow vertical stems left on the stack.
+                    if matches!(operator,
+                            Operator::HStem | Operator::VStem | Operator::HStemHM |
+                            Operator::VStemHM | Operator::HintMask | Operator::CntrMask) {
+                        self.stems += self.stack.len() / 2;
+                    }
+                    if matches!(operator, Operator::HintMask | Operator::CntrMask) {
+                        if let Some(x) = self.routines.last_mut() {
+                            x.1 += self.stems.div_ceil(8);
+                        }
+                    }
+
+                    return Ok(Some((operator, std::mem::take(&mut self.stack))));
+                }
+            }
+        }
+    }
+}
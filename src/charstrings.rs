@@ -0,0 +1,326 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::{
+    cff2::{bias, MAX_CALLS},
+    sfnt::{read_u16, read_u32, read_u8}
+};
+
+//*****************************************************************************
+const OPERATORS: [&str; 32] = [
+    "reserved0", "hstem", "reserved2", "vstem", "vmoveto", "rlineto", "hlineto", "vlineto",
+    "rrcurveto", "reserved9", "callsubr", "return", "escape", "reserved13", "endchar", "vsindex",
+    "blend", "reserved17", "hstemhm", "hintmask", "cntrmask", "rmoveto", "hmoveto", "vstemhm",
+    "rcurveline", "rlinecurve", "vvcurveto", "hhcurveto", "shortint", "callgsubr", "vhcurveto", "hvcurveto"
+];
+
+//*****************************************************************************
+const ESCAPED: [&str; 38] = [
+    "dotsection", "reserved1", "reserved2", "and", "or", "not", "reserved6", "reserved7",
+    "reserved8", "abs", "add", "sub", "div", "reserved13", "neg", "eq", "reserved16",
+    "reserved17", "drop", "reserved19", "put", "get", "ifelse", "random", "mul", "reserved25",
+    "sqrt", "dup", "exch", "index", "roll", "reserved31", "reserved32", "reserved33", "hflex",
+    "flex", "hflex1", "flex1"
+];
+
+//*****************************************************************************
+// One operator with the operands it took, and the segments of the outline
+// it drew as (contour, segment) pairs.
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub depth:    usize,
+    pub offset:   usize,
+    pub text:     String,
+    pub segments: Vec<(usize, usize)>
+}
+
+//*****************************************************************************
+pub struct Disassembler<'l> {
+    global:   &'l [Vec<u8>],
+    local:    &'l [Vec<u8>],
+    cff2:     bool,
+    regions:  Vec<usize>,
+    vs_index: usize,
+    stack:    Vec<f32>,
+    stems:    usize,
+    measured: bool,
+    contour:  usize,
+    segment:  usize,
+    result:   Vec<Instruction>
+}
+
+impl<'l> Disassembler<'l> {
+    //*************************************************************************
+    pub fn new(
+                global: &'l [Vec<u8>],
+                local:  &'l [Vec<u8>]
+            ) -> Self {
+        Self {
+            global,
+            local,
+            cff2:     false,
+            regions:  Vec::new(),
+            vs_index: 0,
+            stack:    Vec::new(),
+            stems:    0,
+            measured: false,
+            contour:  0,
+            segment:  0,
+            result:   Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    // CFF2 charstrings have no width and blend with as many deltas as the
+    // item variation data has regions.
+    pub fn with_regions(
+                mut self,
+                regions:  Vec<usize>,
+                vs_index: usize
+            ) -> Self {
+        self.cff2     = true;
+        self.measured = true;
+        self.regions  = regions;
+        self.vs_index = vs_index;
+        self
+    }
+
+    //*************************************************************************
+    pub fn run(
+                mut self,
+                code: &[u8]
+            ) -> Result<Vec<Instruction>, String> {
+        self.routine(code, 0)?;
+
+        Ok(self.result)
+    }
+
+    //*************************************************************************
+    fn push(
+                &mut self,
+                depth:    usize,
+                offset:   usize,
+                text:     String,
+                segments: Vec<(usize, usize)>
+            ) {
+        self.result.push(Instruction { depth, offset, text, segments });
+    }
+
+    //*************************************************************************
+    fn operands(
+                values: &[f32]
+            ) -> String {
+        values.iter().map(|x|format!("{}", x)).collect::<Vec<String>>().join(" ")
+    }
+
+    //*************************************************************************
+    fn draw(
+                &mut self,
+                operator: u16
+            ) -> Vec<(usize, usize)> {
+        let count = self.stack.len();
+
+        // The same number of segments the outline builder makes for each
+        // operator.
+        let segments = match operator {
+            5                   => count / 2,
+            6 | 7               => count,
+            8                   => count / 6,
+            26 | 27             => count / 4,
+            30 | 31             => count / 4,
+            24 if count >= 2    => (count - 2) / 6 + 1,
+            25 if count >= 6    => (count - 6) / 2 + 1,
+            _                   => 0
+        };
+
+        let result = (self.segment..self.segment + segments).map(|x|(self.contour, x)).collect();
+        self.segment += segments;
+
+        result
+    }
+
+    //*************************************************************************
+    fn take_width(
+                &mut self,
+                operator: u16
+            ) -> Option<f32> {
+        // In CFF the first stack clearing operator can carry the advance
+        // width before its own operands.
+        if self.measured {
+            return None;
+        }
+        let expected = match operator {
+            1 | 3 | 18 | 23 | 19 | 20 => self.stack.len() % 2,
+            21                        => (self.stack.len() == 3) as usize,
+            4 | 22                    => (self.stack.len() == 2) as usize,
+            14                        => (self.stack.len() == 1 || self.stack.len() == 5) as usize,
+            _                         => return None
+        };
+        self.measured = true;
+
+        if expected == 1 && !self.stack.is_empty() {
+            Some(self.stack.remove(0))
+        } else {
+            None
+        }
+    }
+
+    //*************************************************************************
+    fn routine(
+                &mut self,
+                code:  &[u8],
+                depth: usize
+            ) -> Result<bool, String> {
+        let mut position = 0;
+
+        while position < code.len() {
+            let offset = position;
+            let b0     = code[position] as i32;
+
+            match b0 {
+                28 => {
+                    self.stack.push(read_u16(code, position + 1)? as i16 as f32);
+                    position += 3;
+                    continue;
+                },
+                32..=246 => {
+                    self.stack.push((b0 - 139) as f32);
+                    position += 1;
+                    continue;
+                },
+                247..=250 => {
+                    self.stack.push(((b0 - 247) * 256 + read_u8(code, position + 1)? as i32 + 108) as f32);
+                    position += 2;
+                    continue;
+                },
+                251..=254 => {
+                    self.stack.push((-(b0 - 251) * 256 - read_u8(code, position + 1)? as i32 - 108) as f32);
+                    position += 2;
+                    continue;
+                },
+                255 => {
+                    self.stack.push(read_u32(code, position + 1)? as i32 as f32 / 65536.0);
+                    position += 5;
+                    continue;
+                },
+                _ => {}
+            }
+
+            let (operator, name) = if b0 == 12 {
+                let b1 = read_u8(code, position + 1)?;
+                position += 2;
+                (0x0C00 | b1 as u16, ESCAPED.get(b1 as usize).copied().unwrap_or("reserved"))
+            } else {
+                position += 1;
+                (b0 as u16, OPERATORS[b0 as usize])
+            };
+
+            let width = self.take_width(operator)
+                .map(|x|format!(" (width {})", x))
+                .unwrap_or_default();
+
+            match operator {
+                10 | 29 => {
+                    let subroutines = if operator == 10 { self.local } else { self.global };
+                    let value       = self.stack.pop().ok_or("Expected a subroutine number.")?;
+                    let index       = value as i32 + bias(subroutines.len());
+                    let routine     = usize::try_from(index).ok()
+                        .and_then(|x|subroutines.get(x))
+                        .ok_or_else(||format!("Found no subroutine {}.", index))?;
+
+                    if depth >= MAX_CALLS {
+                        return Err(String::from("Subroutines are nested too deeply."));
+                    }
+                    self.push(depth, offset, format!("{} {} (#{})", name, value, index), Vec::new());
+
+                    if self.routine(routine, depth + 1)? {
+                        return Ok(true);
+                    }
+                },
+                11 => {
+                    self.push(depth, offset, String::from(name), Vec::new());
+                    return Ok(false);
+                },
+                14 => {
+                    let text = format!("{} {}{}", name, Disassembler::operands(&self.stack), width);
+                    self.push(depth, offset, text.trim_end().to_string(), Vec::new());
+                    return Ok(true);
+                },
+                15 if self.cff2 => {
+                    let text = format!("{} {}", name, Disassembler::operands(&self.stack));
+
+                    self.vs_index = self.stack.pop().ok_or("Expected a variation store index.")? as usize;
+                    self.push(depth, offset, text, Vec::new());
+                    self.stack.clear();
+                },
+                16 if self.cff2 => {
+                    // The default values stay on the stack for the next
+                    // operator, the deltas are dropped.
+                    let text   = format!("{} {}", name, Disassembler::operands(&self.stack));
+                    let count  = self.stack.pop().ok_or("Expected a blend count.")? as usize;
+                    let deltas = self.regions.get(self.vs_index).copied().unwrap_or(0);
+                    let base   = self.stack.len()
+                        .checked_sub(count * (deltas + 1))
+                        .ok_or("Expected more operands to blend.")?;
+
+                    self.stack.truncate(base + count);
+                    self.push(depth, offset, text, Vec::new());
+                },
+                19 | 20 => {
+                    // Values left on the stack are vertical stems.
+                    self.stems += self.stack.len() / 2;
+
+                    let size = self.stems.div_ceil(8);
+                    let mask = code.get(position..position + size)
+                        .ok_or("Hint mask runs past the end of the charstring.")?
+                        .iter()
+                        .map(|x|format!("{:08b}", x))
+                        .collect::<Vec<String>>()
+                        .join(" ");
+                    position += size;
+
+                    let text = format!("{} {} {}{}", name, Disassembler::operands(&self.stack), mask, width);
+                    self.push(depth, offset, text.replace("  ", " "), Vec::new());
+                    self.stack.clear();
+                },
+                _ => {
+                    if matches!(operator, 1 | 3 | 18 | 23) {
+                        self.stems += self.stack.len() / 2;
+                    }
+                    // A move starts a new contour once the last one drew
+                    // something.
+                    if matches!(operator, 4 | 21 | 22) && self.segment > 0 {
+                        self.contour += 1;
+                        self.segment  = 0;
+                    }
+
+                    let segments = self.draw(operator);
+                    let text     = format!("{} {}{}", name, Disassembler::operands(&self.stack), width);
+
+                    self.push(depth, offset, text.trim_end().to_string(), segments);
+                    self.stack.clear();
+                }
+            }
+        }
+
+        Ok(false)
+    }
+}
@@ -0,0 +1,178 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::slice::Iter;
+
+use iced::Element;
+use iced::widget::{
+    Column, Space, button, checkbox, column, container, horizontal_space, row,
+    text
+};
+
+use super::{Def, Line, Message};
+
+//*****************************************************************************
+pub struct Contour {
+    draw:     bool,
+    closed:   bool,
+    expanded: bool,
+    segments: Vec<Line>
+}
+
+impl Contour {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            draw:     true,
+            closed:   false,
+            expanded: false,
+            segments: Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    pub fn iter(&self) -> Iter<'_, Line> {
+        self.segments.iter()
+    }
+
+    //*************************************************************************
+    pub fn is_enabled(&self) -> bool {
+        self.draw
+    }
+
+    //*************************************************************************
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    //*************************************************************************
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    //*************************************************************************
+    pub fn first(&self) -> Option<&Def> {
+        self.segments.first().map(|x|x.def())
+    }
+
+    //*************************************************************************
+    pub fn last(&self) -> Option<&Def> {
+        self.segments.last().map(|x|x.def())
+    }
+
+    //*************************************************************************
+    pub fn push(
+                &mut self,
+                def: Def
+            ) {
+        self.segments.push(Line::new(def));
+    }
+
+    //*************************************************************************
+    pub fn close(&mut self) {
+        self.closed = match (self.first(), self.last()) {
+            (Some(first), Some(last)) => first.start().touches(last.end()),
+            _                         => false
+        };
+    }
+
+    //*************************************************************************
+    pub fn view(
+                &self,
+                idx: usize
+            ) -> Element<'_, Message> {
+        let (arrow, shape) = (
+            if self.expanded { "-" } else { "+" },
+            if self.closed { "closed" } else { "open" }
+        );
+
+        let header = container(
+            row![
+                button(arrow)
+                    .style(button::secondary)
+                    .on_press(Message::ContourExpand(idx, !self.expanded)),
+                checkbox("", self.draw).on_toggle(move |x|Message::ContourToggle(idx, x)),
+                text(format!("Contour {} - {} segments, {}", idx, self.segments.len(), shape)),
+                horizontal_space()
+            ].padding(4)
+             .spacing(8)
+        ).style(container::bordered_box);
+
+        let mut result = column![
+            Space::new(0, 8),
+            header
+        ];
+
+        if self.expanded {
+            result = result.push(
+                Column::with_children(
+                    self.segments.iter().enumerate().map(|(i, x)|x.view((idx, i)))
+                ).padding([0, 24])
+            );
+        }
+
+        result.into()
+    }
+
+    //*************************************************************************
+    pub fn handle_toggle(
+                &mut self,
+                enable: bool
+            ) {
+        self.draw = enable;
+    }
+
+    //*************************************************************************
+    pub fn handle_expand(
+                &mut self,
+                expand: bool
+            ) {
+        self.expanded = expand;
+    }
+
+    //*************************************************************************
+    pub fn handle_line_change(
+                &mut self,
+                idx: usize,
+                def: Def
+            ) {
+        self.segments[idx].change(def);
+        self.close();
+    }
+
+    //*************************************************************************
+    pub fn handle_line_toggle(
+                &mut self,
+                idx:    usize,
+                enable: bool
+            ) {
+        self.segments[idx].enable(enable);
+    }
+
+    //*************************************************************************
+    pub fn handle_line_remove(
+                &mut self,
+                idx: usize
+            ) {
+        self.segments.remove(idx);
+        self.close();
+    }
+}
@@ -64,7 +64,7 @@ pub struct Define {
     p3:          Value,
     formula:     Content,
     error:       bool,
-    edit:        Option<usize>
+    edit:        Option<(usize, usize)>
 }
 
 impl Default for Define {
@@ -222,7 +222,7 @@ impl Define {
     pub fn handle_edit(
                 &mut self,
                 popin:   Popin,
-                idx:     usize,
+                idx:     (usize, usize),
                 color:   Color,
                 formula: String
             ) {
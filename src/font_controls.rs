@@ -19,20 +19,45 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
+use std::{
+    fmt::Display,
+    fs
+};
+
 use iced::{
     widget::{
-        button, column, container, horizontal_space, row,
+        button, column, container, horizontal_space, pick_list, row,
         text, text_input, vertical_space
     },
     Element
 };
 
-use super::{Glyphs, Lines, Message};
+use super::{sfnt, Browse, GlyphInfo, Glyphs, Guides, Lines, Message, Metadata, PairInfo, Substitutions, Variations};
+
+//*****************************************************************************
+const DEFAULT_UNITS_PER_EM: f32 = 1000.0;
+
+//*****************************************************************************
+#[derive(Debug, Clone, PartialEq)]
+pub struct Face {
+    index: usize,
+    name:  String
+}
+
+impl Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.index, self.name)
+    }
+}
 
 //*****************************************************************************
 pub  struct FontControls {
     filename:   String,
     filter:     String,
+    faces:      Vec<Vec<u8>>,
+    face_names: Vec<Face>,
+    face:       Option<Face>,
+    browse:     Browse,
     glyphs:     Option<Glyphs>
 }
 
@@ -41,8 +66,12 @@ impl FontControls {
     pub fn new() -> Self {
         Self {
             filename: String::from("fonts/NotoSansMono-Regular.ttf"),
-            filter:   String::new(),
-            glyphs:   None
+            filter:     String::new(),
+            faces:      Vec::new(),
+            face_names: Vec::new(),
+            face:       None,
+            browse:     Browse::Characters,
+            glyphs:     None
         }
     }
 
@@ -64,26 +93,78 @@ impl FontControls {
             ).into()
         };
 
-        column![
+        let mut result = column![
             row![
                 text_input("", &self.filename)
                     .on_input(Message::FontFilename),
                 button("Load")
                     .style(button::danger)
                     .on_press(Message::FontLoad(self.filename.clone()))
-            ].spacing(8),
-            msg,
+            ].spacing(8)
+        ];
+
+        // Only collections have a choice of face.
+        if self.face_names.len() > 1 {
+            result = result.push(
+                pick_list(self.face_names.as_slice(), self.face.clone(), Message::FontFace)
+                    .width(320 - 8)
+            );
+        }
+
+        result.push(
+            row![
+                text("Browse:"),
+                pick_list(Browse::ALL, Some(self.browse), Message::FontBrowse)
+            ].spacing(8)
+        ).push(msg).push(
             row![
                 text("Filter:"),
                 text_input("", &self.filter)
                     .on_input(Message::FontFilter)
-            ].spacing(8),
-        ].spacing(4)
+            ].spacing(8)
+        ).spacing(4)
          .padding(4)
          .width(320)
          .into()
     }
 
+    //**************************************************************************
+    pub fn units_per_em(&self) -> f32 {
+        self.glyphs.as_ref()
+            .and_then(|x|x.units_per_em())
+            .unwrap_or(DEFAULT_UNITS_PER_EM)
+    }
+
+    //**************************************************************************
+    pub fn variations(&self) -> Option<&Variations> {
+        self.glyphs.as_ref().map(|x|x.variations())
+    }
+
+    //**************************************************************************
+    pub fn data(&self) -> Option<&[u8]> {
+        self.face.as_ref()
+            .and_then(|x|self.faces.get(x.index))
+            .map(|x|x.as_slice())
+    }
+
+    //**************************************************************************
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.glyphs.as_ref().and_then(|x|x.metadata())
+    }
+
+    //**************************************************************************
+    pub fn substitutions(&self) -> Option<&Substitutions> {
+        self.glyphs.as_ref().map(|x|x.substitutions())
+    }
+
+    //**************************************************************************
+    pub fn find_glyph(
+                &self,
+                input: &str
+            ) -> Option<u16> {
+        self.glyphs.as_ref().and_then(|x|x.find_glyph(input))
+    }
+
     //**************************************************************************
     pub fn handle_font_filename(
                 &mut self,
@@ -104,18 +185,65 @@ impl FontControls {
         }
     }
 
+    //**************************************************************************
+    pub fn handle_font_browse(
+                &mut self,
+                browse: Browse
+            ) {
+        self.browse = browse;
+
+        if let Some(glyphs) = self.glyphs.as_mut() {
+            glyphs.handle_browse(browse, &self.filter);
+        }
+    }
+
     //**************************************************************************
     pub fn handle_font_load(
                 &mut self,
                 filename: String
             ) {
+        self.filter     = String::new();
+        self.faces      = Vec::new();
+        self.face_names = Vec::new();
+        self.face       = None;
+        self.glyphs     = None;
+
+        let faces = fs::read(&filename)
+            .map_err(|x|format!("{:?}", x))
+            .and_then(|x|sfnt::faces(&x));
+
+        match faces {
+            Ok(faces) => {
+                self.face_names = faces.iter()
+                    .enumerate()
+                    .map(|(index, x)|Face { index, name: Glyphs::face_name(x) })
+                    .collect();
+                self.faces = faces;
+
+                if let Some(face) = self.face_names.first().cloned() {
+                    self.handle_font_face(face);
+                }
+            },
+            Err(err) => eprintln!("{:?}", err)
+        }
+    }
+
+    //**************************************************************************
+    pub fn handle_font_face(
+                &mut self,
+                face: Face
+            ) {
         self.filter = String::new();
         self.glyphs = None;
 
-        match Glyphs::new(&filename) {
-            Ok(glyphs) => self.glyphs = Some(glyphs),
-            Err(err)   => eprintln!("{:?}", err)
+        match Glyphs::new(self.faces[face.index].clone()) {
+            Ok(mut glyphs) => {
+                glyphs.handle_browse(self.browse, &self.filter);
+                self.glyphs = Some(glyphs);
+            },
+            Err(err) => eprintln!("{:?}", err)
         }
+        self.face = Some(face);
     }
 
     //**************************************************************************
@@ -123,12 +251,139 @@ impl FontControls {
                 &mut self,
                 glyph: char,
                 lines: &mut Lines
+            ) -> Option<GlyphInfo> {
+        if let Some(glyphs) = self.glyphs.as_mut() {
+            match glyphs.view_glyph(glyph, lines) {
+                Ok(info) => return info,
+                Err(_)   => eprintln!("Error decoding character: U+{:X}", glyph as u32)
+            }
+        }
+
+        None
+    }
+
+    //**************************************************************************
+    pub fn handle_font_glyph(
+                &mut self,
+                id:    u16,
+                lines: &mut Lines
+            ) -> Option<GlyphInfo> {
+        if let Some(glyphs) = self.glyphs.as_mut() {
+            match glyphs.view_glyph_id(id, lines) {
+                Ok(info) => return info,
+                Err(_)   => eprintln!("Error decoding glyph: {}", id)
+            }
+        }
+
+        None
+    }
+
+    //**************************************************************************
+    pub fn handle_font_location(
+                &mut self,
+                location: &[f32]
             ) {
         if let Some(glyphs) = self.glyphs.as_mut() {
-            if let Err(_) = glyphs.view_glyph(glyph, lines) {
-                eprintln!("Error decoding character: U+{:X}", glyph as u32);
+            glyphs.handle_location(location);
+        }
+    }
+
+    //**************************************************************************
+    pub fn handle_font_hinting(
+                &mut self,
+                hinting: Option<(i32, Option<usize>)>
+            ) {
+        if let Some(glyphs) = self.glyphs.as_mut() {
+            glyphs.handle_hinting(hinting);
+        }
+    }
+
+    //**************************************************************************
+    pub fn handle_font_redraw(
+                &mut self,
+                info:  Option<&GlyphInfo>,
+                lines: &mut Lines
+            ) -> Option<GlyphInfo> {
+        let glyphs = self.glyphs.as_mut()?;
+        let info   = info?;
+
+        match glyphs.view_id(Some(info.id), info.value, lines) {
+            Ok(info) => info,
+            Err(_)   => {
+                eprintln!("Error decoding glyph: {}", info.id);
+                None
+            }
+        }
+    }
+
+    //**************************************************************************
+    pub fn handle_font_text(
+                &mut self,
+                text:  &str,
+                lines: &mut Lines
+            ) -> Option<Guides> {
+        let glyphs = self.glyphs.as_mut()?;
+
+        match glyphs.view_text(text, lines) {
+            Ok(guides) => guides,
+            Err(_)     => {
+                eprintln!("Error decoding text: {}", text);
+                None
+            }
+        }
+    }
+
+    //**************************************************************************
+    pub fn handle_font_substitute(
+                &mut self,
+                text:     &str,
+                script:   &str,
+                language: &str,
+                features: &[String],
+                lines:    &mut Lines
+            ) -> (Option<Guides>, Vec<String>) {
+        let Some(glyphs) = self.glyphs.as_mut() else {
+            return (None, Vec::new());
+        };
+
+        match glyphs.view_substituted(text, script, language, features, lines) {
+            Ok((guides, steps)) => {
+                let labels = |ids: &[u16]|ids.iter().map(|&x|glyphs.glyph_label(x)).collect::<Vec<String>>().join(" ");
+                let steps  = steps.iter()
+                    .map(|x|format!("#{} {} {}: {} -> {}", x.lookup, x.feature, x.kind, labels(&x.before), labels(&x.after)))
+                    .collect();
+                (guides, steps)
+            },
+            Err(err) => {
+                eprintln!("Error substituting text: {}: {}", text, err);
+                (None, Vec::new())
             }
         }
     }
+
+    //**************************************************************************
+    pub fn handle_font_pair(
+                &mut self,
+                info:  &GlyphInfo,
+                right: Option<u16>,
+                lines: &mut Lines
+            ) -> (Option<f32>, Vec<PairInfo>) {
+        let Some(glyphs) = self.glyphs.as_mut() else {
+            return (None, Vec::new());
+        };
+        let pairs = glyphs.kerning_pairs(info.id);
+
+        let value = right.and_then(|right|{
+            match glyphs.view_pair(info.id, info.advance, right, lines) {
+                Ok(value) => Some(value),
+                Err(_)    => {
+                    eprintln!("Error decoding glyph: {}", right);
+                    None
+                }
+            }
+        });
+
+        (value, pairs)
+    }
 }
 
@@ -0,0 +1,258 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::ops::Range;
+
+use font::opentype::truetype::tables::glyph_data::{Arguments, Options};
+use iced::{
+    widget::{
+        checkbox, column, container, horizontal_space, mouse_area, row, scrollable,
+        text, vertical_space, Column
+    },
+    Element, Font
+};
+
+use super::{Color, Component, Def, Guides, Hinted, Instruction, Message, Operation, PlotPoint, Shift};
+
+//*****************************************************************************
+const FLAGS: [(u16, &str); 12] = [
+    (0x0001, "ARG_1_AND_2_ARE_WORDS"),
+    (0x0002, "ARGS_ARE_XY_VALUES"),
+    (0x0004, "ROUND_XY_TO_GRID"),
+    (0x0008, "WE_HAVE_A_SCALE"),
+    (0x0020, "MORE_COMPONENTS"),
+    (0x0040, "WE_HAVE_AN_X_AND_Y_SCALE"),
+    (0x0080, "WE_HAVE_A_TWO_BY_TWO"),
+    (0x0100, "WE_HAVE_INSTRUCTIONS"),
+    (0x0200, "USE_MY_METRICS"),
+    (0x0400, "OVERLAP_COMPOUND"),
+    (0x0800, "SCALED_COMPONENT_OFFSET"),
+    (0x1000, "UNSCALED_COMPONENT_OFFSET")
+];
+
+//*****************************************************************************
+pub struct ComponentInfo {
+    pub component: Component,
+    pub name:      Option<String>,
+    pub color:     Color,
+    pub show:      bool
+}
+
+impl ComponentInfo {
+    //*************************************************************************
+    fn view(
+                &self,
+                idx: usize
+            ) -> Element<'_, Message> {
+        let component = &self.component;
+        let name      = self.name.as_deref().unwrap_or("-");
+
+        let placement = match component.arguments {
+            Arguments::Offsets(x, y) => format!("offset ({}, {})", x, y),
+            Arguments::Indices(a, b) => format!("points {} -> {}", a, b)
+        };
+        let transform = match component.options {
+            Options::None                   => String::new(),
+            Options::Scalar(x)              => format!(", scale {}", f32::from(x)),
+            Options::Vector(x, y)           => format!(", scale ({}, {})", f32::from(x), f32::from(y)),
+            Options::Matrix(xx, xy, yx, yy) => format!(
+                ", 2x2 [{} {}; {} {}]", f32::from(xx), f32::from(xy), f32::from(yx), f32::from(yy)
+            )
+        };
+        let flags = FLAGS.iter()
+            .filter(|(mask, _)|component.flags & mask != 0)
+            .map(|(_, name)|*name)
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        column![
+            checkbox(format!("#{} {} ({})", component.glyph_id, name, self.color), self.show)
+                .on_toggle(move |x|Message::ComponentToggle(idx, x)),
+            text(format!("{}{}", placement, transform)),
+            text(flags).size(12)
+        ].padding([0, 16 * component.depth as u16])
+         .into()
+    }
+}
+
+//*****************************************************************************
+pub struct GlyphInfo {
+    pub id:            u16,
+    pub value:         Option<char>,
+    pub name:          Option<String>,
+    pub glyph_name:    Option<String>,
+    pub advance:       f32,
+    pub side_bearings: (f32, f32),
+    pub bounding_box:  (f32, f32, f32, f32),
+    pub contours:      usize,
+    pub on_curve:      usize,
+    pub off_curve:     usize,
+    pub guides:        Option<Guides>,
+    pub components:    Vec<ComponentInfo>,
+    pub shifts:        Vec<Shift>,
+    pub charstring:    Vec<Instruction>,
+    pub instructions:  Vec<Operation>,
+    pub hover:         Option<usize>,
+    pub hinted:        Option<Hinted>,
+    pub original:      Vec<Def>,
+    pub moves:         Vec<(f32, f32)>,
+    pub numbered:      Vec<(PlotPoint, bool)>
+}
+
+impl GlyphInfo {
+    //*************************************************************************
+    fn field<'a>(
+                label: &'a str,
+                value: String
+            ) -> Element<'a, Message> {
+        row![
+            text(label).width(110),
+            text(value)
+        ].spacing(8)
+         .into()
+    }
+
+    //*************************************************************************
+    pub fn view(
+                info: Option<&GlyphInfo>
+            ) -> Element<'_, Message> {
+        let Some(info) = info else {
+            return container(
+                column![
+                    vertical_space(),
+                    row![
+                        horizontal_space(),
+                        text("No glyph selected."),
+                        horizontal_space(),
+                    ],
+                    vertical_space()
+                ]
+            ).width(300)
+             .into();
+        };
+
+        let (x_min, y_min, x_max, y_max) = info.bounding_box;
+        let value = match info.value {
+            Some(value) => format!("U+{:04X}", value as u32),
+            None        => String::from("-")
+        };
+        let or_dash = |x: &Option<String>| x.clone().unwrap_or_else(||String::from("-"));
+
+        let mut fields = Column::with_children([
+            GlyphInfo::field("Glyph ID:", format!("{}", info.id)),
+            GlyphInfo::field("Glyph name:", or_dash(&info.glyph_name)),
+            GlyphInfo::field("Code point:", value),
+            GlyphInfo::field("Name:", or_dash(&info.name)),
+            GlyphInfo::field("Advance:", format!("{}", info.advance)),
+            GlyphInfo::field("LSB / RSB:", format!("{} / {}", info.side_bearings.0, info.side_bearings.1)),
+            GlyphInfo::field("Bounding box:", format!("({}, {}) - ({}, {})", x_min, y_min, x_max, y_max)),
+            GlyphInfo::field("Contours:", format!("{}", info.contours)),
+            GlyphInfo::field("On-curve:", format!("{}", info.on_curve)),
+            GlyphInfo::field("Off-curve:", format!("{}", info.off_curve)),
+        ]).spacing(4);
+
+        if !info.components.is_empty() {
+            fields = fields.push(text("Components:"));
+
+            for (idx, component) in info.components.iter().enumerate() {
+                fields = fields.push(component.view(idx));
+            }
+        }
+
+        if !info.charstring.is_empty() {
+            fields = fields.push(text("Charstring:"));
+
+            for (idx, instruction) in info.charstring.iter().enumerate() {
+                let mut line = text(format!("{:5} {}", instruction.offset, instruction.text))
+                    .font(Font::MONOSPACE)
+                    .size(12);
+
+                if info.hover == Some(idx) && !instruction.segments.is_empty() {
+                    line = line.style(text::primary);
+                }
+
+                fields = fields.push(
+                    mouse_area(container(line).padding([0, 16 * instruction.depth as u16]))
+                        .on_enter(Message::CharstringHover(idx, true))
+                        .on_exit(Message::CharstringHover(idx, false))
+                );
+            }
+        }
+
+        if !info.instructions.is_empty() {
+            fields = fields.push(text("Instructions:"));
+
+            for operation in info.instructions.iter() {
+                fields = fields.push(
+                    container(
+                        text(format!("{:5} {}", operation.offset, operation))
+                            .font(Font::MONOSPACE)
+                            .size(12)
+                    ).padding([0, 16 * operation.depth as u16])
+                );
+            }
+        }
+
+        scrollable(fields.padding(4))
+            .width(300)
+            .into()
+    }
+
+    //*************************************************************************
+    pub fn handle_component_toggle(
+                &mut self,
+                idx:  usize,
+                show: bool
+            ) -> Range<usize> {
+        // The components nested in this one follow it.
+        let depth = self.components[idx].component.depth;
+
+        self.components[idx].show = show;
+
+        for component in self.components[idx + 1..].iter_mut().take_while(|x|x.component.depth > depth) {
+            component.show = show;
+        }
+
+        self.components[idx].component.contours.clone()
+    }
+
+    //*************************************************************************
+    pub fn handle_charstring_hover(
+                &mut self,
+                idx:   usize,
+                hover: bool
+            ) {
+        // Leaving a line after entering the next one keeps the next one.
+        if hover {
+            self.hover = Some(idx);
+        } else if self.hover == Some(idx) {
+            self.hover = None;
+        }
+    }
+
+    //*************************************************************************
+    pub fn highlight(&self) -> Vec<(usize, usize)> {
+        self.hover
+            .and_then(|x|self.charstring.get(x))
+            .map(|x|x.segments.clone())
+            .unwrap_or_default()
+    }
+}
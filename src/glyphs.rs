@@ -22,8 +22,7 @@ SOFTWARE.
 use std::{
     cell::Cell,
     collections::{BTreeMap, HashMap},
-    fs::File,
-    io::Error,
+    io::{Cursor, Error},
     rc::Rc
 };
 use iced::{
@@ -34,70 +33,236 @@ use iced::{
     Element
 };
 use font::{
-    self,
-    formats::opentype::characters::Character,
     glyph::Segment,
-    Font
+    opentype::{
+        self,
+        truetype::tables::{
+            character_mapping::{CharacterMapping, Encoding},
+            names::NameID,
+            FontHeader, Names, WindowsMetrics
+        }
+    }
 };
 
-use crate::{Color, PlotPoint};
+use crate::{bytecode, substitutions, unicode, Browse, Color, ComponentInfo, GlyphInfo, GlyphPoints, Guides, Hinted, Interpreter, Kerning, Metadata, Outline, Outlines, PairInfo, PlotPoint, Step, Substitutions, Variations};
 use super::{Lines, Message};
 
+//*****************************************************************************
+const COMPONENT_COLORS: [Color; 8] = [
+    Color::Blue,
+    Color::Crimson,
+    Color::Green,
+    Color::Orange,
+    Color::Purple,
+    Color::Cyan,
+    Color::Brown,
+    Color::Magenta
+];
+
+//*****************************************************************************
+// The outline the hinting started from, under the hinted one.
+const ORIGINAL_COLOR: Color = Color::Blue;
+
+//*****************************************************************************
+// The glyph for each character and the ranges of characters mapped.
+type Mapping = (HashMap<u32, u16>, Vec<(u32, u32)>);
+
+//*****************************************************************************
+// The minimum x and y, then the maximum.
+type BoundingBox = (f32, f32, f32, f32);
+
+//*****************************************************************************
+// The hinting that ran, the outline it left and how far it moved each point.
+type HintedGlyph = (Hinted, GlyphPoints, font::Glyph, font::Glyph, Vec<(f32, f32)>);
+
 //*****************************************************************************
 pub struct Glyphs {
-    font:       Font<File>,
-    selected:   Option<Rc<Glyph>>,
-    by_chars:   HashMap<char, Rc<Glyph>>,
-    by_unicode: BTreeMap<String, Rc<Glyph>>,
-    filtered:   Vec<Rc<Glyph>>
+    metrics:      Option<Guides>,
+    outlines:     Outlines,
+    kerning:      Kerning,
+    substitutions: Substitutions,
+    metadata:     Option<Metadata>,
+    interpreter:  Option<Interpreter>,
+    hinting:      Option<(i32, Option<usize>)>,
+    units_per_em: Option<f32>,
+    glyph_ids:    HashMap<u32, u16>,
+    chars:        HashMap<u16, char>,
+    browse:       Browse,
+    selected:     Vec<Rc<Glyph>>,
+    by_chars:     HashMap<char, Rc<Glyph>>,
+    by_unicode:   BTreeMap<String, Rc<Glyph>>,
+    by_ids:       Vec<Rc<Glyph>>,
+    filtered:     Vec<Rc<Glyph>>
 }
 
 impl Glyphs {
     //**************************************************************************
     pub fn new(
-                filename: &str
+                data: Vec<u8>
             ) -> Result<Self, String> {
-        let input                    = File::open(filename).map_err(|x|format!("{:?}", x))?;
-        let font::File { mut fonts } = font::File::read(input).map_err(|x|format!("{:?}", x))?;
-
-        if fonts.len() != 1 {
-            return Err(format!("Found {} fonts in the file expected only 1.", fonts.len()));
-        }
+        let (glyph_ids, ranges)    = Glyphs::read_mapping(&data).map_err(|x|format!("{:?}", x))?;
+        let outlines               = Outlines::read(&data).map_err(|x|format!("{:?}", x))?;
+        let (units_per_em, metrics) = Glyphs::read_metrics(&data).map_err(|x|format!("{:?}", x))?;
+
+        // Glyphs are still worth seeing when the kerning can't be read.
+        let kerning = Kerning::read(&data).unwrap_or_else(|err|{
+            eprintln!("Error reading kerning: {}", err);
+            Kerning::default()
+        });
+        let substitutions = Substitutions::read(&data).unwrap_or_else(|err|{
+            eprintln!("Error reading substitutions: {}", err);
+            Substitutions::default()
+        });
+        let metadata = Metadata::read(&data).map_err(|err|eprintln!("Error reading metadata: {}", err)).ok();
+        let interpreter = Interpreter::read(&data).unwrap_or_else(|err|{
+            eprintln!("Error reading hinting: {}", err);
+            None
+        });
 
         let mut by_chars  = HashMap::new();
         let mut by_unicode = BTreeMap::new();
         let mut filtered   = Vec::new();
 
-        if let Ok(chars) = fonts[0].characters() {
-            for ch in chars.iter() {
-                match ch {
-                    Character::Scalar(x) => {
-                        let g = Rc::new(Glyph::new(*x));
-                        by_chars.insert(*x, g.clone());
-                        by_unicode.insert(g.code(), g.clone());
-                        filtered.push(g);
-                    },
-                    Character::Range((start, end)) => {
-                        for x in *start..=*end {
-                            let g = Rc::new(Glyph::new(x));
-                            by_chars.insert(x, g.clone());
-                            by_unicode.insert(g.code(), g.clone());
-                            filtered.push(g);
-                        }
-                    },
-                }
+        for x in ranges.iter().flat_map(|&(start, end)|start..=end).filter_map(char::from_u32) {
+            let g = Rc::new(Glyph::new(x));
+            by_chars.insert(x, g.clone());
+            by_unicode.insert(g.code().to_string(), g.clone());
+            filtered.push(g);
+        }
+
+        // A glyph used by several characters shows the lowest one.
+        let mut chars = HashMap::new();
+
+        for (&value, &id) in glyph_ids.iter() {
+            if let Some(ch) = char::from_u32(value) {
+                let entry = chars.entry(id).or_insert(ch);
+                *entry = ch.min(*entry);
             }
         }
 
+        let by_ids = (0..outlines.count())
+            .map(|x|Rc::new(Glyph::with_id(x as u16, outlines.name(x as u16))))
+            .collect();
+
         Ok(
             Self {
-                font:     fonts.pop().unwrap(),
-                selected: None,
-                by_chars, by_unicode, filtered
+                browse:   Browse::Characters,
+                selected: Vec::new(),
+                hinting:  None,
+                metrics, outlines, kerning, substitutions, metadata, interpreter, by_chars, by_unicode, by_ids, filtered, units_per_em, glyph_ids, chars
             }
         )
     }
 
+    //*************************************************************************
+    pub fn find_name(
+                names: &Names,
+                id:    NameID
+            ) -> Option<String> {
+        let mut found = None;
+
+        // English names are preferred.
+        for ((_, _, language, name_id), value) in names.iter() {
+            if name_id != id || value.is_none() {
+                continue;
+            }
+            if language.tag(&[] as &[Option<String>]).is_some_and(|x|x.starts_with("en")) {
+                return value;
+            }
+            found = found.or(value);
+        }
+
+        found
+    }
+
+    //*************************************************************************
+    pub fn face_name(
+                data: &[u8]
+            ) -> String {
+        let mut tape = Cursor::new(data);
+        let Ok(font) = opentype::Font::read(&mut tape) else {
+            return String::from("<unreadable>");
+        };
+        let Ok(Some(names)) = font.take::<_, Names>(&mut tape) else {
+            return String::from("<unnamed>");
+        };
+
+        // The typographic family and style names are preferred over the
+        // legacy ones.
+        let find = |id: NameID| Glyphs::find_name(&names, id);
+
+        let family = find(NameID::TypographicFamilyName).or_else(||find(NameID::FontFamilyName));
+        let style  = find(NameID::TypographicSubfamilyName).or_else(||find(NameID::FontSubfamilyName));
+
+        match (family, style) {
+            (Some(family), Some(style)) => format!("{} {}", family, style),
+            (Some(family), None)        => family,
+            _                           => String::from("<unnamed>")
+        }
+    }
+
+    //*************************************************************************
+    fn read_mapping(
+                data: &[u8]
+            ) -> Result<Mapping, Error> {
+        let mut tape = Cursor::new(data);
+        let font     = opentype::Font::read(&mut tape)?;
+
+        // Same choice of encoding the font crate makes when it draws glyphs.
+        if let Some(mapping) = font.take::<_, CharacterMapping>(&mut tape)? {
+            for encoding in mapping.encodings.iter() {
+                match encoding {
+                    Encoding::Format0(x)  => return Ok((x.mapping(), x.characters())),
+                    Encoding::Format4(x)  => return Ok((x.mapping(), x.characters())),
+                    Encoding::Format6(x)  => return Ok((x.mapping(), x.characters())),
+                    Encoding::Format12(x) => return Ok((x.mapping(), x.characters())),
+                    _                     => {}
+                }
+            }
+        }
+
+        Ok((HashMap::new(), Vec::new()))
+    }
+
+    //*************************************************************************
+    fn read_metrics(
+                data: &[u8]
+            ) -> Result<(Option<f32>, Option<Guides>), Error> {
+        let mut tape = Cursor::new(data);
+        let font     = opentype::Font::read(&mut tape)?;
+
+        let header:  Option<FontHeader>     = font.take(&mut tape)?;
+        let windows: Option<WindowsMetrics> = font.take(&mut tape)?;
+
+        // Same metrics the font crate reads, the heights only exist from
+        // version 2 of the OS/2 table.
+        let (Some(header), Some(windows)) = (header, windows) else {
+            return Ok((None, None));
+        };
+        let (ascender, descender, heights) = match &windows {
+            WindowsMetrics::Version0(x) => (x.typographic_ascender, x.typographic_descender, None),
+            WindowsMetrics::Version1(x) => (x.typographic_ascender, x.typographic_descender, None),
+            WindowsMetrics::Version2(x) => (x.typographic_ascender, x.typographic_descender, Some((x.x_height, x.cap_height))),
+            WindowsMetrics::Version3(x) => (x.typographic_ascender, x.typographic_descender, Some((x.x_height, x.cap_height))),
+            WindowsMetrics::Version4(x) => (x.typographic_ascender, x.typographic_descender, Some((x.x_height, x.cap_height))),
+            WindowsMetrics::Version5(x) => (x.typographic_ascender, x.typographic_descender, Some((x.x_height, x.cap_height)))
+        };
+        let (x_height, cap_height) = heights
+            .map(|(x, y)|(x as f32, y as f32))
+            .unwrap_or((f32::NAN, f32::NAN));
+
+        let guides = Guides {
+            baseline:     0.0,
+            ascender:     ascender as f32,
+            descender:    descender as f32,
+            advance:      0.0,
+            bounding_box: (f32::NAN, f32::NAN, f32::NAN, f32::NAN),
+            x_height, cap_height
+        };
+
+        Ok((Some(header.units_per_em as f32), Some(guides)))
+    }
+
     //*************************************************************************
     fn glyph_button(
                 glyph: &Rc<Glyph>
@@ -109,7 +274,7 @@ impl Glyphs {
         };
         button(
             glyph.label()
-        ).on_press(Message::FontView(glyph.value))
+        ).on_press(glyph.press.clone())
          .style(style)
          .into()
     }
@@ -142,7 +307,11 @@ impl Glyphs {
             if self.filtered.len() > 0 {
                 return false;
             }
-            if let Some(val) = self.by_chars.get(&ch) {
+            let found = match self.browse {
+                Browse::Characters => self.by_chars.get(&ch),
+                Browse::GlyphIds   => self.glyph_ids.get(&(ch as u32)).and_then(|&x|self.by_ids.get(x as usize))
+            };
+            if let Some(val) = found {
                 self.filtered.push(val.clone());
             }
         }
@@ -166,85 +335,502 @@ impl Glyphs {
         }
     }
 
+    //**************************************************************************
+    fn filter_by_id(
+                &mut self,
+                filter: &str
+            ) {
+        self.filtered.clear();
+
+        // Matches the glyph ID or the glyph name.
+        let actual = filter.trim().to_uppercase();
+
+        for glyph in self.by_ids.iter() {
+            if glyph.code().contains(&actual) {
+                self.filtered.push(glyph.clone());
+            }
+        }
+    }
+
     //**************************************************************************
     pub fn filter(
                 &mut self,
                 filter: &str
             ) {
+        self.filtered.clear();
+
         if filter.trim().len() == 0 {
-            for glyph in self.by_unicode.values() {
-                self.filtered.push(glyph.clone());
+            match self.browse {
+                Browse::Characters => self.filtered.extend(self.by_unicode.values().cloned()),
+                Browse::GlyphIds   => self.filtered.extend(self.by_ids.iter().cloned())
             }
         } else if self.filter_by_char(filter) == false {
-            self.filter_by_unicode(filter);
+            match self.browse {
+                Browse::Characters => self.filter_by_unicode(filter),
+                Browse::GlyphIds   => self.filter_by_id(filter)
+            }
         }
     }
 
+    //**************************************************************************
+    pub fn handle_browse(
+                &mut self,
+                browse: Browse,
+                filter: &str
+            ) {
+        self.browse = browse;
+        self.filter(filter);
+    }
+
+    //**************************************************************************
+    pub fn units_per_em(&self) -> Option<f32> {
+        self.units_per_em
+    }
+
+    //**************************************************************************
+    pub fn variations(&self) -> &Variations {
+        self.outlines.variations()
+    }
+
+    //**************************************************************************
+    pub fn handle_location(
+                &mut self,
+                location: &[f32]
+            ) {
+        self.outlines.set_location(location);
+    }
+
+    //**************************************************************************
+    pub fn handle_hinting(
+                &mut self,
+                hinting: Option<(i32, Option<usize>)>
+            ) {
+        self.hinting = hinting;
+    }
+
+    //**************************************************************************
+    fn hint(
+                &self,
+                id:     u16,
+                points: &GlyphPoints
+            ) -> Option<HintedGlyph> {
+        // Only TrueType outlines have instructions to run.
+        let interpreter  = self.interpreter.as_ref()?;
+        let (ppem, step) = self.hinting?;
+
+        // The original is drawn from the same points as the hinted outline,
+        // so that both place composite parts alike.
+        let hinted  = interpreter.hint(points, ppem, step);
+        let moved   = hinted.points(points);
+        let outline = self.outlines.outline_from_points(id, &moved).and_then(|outline|{
+            Ok((outline.glyph, self.outlines.outline_from_points(id, points)?.glyph))
+        });
+
+        match outline {
+            Ok((glyph, original)) => {
+                let moves = hinted.moves(points);
+                Some((hinted, moved, glyph, original, moves))
+            },
+            Err(err) => {
+                eprintln!("Error hinting glyph: {}: {}", id, err);
+                None
+            }
+        }
+    }
+
+    //**************************************************************************
+    pub fn find_glyph(
+                &self,
+                input: &str
+            ) -> Option<u16> {
+        // Either a single character or a glyph ID after a '#'.
+        let input = input.trim();
+
+        if let Some(id) = input.strip_prefix('#') {
+            return id.parse().ok().filter(|&x: &u16|(x as usize) < self.outlines.count());
+        }
+
+        let mut chars = input.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => self.glyph_ids.get(&(ch as u32)).copied(),
+            _                => None
+        }
+    }
+
+    //**************************************************************************
+    pub fn kerning_pairs(
+                &self,
+                left: u16
+            ) -> Vec<PairInfo> {
+        self.kerning.pairs(left)
+            .into_iter()
+            .map(|(id, value)|PairInfo { id, code: self.glyph_code(id), label: self.glyph_label(id), value })
+            .collect()
+    }
+
+    //**************************************************************************
+    fn visible_char(
+                &self,
+                id: u16
+            ) -> Option<char> {
+        self.chars.get(&id).copied().filter(|x|!x.is_whitespace() && !x.is_control())
+    }
+
+    //**************************************************************************
+    pub fn glyph_code(
+                &self,
+                id: u16
+            ) -> String {
+        // Either a single character or a glyph ID after a '#', the way
+        // find_glyph() reads it back.
+        match self.visible_char(id) {
+            Some(ch) => ch.to_string(),
+            None     => format!("#{}", id)
+        }
+    }
+
+    //**************************************************************************
+    pub fn glyph_label(
+                &self,
+                id: u16
+            ) -> String {
+        match self.visible_char(id) {
+            Some(ch) => ch.to_string(),
+            None     => self.outlines.name(id).map(String::from).unwrap_or_else(||format!("#{}", id))
+        }
+    }
+
+    //**************************************************************************
+    pub fn metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    //**************************************************************************
+    pub fn substitutions(&self) -> &Substitutions {
+        &self.substitutions
+    }
+
+    //**************************************************************************
+    pub fn view_pair(
+                &mut self,
+                left:    u16,
+                advance: f32,
+                right:   u16,
+                lines:   &mut Lines
+            ) -> Result<f32, Error> {
+        // The right glyph starts at the left one's advance moved by the
+        // kerning.
+        let value = self.kerning.get(left, right);
+
+        if let Some(Outline { glyph: def, .. }) = self.outlines.glyph(right)? {
+            let colors = vec![Color::Black; def.contours.len()];
+            Glyphs::add_contours(&def, &colors, PlotPoint::new(advance + value, 0.0), lines);
+        }
+
+        Ok(value)
+    }
+
+    //**************************************************************************
+    pub fn view_text(
+                &mut self,
+                text:  &str,
+                lines: &mut Lines
+            ) -> Result<Option<Guides>, Error> {
+        lines.clear();
+
+        let ids = self.text_ids(text);
+        let (advance, bounding_box) = self.add_run(&ids, Color::Black, 0.0, lines)?;
+
+        Ok(self.metrics.map(|metrics|Guides { advance, bounding_box, ..metrics }))
+    }
+
+    //**************************************************************************
+    pub fn view_substituted(
+                &mut self,
+                text:     &str,
+                script:   &str,
+                language: &str,
+                features: &[String],
+                lines:    &mut Lines
+            ) -> Result<(Option<Guides>, Vec<Step>), Error> {
+        lines.clear();
+
+        let before = self.text_ids(text);
+        let forms  = substitutions::joining_forms(text);
+        let (after, steps) = self.substitutions
+            .apply(&before, &forms, script, language, features)
+            .map_err(Error::other)?;
+
+        // The substituted run sits a line below the typed one.
+        let line = match self.metrics {
+            Some(metrics) => metrics.ascender - metrics.descender,
+            None          => self.units_per_em.unwrap_or(1000.0) * 1.2
+        };
+
+        // Right to left scripts are drawn in the order they are read.
+        let visual = |ids: &[u16]|{
+            let mut ids = ids.to_vec();
+            if substitutions::is_right_to_left(script) {
+                ids.reverse();
+            }
+            ids
+        };
+
+        let (advance1, box1) = self.add_run(&visual(&before), Color::Black, 0.0, lines)?;
+        let (advance2, box2) = self.add_run(&visual(&after), Color::Blue, -line, lines)?;
+
+        let bounding_box = (box1.0.min(box2.0), box1.1.min(box2.1), box1.2.max(box2.2), box1.3.max(box2.3));
+        let guides = self.metrics.map(|metrics|Guides { advance: advance1.max(advance2), bounding_box, ..metrics });
+
+        Ok((guides, steps))
+    }
+
+    //**************************************************************************
+    fn text_ids(
+                &self,
+                text: &str
+            ) -> Vec<u16> {
+        // Characters the font doesn't map show as glyph 0.
+        text.chars()
+            .map(|ch|self.glyph_ids.get(&(ch as u32)).copied().unwrap_or(0))
+            .collect()
+    }
+
+    //**************************************************************************
+    fn add_run(
+                &mut self,
+                ids:   &[u16],
+                color: Color,
+                y:     f32,
+                lines: &mut Lines
+            ) -> Result<(f32, BoundingBox), Error> {
+        // Each glyph starts where the one before it advanced to.
+        let mut x            = 0.0_f32;
+        let mut bounding_box = (f32::NAN, f32::NAN, f32::NAN, f32::NAN);
+
+        for &id in ids {
+            if let Some(Outline { glyph: def, .. }) = self.outlines.glyph(id)? {
+                let colors = vec![color; def.contours.len()];
+                Glyphs::add_contours(&def, &colors, PlotPoint::new(x, y), lines);
+
+                let (x_min, y_min, x_max, y_max) = def.bounding_box;
+                bounding_box = (
+                    bounding_box.0.min(x + x_min),
+                    bounding_box.1.min(y + y_min),
+                    bounding_box.2.max(x + x_max),
+                    bounding_box.3.max(y + y_max)
+                );
+                x += def.advance_width;
+            }
+        }
+
+        Ok((x, bounding_box))
+    }
+
     //**************************************************************************
     pub fn view_glyph(
                 &mut self,
                 glyph: char,
                 lines: &mut Lines
-            ) -> Result<(), Error> {
-        if let Some(glyph) = self.selected.take() {
+            ) -> Result<Option<GlyphInfo>, Error> {
+        let id = self.glyph_ids.get(&(glyph as u32)).copied();
+
+        self.view_id(id, Some(glyph), lines)
+    }
+
+    //**************************************************************************
+    pub fn view_glyph_id(
+                &mut self,
+                id:    u16,
+                lines: &mut Lines
+            ) -> Result<Option<GlyphInfo>, Error> {
+        let value = self.chars.get(&id).copied();
+
+        self.view_id(Some(id), value, lines)
+    }
+
+    //**************************************************************************
+    fn add_contours(
+                def:    &font::Glyph,
+                colors: &[Color],
+                origin: PlotPoint,
+                lines:  &mut Lines
+            ) -> (usize, usize) {
+        let mut on_curve  = 0;
+        let mut off_curve = 0;
+
+        let mut p0 = origin;
+        for (contour, &color) in def.iter().zip(colors.iter()) {
+            p0 = p0 + contour.offset;
+            lines.open_contour();
+
+            let start = p0;
+
+            for segment in contour.iter() {
+                match segment {
+                    Segment::Linear(o1) => {
+                        let p1 = p0 + *o1;
+                        lines.add_line(color, p0, p1);
+                        on_curve += 1;
+                        p0 = p1
+                    },
+                    Segment::Quadratic(o1, o2) => {
+                        let p1 = p0 + *o1;
+                        let p2 = p1 + *o2;
+                        lines.add_quadratic(color, p0, p1, p2);
+                        on_curve  += 1;
+                        off_curve += 1;
+                        p0 = p2;
+                    },
+                    Segment::Cubic(o1, o2, o3) => {
+                        let p1 = p0 + *o1;
+                        let p2 = p1 + *o2;
+                        let p3 = p2 + *o3;
+                        lines.add_cubic(color, p0, p1, p2, p3);
+                        on_curve  += 1;
+                        off_curve += 2;
+                        p0 = p3;
+                    }
+                };
+            }
+
+            // Every contour in a font is closed, PostScript outlines leave
+            // the last segment back to the start unsaid.
+            if !p0.touches(start) {
+                lines.add_line(color, p0, start);
+            }
+            lines.close_contour();
+        }
+
+        (on_curve, off_curve)
+    }
+
+    //**************************************************************************
+    pub fn view_id(
+                &mut self,
+                id:    Option<u16>,
+                value: Option<char>,
+                lines: &mut Lines
+            ) -> Result<Option<GlyphInfo>, Error> {
+        for glyph in self.selected.drain(..) {
             glyph.clear_selected();
         }
 
-        if let Some(glyph) = self.by_chars.get(&glyph) {
+        let by_char = value.and_then(|x|self.by_chars.get(&x));
+        let by_id   = id.and_then(|x|self.by_ids.get(x as usize));
+
+        for glyph in by_char.into_iter().chain(by_id) {
             glyph.set_selected();
-            self.selected = Some(glyph.clone());
+            self.selected.push(glyph.clone());
         }
 
         lines.clear();
 
-        if let Some(def) = self.font.glyph(glyph)? {
-            let mut p0 = PlotPoint::new(0.0, 0.0);
-            for contour in def.iter() {
-                p0 = p0 + contour.offset;
-
-                for segment in contour.iter() {
-                    match segment {
-                        Segment::Linear(o1) => {
-                            let p1 = p0 + *o1;
-                            lines.add_line(Color::Black, p0, p1);
-                            p0 = p1
-                        },
-                        Segment::Quadratic(o1, o2) => {
-                            let p1 = p0 + *o1;
-                            let p2 = p1 + *o2;
-                            lines.add_quadratic(Color::Black, p0, p1, p2);
-                            p0 = p2;
-                        },
-                        Segment::Cubic(o1, o2, o3) => {
-                            let p1 = p0 + *o1;
-                            let p2 = p1 + *o2;
-                            let p3 = p2 + *o3;
-                            lines.add_cubic(Color::Black, p0, p1, p2, p3);
-                            p0 = p3;
-                        }
-                    };
-                }
-                let ( left , _, right, _ ) = def.bounding_box;
-
-                if let Ok(metrics) = self.font.metrics() {
-                    lines.add_line(Color::Gold, PlotPoint::new(left - def.side_bearings.0, metrics.baseline), PlotPoint::new(right + def.side_bearings.1, metrics.baseline));
-
-                    lines.add_line(Color::Crimson, PlotPoint::new(0.0, metrics.ascender), PlotPoint::new(def.advance_width, metrics.ascender));
-                    lines.add_line(Color::Crimson, PlotPoint::new(left - def.side_bearings.0, metrics.descender), PlotPoint::new(left - def.side_bearings.0, metrics.ascender));
-                    lines.add_line(Color::Crimson, PlotPoint::new(right + def.side_bearings.1, metrics.descender), PlotPoint::new(right + def.side_bearings.1, metrics.ascender));
-                    lines.add_line(Color::Crimson, PlotPoint::new(0.0, metrics.descender), PlotPoint::new(def.advance_width, metrics.descender));
-                }
+        let Some(id) = id else {
+            return Ok(None);
+        };
 
+        let mut info = None;
+
+        if let Some(Outline { glyph: def, components, shifts }) = self.outlines.glyph(id)? {
+            // The hinted outline takes the place of the one the font drew,
+            // which is kept to compare against. The variation deltas no
+            // longer line up with it.
+            let points = self.outlines.points(id).unwrap_or_else(|err|{
+                eprintln!("Error reading glyph points: {}: {}", id, err);
+                None
+            });
+            let (def, original, shifts, hinted, points, moves) = match points.as_ref().and_then(|x|self.hint(id, x)) {
+                Some((hinted, moved, glyph, original, moves)) => {
+                    let mut layer = Lines::new();
+                    let colors    = vec![ORIGINAL_COLOR; original.contours.len()];
+                    Glyphs::add_contours(&original, &colors, PlotPoint::new(0.0, 0.0), &mut layer);
+
+                    let original = layer.iter().map(|x|x.def().clone()).collect();
+                    (glyph, original, Vec::new(), Some(hinted), Some(moved), moves)
+                },
+                None => (def, Vec::new(), shifts, None, points, Vec::new())
+            };
+
+            // Each contour takes the color of the innermost component that
+            // drew it.
+            let colors = (0..def.contours.len())
+                .map(|i|{
+                    components.iter()
+                        .enumerate()
+                        .filter(|(_, x)|x.contours.contains(&i))
+                        .max_by_key(|(_, x)|x.depth)
+                        .map(|(j, _)|COMPONENT_COLORS[j % COMPONENT_COLORS.len()])
+                        .unwrap_or(Color::Black)
+                })
+                .collect::<Vec<Color>>();
+
+            let (mut on_curve, mut off_curve) = Glyphs::add_contours(&def, &colors, PlotPoint::new(0.0, 0.0), lines);
+
+            // The segments of a TrueType outline add the points implied
+            // between two off the curve, the glyph's own points don't.
+            if let Some(points) = points.as_ref() {
+                on_curve  = points.on_curve.iter().filter(|&&x|x).count();
+                off_curve = points.on_curve.len() - on_curve;
             }
+            let numbered = points.map(|x|{
+                x.points.iter()
+                    .zip(x.on_curve.iter())
+                    .map(|(&(x, y), &on)|(PlotPoint::new(x, y), on))
+                    .collect()
+            }).unwrap_or_default();
+
+            let guides = self.metrics.map(|metrics| {
+                Guides {
+                    advance:      def.advance_width,
+                    bounding_box: def.bounding_box,
+                    ..metrics
+                }
+            });
+
+            let charstring = self.outlines.charstring(id).unwrap_or_else(|err|{
+                eprintln!("Error disassembling glyph: {}: {}", id, err);
+                Vec::new()
+            });
+            let instructions = self.outlines.instructions(id)
+                .map_err(|x|x.to_string())
+                .and_then(|x|bytecode::disassemble(&x))
+                .unwrap_or_else(|err|{
+                    eprintln!("Error disassembling glyph instructions: {}: {}", id, err);
+                    Vec::new()
+                });
+
+            info = Some(
+                GlyphInfo {
+                    glyph_name:    self.outlines.name(id).map(String::from),
+                    name:          value.map(unicode::name),
+                    advance:       def.advance_width,
+                    side_bearings: def.side_bearings,
+                    bounding_box:  def.bounding_box,
+                    contours:      def.contours.len(),
+                    components:    components.into_iter()
+                        .enumerate()
+                        .map(|(i, component)|ComponentInfo {
+                            name:  self.outlines.name(component.glyph_id).map(String::from),
+                            color: COMPONENT_COLORS[i % COMPONENT_COLORS.len()],
+                            show:  true,
+                            component
+                        })
+                        .collect(),
+                    hover:         None,
+                    id, value, hinted, original, moves, numbered, on_curve, off_curve, guides, shifts, charstring, instructions
+                }
+            );
         }
 
-        Ok(())
+        Ok(info)
     }
 }
 
 //*****************************************************************************
 struct Glyph {
-    value:    char,
+    press:    Message,
+    code:     String,
     label:    String,
     selected: Cell<bool>
 }
@@ -260,9 +846,27 @@ impl Glyph {
         } else {
             format!("U+{:04X} ({})", u32::from(value), value)
         };
+        let press    = Message::FontView(value);
+        let code     = format!("{:04X}", u32::from(value));
+        let selected = Cell::new(false);
+
+        Self { press, code, label, selected }
+    }
+
+    //**************************************************************************
+    pub fn with_id(
+                id:   u16,
+                name: Option<&str>
+            ) -> Self {
+        let label = match name {
+            Some(name) => format!("#{} {}", id, name),
+            None       => format!("#{}", id)
+        };
+        let press    = Message::FontGlyph(id);
+        let code     = label.to_uppercase();
         let selected = Cell::new(false);
 
-        Self { value, label, selected }
+        Self { press, code, label, selected }
     }
 
     //**************************************************************************
@@ -271,8 +875,8 @@ impl Glyph {
     }
 
     //**************************************************************************
-    pub fn code(&self) -> String {
-        format!("{:04X}", u32::from(self.value))
+    pub fn code(&self) -> &str {
+        &self.code
     }
 
     //**************************************************************************
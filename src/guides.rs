@@ -0,0 +1,135 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::fmt::Display;
+
+use super::Color;
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guide {
+    Baseline,
+    XHeight,
+    CapHeight,
+    Ascender,
+    Descender,
+    Advance,
+    BoundingBox
+}
+
+impl Guide {
+    //*************************************************************************
+    pub const ALL: &'static [Self] = &[
+        Self::Baseline,
+        Self::XHeight,
+        Self::CapHeight,
+        Self::Ascender,
+        Self::Descender,
+        Self::Advance,
+        Self::BoundingBox
+    ];
+
+    //*************************************************************************
+    pub const DEFAULT: &'static [Self] = &[
+        Self::Baseline,
+        Self::Ascender,
+        Self::Descender,
+        Self::Advance
+    ];
+
+    //*************************************************************************
+    pub fn color(&self) -> Color {
+        match self {
+            Self::Baseline    => Color::Gold,
+            Self::XHeight     => Color::Cyan,
+            Self::CapHeight   => Color::Orange,
+            Self::Ascender    => Color::Crimson,
+            Self::Descender   => Color::Crimson,
+            Self::Advance     => Color::Purple,
+            Self::BoundingBox => Color::Green
+        }
+    }
+}
+
+//*****************************************************************************
+impl Display for Guide {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Baseline    => "Baseline",
+            Self::XHeight     => "x-height",
+            Self::CapHeight   => "Cap height",
+            Self::Ascender    => "Ascender",
+            Self::Descender   => "Descender",
+            Self::Advance     => "Advance",
+            Self::BoundingBox => "Bounding box",
+        })
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy)]
+pub struct Guides {
+    pub baseline:     f32,
+    pub x_height:     f32,
+    pub cap_height:   f32,
+    pub ascender:     f32,
+    pub descender:    f32,
+    pub advance:      f32,
+    pub bounding_box: (f32, f32, f32, f32)
+}
+
+impl Guides {
+    //*************************************************************************
+    pub fn lines(
+                &self,
+                guide: Guide
+            ) -> Vec<(f32, f32, f32, f32)> {
+        let (x_min, y_min, x_max, y_max) = self.bounding_box;
+
+        // The horizontal guides run across the glyph box and the advance.
+        let left  = x_min.min(0.0);
+        let right = x_max.max(self.advance);
+
+        let across = |y: f32| if y.is_finite() {
+            vec![(left, y, right, y)]
+        } else {
+            Vec::new()
+        };
+
+        match guide {
+            Guide::Baseline    => across(self.baseline),
+            Guide::XHeight     => across(self.x_height),
+            Guide::CapHeight   => across(self.cap_height),
+            Guide::Ascender    => across(self.ascender),
+            Guide::Descender   => across(self.descender),
+            Guide::Advance     => vec![
+                (0.0, self.descender, 0.0, self.ascender),
+                (self.advance, self.descender, self.advance, self.ascender)
+            ],
+            Guide::BoundingBox => vec![
+                (x_min, y_min, x_max, y_min),
+                (x_max, y_min, x_max, y_max),
+                (x_max, y_max, x_min, y_max),
+                (x_min, y_max, x_min, y_min)
+            ]
+        }
+    }
+}
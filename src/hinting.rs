@@ -0,0 +1,427 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{
+        button, checkbox, column, container, mouse_area, row, scrollable, text,
+        vertical_rule, Column
+    },
+    Element, Font, Length
+};
+
+use crate::interpreter::{Hinted, Vector, Zone};
+use super::{Color, Message, PlotPoint};
+
+//*****************************************************************************
+// How much of the trace shows before the next instruction, twice as much
+// follows it.
+const CONTEXT: usize = 8;
+
+// How much of the stack shows, from the top down.
+const STACK: usize = 16;
+
+// Points that moved less than the hinting can place them are taken to have
+// stayed where they were.
+const STILL: f32 = 1.0 / 64.0;
+
+//*****************************************************************************
+// A point of interest drawn over the outline, as an arrow when it has a
+// heading.
+#[derive(Debug, Clone)]
+pub struct Mark {
+    pub at:      PlotPoint,
+    pub label:   String,
+    pub color:   Color,
+    pub heading: Option<PlotPoint>
+}
+
+//*****************************************************************************
+pub struct Hinting {
+    show:    bool,
+    compare: bool,
+    step:    Option<usize>
+}
+
+impl Hinting {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            show:    false,
+            compare: false,
+            step:    None
+        }
+    }
+
+    //*************************************************************************
+    fn vector(
+                (x, y): Vector
+            ) -> String {
+        format!("({:.4}, {:.4})", x as f32 / 16384.0, y as f32 / 16384.0)
+    }
+
+    //*************************************************************************
+    fn pixels(
+                value: i32
+            ) -> String {
+        format!("{:.2}", value as f32 / 64.0)
+    }
+
+    //*************************************************************************
+    fn field<'a>(
+                label: &'a str,
+                value: String
+            ) -> Element<'a, Message> {
+        row![
+            text(label).width(120),
+            text(value)
+        ].spacing(8)
+         .into()
+    }
+
+    //*************************************************************************
+    fn line<'a>(
+                value: String
+            ) -> Element<'a, Message> {
+        text(value).font(Font::MONOSPACE).size(12).into()
+    }
+
+    //*************************************************************************
+    fn over(
+                hinted: &Hinted
+            ) -> usize {
+        // Calls made by the next instruction run to their end.
+        let Some(next) = hinted.next() else {
+            return hinted.trace.len();
+        };
+
+        hinted.trace.iter()
+            .enumerate()
+            .skip(hinted.step + 1)
+            .find(|(_, x)|x.depth <= next.depth)
+            .map(|(i, _)|i)
+            .unwrap_or(hinted.trace.len())
+    }
+
+    //*************************************************************************
+    fn out(
+                hinted: &Hinted
+            ) -> usize {
+        // The function the next instruction is in runs to its end.
+        let Some(next) = hinted.next() else {
+            return hinted.trace.len();
+        };
+
+        hinted.trace.iter()
+            .enumerate()
+            .skip(hinted.step + 1)
+            .find(|(_, x)|x.depth < next.depth)
+            .map(|(i, _)|i)
+            .unwrap_or(hinted.trace.len())
+    }
+
+    //*************************************************************************
+    pub fn view(
+                &self,
+                hinted: Option<&Hinted>
+            ) -> Element<'_, Message> {
+        let mut result = row![
+            checkbox("Hinting", self.show).on_toggle(Message::HintingToggle)
+        ].padding(4)
+         .spacing(8);
+
+        if !self.show {
+            return result.into();
+        }
+        result = result.push(checkbox("Compare", self.compare).on_toggle(Message::HintingCompare));
+
+        let Some(hinted) = hinted else {
+            return result.into();
+        };
+
+        let step    = hinted.step;
+        let running = step < hinted.trace.len();
+        let next    = match hinted.next() {
+            Some(trace) => hinted.describe(trace),
+            None        => String::from("Done.")
+        };
+        let goto = |ok: bool, step: usize|ok.then_some(Message::HintingStep(Some(step)));
+
+        result.push(button("Restart").style(button::secondary).on_press_maybe(goto(step > 0, 0)))
+            .push(button("Back").style(button::secondary).on_press_maybe(goto(step > 0, step.saturating_sub(1))))
+            .push(button("Step").style(button::secondary).on_press_maybe(goto(running, step + 1)))
+            .push(button("Over").style(button::secondary).on_press_maybe(goto(running, Hinting::over(hinted))))
+            .push(button("Out").style(button::secondary).on_press_maybe(goto(running, Hinting::out(hinted))))
+            .push(button("Run").style(button::secondary).on_press_maybe(running.then_some(Message::HintingStep(None))))
+            .push(text(format!("Step {} of {}", step, hinted.trace.len())).width(140))
+            .push(text(next).font(Font::MONOSPACE))
+            .into()
+    }
+
+    //*************************************************************************
+    fn zone<'a>(
+                fields: Column<'a, Message>,
+                label:  &'a str,
+                zone:   &Zone
+            ) -> Column<'a, Message> {
+        let mut fields = fields.push(text(label));
+
+        for (i, (&(x, y), &touched)) in zone.current.iter().zip(zone.touched.iter()).enumerate() {
+            let touched = match touched {
+                (true, true)   => "xy",
+                (true, false)  => "x",
+                (false, true)  => "y",
+                (false, false) => ""
+            };
+            fields = fields.push(
+                Hinting::line(format!("{:4} ({}, {}) {}", i, Hinting::pixels(x), Hinting::pixels(y), touched))
+            );
+        }
+
+        fields
+    }
+
+    //*************************************************************************
+    fn movement<'a>(
+                moves: &[(f32, f32)]
+            ) -> Column<'a, Message> {
+        // The points that moved furthest come first.
+        let distance = |&(x, y): &(f32, f32)|(x * x + y * y).sqrt();
+
+        let mut order = (0..moves.len()).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b|distance(&moves[b]).total_cmp(&distance(&moves[a])));
+
+        let moved   = moves.iter().filter(|x|distance(x) >= STILL).count();
+        let total   = moves.iter().map(distance).sum::<f32>();
+        let largest = match order.first() {
+            Some(&i) => format!("{:.2} px at {}", distance(&moves[i]), i),
+            None     => String::from("-")
+        };
+
+        let mut fields = column![
+            text("Movement:"),
+            Hinting::field("Moved:", format!("{} of {} points", moved, moves.len())),
+            Hinting::field("Largest:", largest),
+            Hinting::field("Average:", format!("{:.2} px", total / moves.len().max(1) as f32))
+        ].spacing(2);
+
+        for i in order {
+            let (x, y) = moves[i];
+            fields = fields.push(Hinting::line(format!("{:4} {:+.2} {:+.2} {:.2}", i, x, y, distance(&moves[i]))));
+        }
+
+        fields
+    }
+
+    //*************************************************************************
+    pub fn info_view<'a>(
+                &'a self,
+                hinted: &'a Hinted,
+                moves:  &[(f32, f32)]
+            ) -> Element<'a, Message> {
+        let machine = &hinted.machine;
+        let state   = &machine.state;
+
+        // The instructions around the next one, any of them can be jumped to.
+        let start     = hinted.step.saturating_sub(CONTEXT);
+        let mut trace = Column::new().spacing(2).push(text("Trace:"));
+
+        for (i, x) in hinted.trace.iter().enumerate().skip(start).take(3 * CONTEXT) {
+            let mut line = text(format!("{:6} {}", i, hinted.describe(x)))
+                .font(Font::MONOSPACE)
+                .size(12);
+
+            if i == hinted.step {
+                line = line.style(text::primary);
+            }
+            trace = trace.push(
+                mouse_area(container(line).padding([0, 16 * x.depth as u16]))
+                    .on_press(Message::HintingStep(Some(i)))
+            );
+        }
+        if let Some((step, error)) = hinted.error.as_ref() {
+            trace = trace.push(text(format!("Stopped at step {}: {}", step, error)).style(text::danger));
+        }
+
+        let graphics = column![
+            text("Graphics state:"),
+            Hinting::field("Projection:", Hinting::vector(state.projection)),
+            Hinting::field("Freedom:", Hinting::vector(state.freedom)),
+            Hinting::field("Dual:", Hinting::vector(state.dual)),
+            Hinting::field("Zones:", format!("{} {} {}", state.zp[0], state.zp[1], state.zp[2])),
+            Hinting::field("Reference:", format!("{} {} {}", state.rp[0], state.rp[1], state.rp[2])),
+            Hinting::field("Loop:", format!("{}", state.loop_count)),
+            Hinting::field("Round:", state.round.to_string()),
+            Hinting::field("Min distance:", Hinting::pixels(state.min_distance)),
+            Hinting::field("CVT cut-in:", Hinting::pixels(state.cut_in)),
+            Hinting::field("Single width:", format!("{} / {}", Hinting::pixels(state.single_width), Hinting::pixels(state.single_width_cut_in))),
+            Hinting::field("Delta base:", format!("{} / {}", state.delta_base, state.delta_shift)),
+            Hinting::field("Auto flip:", format!("{}", state.auto_flip)),
+            Hinting::field("Instruct:", format!("{}", state.instruct_control))
+        ].spacing(2);
+
+        // The top of the stack first, calls from the innermost out.
+        let mut values = Column::new().spacing(2).push(text(format!("Stack ({}):", machine.stack.len())));
+        for value in machine.stack.iter().rev().take(STACK) {
+            values = values.push(Hinting::line(format!("{}", value)));
+        }
+
+        values = values.push(text("Calls:"));
+        for frame in machine.calls.iter().rev() {
+            values = values.push(Hinting::line(format!("{} {}", frame.program, frame.position)));
+        }
+
+        let mut zones = Column::new().spacing(2);
+        zones = Hinting::zone(zones, "Glyph zone:", &machine.zones[1]);
+        zones = Hinting::zone(zones, "Twilight zone:", &machine.zones[0]);
+
+        let mut result = row![
+            scrollable(trace.padding(4)).width(Length::FillPortion(3)),
+            vertical_rule(8),
+            scrollable(graphics.padding(4)).width(Length::FillPortion(2)),
+            vertical_rule(8),
+            scrollable(values.padding(4)).width(Length::FillPortion(1)),
+            vertical_rule(8),
+            scrollable(zones.padding(4)).width(Length::FillPortion(2))
+        ];
+
+        if self.compare {
+            result = result.push(vertical_rule(8))
+                .push(scrollable(Hinting::movement(moves).padding(4)).width(Length::FillPortion(2)));
+        }
+
+        result.width(1000).into()
+    }
+
+    //*************************************************************************
+    fn push(
+                marks: &mut Vec<Mark>,
+                mark:  Mark
+            ) {
+        // Marks in the same place share a label rather than draw over it.
+        let same = marks.iter_mut().find(|x|{
+            x.at.touches(mark.at) && match (x.heading, mark.heading) {
+                (Some(a), Some(b)) => a.touches(b),
+                (None, None)       => true,
+                _                  => false
+            }
+        });
+
+        match same {
+            Some(x) => x.label = format!("{} {}", x.label, mark.label),
+            None    => marks.push(mark)
+        }
+    }
+
+    //*************************************************************************
+    pub fn marks(
+                &self,
+                hinted: Option<&Hinted>
+            ) -> Vec<Mark> {
+        let Some(hinted) = hinted.filter(|_|self.show) else {
+            return Vec::new();
+        };
+        let state      = &hinted.machine.state;
+        let mut result = Vec::new();
+
+        // The reference points, each in the zone it's used with.
+        let references = [(state.zp[0], state.rp[0]), (state.zp[0], state.rp[1]), (state.zp[1], state.rp[2])];
+        for (i, &(zone, point)) in references.iter().enumerate() {
+            if let Some((x, y)) = hinted.position(zone, point) {
+                Hinting::push(&mut result, Mark {
+                    at:      PlotPoint::new(x, y),
+                    label:   format!("RP{}", i),
+                    color:   Color::Orange,
+                    heading: None
+                });
+            }
+        }
+
+        // The twilight points the instructions have placed.
+        let twilight = &hinted.machine.zones[0];
+        for (i, &point) in twilight.current.iter().enumerate() {
+            if point == (0, 0) && twilight.touched[i] == (false, false) {
+                continue;
+            }
+            if let Some((x, y)) = hinted.position(0, i) {
+                Hinting::push(&mut result, Mark {
+                    at:      PlotPoint::new(x, y),
+                    label:   format!("T{}", i),
+                    color:   Color::Purple,
+                    heading: None
+                });
+            }
+        }
+
+        // The vectors start at rp0 and are a quarter of an em long.
+        let at     = hinted.position(state.zp[0], state.rp[0]).unwrap_or((0.0, 0.0));
+        let length = hinted.to_font((hinted.machine.ppem * 16, 0)).0;
+        let vectors = [("PV", state.projection, Color::Crimson), ("FV", state.freedom, Color::Cyan), ("DV", state.dual, Color::Gold)];
+
+        for (label, (x, y), color) in vectors {
+            Hinting::push(&mut result, Mark {
+                at:      PlotPoint::new(at.0, at.1),
+                label:   String::from(label),
+                heading: Some(PlotPoint::new(length * x as f32 / 16384.0, length * y as f32 / 16384.0)),
+                color
+            });
+        }
+
+        result
+    }
+
+    //*************************************************************************
+    pub fn is_comparing(&self) -> bool {
+        self.show && self.compare
+    }
+
+    //*************************************************************************
+    pub fn settings(
+                &self,
+                ppem: i32
+            ) -> Option<(i32, Option<usize>)> {
+        self.show.then_some((ppem, self.step))
+    }
+
+    //*************************************************************************
+    pub fn handle_toggle(
+                &mut self,
+                show: bool
+            ) {
+        self.show = show;
+        self.step = None;
+    }
+
+    //*************************************************************************
+    pub fn handle_compare(
+                &mut self,
+                compare: bool
+            ) {
+        self.compare = compare;
+    }
+
+    //*************************************************************************
+    pub fn handle_step(
+                &mut self,
+                step: Option<usize>
+            ) {
+        self.step = step;
+    }
+}
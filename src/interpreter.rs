@@ -0,0 +1,2017 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    bytecode::{self, Operation},
+    outlines::GlyphPoints,
+    sfnt::{self, read_u16}
+};
+
+//*****************************************************************************
+// A program that runs longer than this is taken to be stuck in a loop.
+const MAX_STEPS: usize = 1_000_000;
+const MAX_CALLS: usize = 64;
+
+// Each glyph gets the origin, the advance and the two vertical phantom
+// points after its own.
+const PHANTOM: usize = 4;
+
+//*****************************************************************************
+pub type Point  = (i32, i32);
+pub type Vector = (i32, i32);
+
+// Vectors are 2.14 fixed point numbers.
+const ONE: i32 = 0x4000;
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Program {
+    Font,
+    Control,
+    Glyph
+}
+
+impl Program {
+    //*************************************************************************
+    fn index(&self) -> usize {
+        match self {
+            Program::Font    => 0,
+            Program::Control => 1,
+            Program::Glyph   => 2
+        }
+    }
+}
+
+impl Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Program::Font    => "fpgm",
+            Program::Control => "prep",
+            Program::Glyph   => "glyf"
+        })
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Round {
+    Grid,
+    HalfGrid,
+    DoubleGrid,
+    DownToGrid,
+    UpToGrid,
+    Off,
+    Super(i32, i32, i32)
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Round::Grid                           => f.write_str("grid"),
+            Round::HalfGrid                       => f.write_str("half grid"),
+            Round::DoubleGrid                     => f.write_str("double grid"),
+            Round::DownToGrid                     => f.write_str("down to grid"),
+            Round::UpToGrid                       => f.write_str("up to grid"),
+            Round::Off                            => f.write_str("off"),
+            Round::Super(period, phase, threshold) => write!(f, "super {}/{}/{}", period, phase, threshold)
+        }
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone)]
+pub struct GraphicsState {
+    pub projection:          Vector,
+    pub freedom:             Vector,
+    pub dual:                Vector,
+    pub rp:                  [usize; 3],
+    pub zp:                  [usize; 3],
+    pub loop_count:          i32,
+    pub min_distance:        i32,
+    pub round:               Round,
+    pub cut_in:              i32,
+    pub single_width_cut_in: i32,
+    pub single_width:        i32,
+    pub delta_base:          i32,
+    pub delta_shift:         i32,
+    pub auto_flip:           bool,
+    pub instruct_control:    i32
+}
+
+impl GraphicsState {
+    //*************************************************************************
+    fn new() -> Self {
+        Self {
+            projection:          (ONE, 0),
+            freedom:             (ONE, 0),
+            dual:                (ONE, 0),
+            rp:                  [0; 3],
+            zp:                  [1; 3],
+            loop_count:          1,
+            min_distance:        64,
+            round:               Round::Grid,
+            cut_in:              68,
+            single_width_cut_in: 0,
+            single_width:        0,
+            delta_base:          9,
+            delta_shift:         3,
+            auto_flip:           true,
+            instruct_control:    0
+        }
+    }
+
+    //*************************************************************************
+    fn reset(&mut self) {
+        // Glyph programs keep what the control program set, apart from these.
+        self.projection = (ONE, 0);
+        self.freedom    = (ONE, 0);
+        self.dual       = (ONE, 0);
+        self.rp         = [0; 3];
+        self.zp         = [1; 3];
+        self.loop_count = 1;
+        self.round      = Round::Grid;
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Default)]
+pub struct Zone {
+    pub original: Vec<Point>,
+    pub current:  Vec<Point>,
+    pub unscaled: Vec<Point>,
+    pub touched:  Vec<(bool, bool)>,
+    pub on_curve: Vec<bool>,
+    pub ends:     Vec<usize>
+}
+
+impl Zone {
+    //*************************************************************************
+    fn new(
+                count: usize
+            ) -> Self {
+        Self {
+            original: vec![(0, 0); count],
+            current:  vec![(0, 0); count],
+            unscaled: vec![(0, 0); count],
+            touched:  vec![(false, false); count],
+            on_curve: vec![true; count],
+            ends:     Vec::new()
+        }
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy)]
+struct Function {
+    program: Program,
+    start:   usize,
+    end:     usize
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub program:  Program,
+    pub position: usize,
+    start:        usize,
+    end:          usize,
+    count:        i32
+}
+
+//*****************************************************************************
+// An instruction that ran, and how deep in calls it was.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub program:   Program,
+    pub operation: Operation,
+    pub depth:     usize
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone)]
+pub struct Machine {
+    pub state:   GraphicsState,
+    pub stack:   Vec<i32>,
+    pub cvt:     Vec<i32>,
+    pub storage: Vec<i32>,
+    pub zones:   [Zone; 2],
+    pub calls:   Vec<Frame>,
+    pub ppem:    i32,
+    codes:       [Vec<u8>; 3],
+    functions:   HashMap<i32, Function>,
+    definitions: HashMap<u8, Function>,
+    scale:       i32,
+    units:       i32
+}
+
+//*****************************************************************************
+// Fixed point arithmetic rounds like FreeType's.
+fn mul_div(
+            a: i32,
+            b: i32,
+            c: i32
+        ) -> i32 {
+    if c == 0 {
+        return 0;
+    }
+    let sign   = (a < 0) ^ (b < 0) ^ (c < 0);
+    let (a, b, c) = (a.unsigned_abs() as i64, b.unsigned_abs() as i64, c.unsigned_abs() as i64);
+    let result = ((a * b + c / 2) / c) as i32;
+
+    if sign { result.wrapping_neg() } else { result }
+}
+
+//*****************************************************************************
+// How far apart two values are, worked out wider so that it can't overflow.
+fn gap(
+            a: i32,
+            b: i32
+        ) -> i64 {
+    (a as i64 - b as i64).abs()
+}
+
+//*****************************************************************************
+fn mul_fix(
+            a: i32,
+            b: i32
+        ) -> i32 {
+    mul_div(a, b, 0x10000)
+}
+
+//*****************************************************************************
+fn mul_14(
+            a: i32,
+            b: i32
+        ) -> i32 {
+    let value = a as i64 * b as i64;
+
+    ((value + 0x2000 + (value >> 63)) >> 14) as i32
+}
+
+//*****************************************************************************
+fn dot_14(
+            (x, y):   Point,
+            (vx, vy): Vector
+        ) -> i32 {
+    let value = x as i64 * vx as i64 + y as i64 * vy as i64;
+
+    ((value + 0x2000 + (value >> 63)) >> 14) as i32
+}
+
+//*****************************************************************************
+fn normalize(
+            x: i32,
+            y: i32
+        ) -> Option<Vector> {
+    // FreeType's approximation, which the hinting of some fonts relies on
+    // to the last bit.
+    let (sx, sy)       = (if x < 0 { -1 } else { 1 }, if y < 0 { -1 } else { 1 });
+    let (mut ux, mut uy) = (x.unsigned_abs(), y.unsigned_abs());
+
+    match (ux, uy) {
+        (0, 0) => return None,
+        (0, _) => return Some((0, sy * ONE)),
+        (_, 0) => return Some((sx * ONE, 0)),
+        _      => {}
+    }
+
+    let estimate = |ux: u32, uy: u32| if ux > uy { ux + (uy >> 1) } else { uy + (ux >> 1) };
+    let mut length = estimate(ux, uy);
+    let zeros      = length.leading_zeros();
+    let shift      = zeros as i32 - 15 - (length >= (0xAAAA_AAAA_u32 >> zeros)) as i32;
+
+    if shift > 0 {
+        ux   <<= shift;
+        uy   <<= shift;
+        length = estimate(ux, uy);
+    } else {
+        ux    >>= -shift;
+        uy    >>= -shift;
+        length >>= -shift;
+    }
+
+    let mut b      = 0x10000_i32.wrapping_sub(length as i32);
+    let (x, y)     = (ux as i32, uy as i32);
+    let (mut u, mut v);
+    loop {
+        u = x.wrapping_add(x.wrapping_mul(b) >> 16) as u32;
+        v = y.wrapping_add(y.wrapping_mul(b) >> 16) as u32;
+
+        let z = (u.wrapping_mul(u).wrapping_add(v.wrapping_mul(v)) as i32).wrapping_neg() / 0x200;
+        let z = z.wrapping_mul(0x10000_i32.wrapping_add(b) >> 8) / 0x10000;
+        b = b.wrapping_add(z);
+        if z <= 0 {
+            break;
+        }
+    }
+
+    Some(((u as i32).wrapping_mul(sx) / 4, (v as i32).wrapping_mul(sy) / 4))
+}
+
+impl Machine {
+    //*************************************************************************
+    fn pop(&mut self) -> Result<i32, String> {
+        self.stack.pop().ok_or_else(||String::from("Stack underflow."))
+    }
+
+    //*************************************************************************
+    fn frame(&mut self) -> Result<&mut Frame, String> {
+        self.calls.last_mut().ok_or_else(||String::from("No program is running."))
+    }
+
+    //*************************************************************************
+    fn point(
+                &self,
+                zp:    usize,
+                index: i32
+            ) -> Result<(usize, usize), String> {
+        let zone = self.state.zp[zp];
+
+        usize::try_from(index).ok()
+            .filter(|&x|x < self.zones[zone].current.len())
+            .map(|x|(zone, x))
+            .ok_or_else(||format!("Point {} is outside zone {}.", index, zone))
+    }
+
+    //*************************************************************************
+    fn glyph_point(
+                &self,
+                index: i32
+            ) -> Result<usize, String> {
+        // Only the glyph zone has points on and off the curve, whichever
+        // zone zp0 points at.
+        usize::try_from(index).ok()
+            .filter(|&x|x < self.zones[1].on_curve.len())
+            .ok_or_else(||format!("Point {} is outside zone 1.", index))
+    }
+
+    //*************************************************************************
+    fn current(
+                &self,
+                (zone, index): (usize, usize)
+            ) -> Point {
+        self.zones[zone].current[index]
+    }
+
+    //*************************************************************************
+    fn original(
+                &self,
+                (zone, index): (usize, usize)
+            ) -> Point {
+        self.zones[zone].original[index]
+    }
+
+    //*************************************************************************
+    fn project(
+                &self,
+                a: Point,
+                b: Point
+            ) -> i32 {
+        dot_14((a.0.wrapping_sub(b.0), a.1.wrapping_sub(b.1)), self.state.projection)
+    }
+
+    //*************************************************************************
+    fn dual_project(
+                &self,
+                a: Point,
+                b: Point
+            ) -> i32 {
+        dot_14((a.0.wrapping_sub(b.0), a.1.wrapping_sub(b.1)), self.state.dual)
+    }
+
+    //*************************************************************************
+    fn shift(
+                &self,
+                distance: i32
+            ) -> (i32, i32) {
+        // Moving along the freedom vector changes the projection by less
+        // than the distance moved.
+        let (fx, fy) = self.state.freedom;
+        let (px, py) = self.state.projection;
+        let dot      = match (fx, fy) {
+            (ONE, _) => px,
+            (_, ONE) => py,
+            _        => (px * fx + py * fy) >> 14
+        };
+        let dot      = if dot.abs() < 0x400 { ONE } else { dot };
+
+        (mul_div(distance, fx, dot), mul_div(distance, fy, dot))
+    }
+
+    //*************************************************************************
+    fn move_point(
+                &mut self,
+                (zone, index): (usize, usize),
+                distance:      i32,
+                touch:         bool
+            ) {
+        let (dx, dy) = self.shift(distance);
+        let (fx, fy) = self.state.freedom;
+        let zone     = &mut self.zones[zone];
+
+        if fx != 0 {
+            zone.current[index].0 = zone.current[index].0.wrapping_add(dx);
+            zone.touched[index].0 |= touch;
+        }
+        if fy != 0 {
+            zone.current[index].1 = zone.current[index].1.wrapping_add(dy);
+            zone.touched[index].1 |= touch;
+        }
+    }
+
+    //*************************************************************************
+    fn scale(
+                &self,
+                value: f32
+            ) -> i32 {
+        (value as f64 * self.scale as f64 / 65536.0).round() as i32
+    }
+
+    //*************************************************************************
+    fn phantom(
+                &self,
+                glyph: &GlyphPoints
+            ) -> [Point; PHANTOM] {
+        [(self.scale(glyph.origin), 0), (self.scale(glyph.origin + glyph.advance), 0), (0, 0), (0, 0)]
+    }
+
+    //*************************************************************************
+    fn original_distance(
+                &self,
+                a:        (usize, usize),
+                b:        (usize, usize),
+                twilight: bool
+            ) -> i32 {
+        if twilight || a.0 == 0 || b.0 == 0 {
+            self.original_units(a, b, true)
+        } else {
+            mul_fix(self.original_units(a, b, false), self.units)
+        }
+    }
+
+    //*************************************************************************
+    fn original_units(
+                &self,
+                a:        (usize, usize),
+                b:        (usize, usize),
+                twilight: bool
+            ) -> i32 {
+        // Distances between glyph points are measured in font units, as
+        // rounding the scaled outline would lose precision.
+        if twilight || a.0 == 0 || b.0 == 0 {
+            return self.dual_project(self.original(a), self.original(b));
+        }
+        let (a, b) = (self.zones[a.0].unscaled[a.1], self.zones[b.0].unscaled[b.1]);
+
+        self.dual_project(a, b)
+    }
+
+    //*************************************************************************
+    fn round(
+                &self,
+                distance: i32
+            ) -> i32 {
+        // Worked out wider, so that no distance can overflow.
+        let sign   = if distance < 0 { -1 } else { 1 };
+        let amount = (distance as i64).abs();
+
+        let result = match self.state.round {
+            Round::Grid       => (amount + 32) & !63,
+            Round::HalfGrid   => (amount & !63) + 32,
+            Round::DoubleGrid => (amount + 16) & !31,
+            Round::DownToGrid => amount & !63,
+            Round::UpToGrid   => (amount + 63) & !63,
+            Round::Off        => return distance,
+            Round::Super(period, phase, threshold) => {
+                let (period, phase) = (period as i64, phase as i64);
+                let value = (amount - phase + threshold as i64).div_euclid(period) * period + phase;
+                if value < 0 { phase } else { value }
+            }
+        };
+
+        (sign * result).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    //*************************************************************************
+    fn super_round(
+                value: i32,
+                grid:  i32
+            ) -> Round {
+        // The grid is in 2.14, the results in 26.6.
+        let period = match (value >> 6) & 3 {
+            0 => grid / 2,
+            2 => grid * 2,
+            _ => grid
+        };
+        let phase = match (value >> 4) & 3 {
+            0 => 0,
+            1 => period / 4,
+            2 => period / 2,
+            _ => period * 3 / 4
+        };
+        let threshold = match value & 15 {
+            0 => period - 1,
+            x => (x - 4) * period / 8
+        };
+
+        Round::Super((period >> 8).max(1), phase >> 8, threshold >> 8)
+    }
+
+    //*************************************************************************
+    fn line_vector(
+                &self,
+                a:             (usize, usize),
+                b:             (usize, usize),
+                perpendicular: bool,
+                original:      bool
+            ) -> Vector {
+        let (p, q) = if original {
+            (self.original(a), self.original(b))
+        } else {
+            (self.current(a), self.current(b))
+        };
+
+        // A line between two points in the same place runs along x.
+        match (p.0.wrapping_sub(q.0), p.1.wrapping_sub(q.1)) {
+            (0, 0)             => (ONE, 0),
+            (x, y) if perpendicular => normalize(y.wrapping_neg(), x).unwrap_or((ONE, 0)),
+            (x, y)             => normalize(x, y).unwrap_or((ONE, 0))
+        }
+    }
+
+    //*************************************************************************
+    fn skip(
+                &mut self,
+                targets: &[u8]
+            ) -> Result<u8, String> {
+        // Nested IFs are passed over whole.
+        let frame     = *self.frame()?;
+        let code      = &self.codes[frame.program.index()];
+        let mut depth = 0;
+        let mut position = frame.position;
+
+        while position < frame.end {
+            let (operation, next) = bytecode::decode(code, position)?;
+            position = next;
+
+            match operation.opcode {
+                0x58                                              => depth += 1,
+                0x59 if depth > 0                                 => depth -= 1,
+                x if depth == 0 && targets.contains(&x)           => {
+                    self.frame()?.position = position;
+                    return Ok(x);
+                },
+                _                                                 => {}
+            }
+        }
+
+        Err(String::from("Found no end to the block."))
+    }
+
+    //*************************************************************************
+    fn define(
+                &mut self
+            ) -> Result<Function, String> {
+        let frame = *self.frame()?;
+        let code  = &self.codes[frame.program.index()];
+        let mut position = frame.position;
+
+        while position < frame.end {
+            let (operation, next) = bytecode::decode(code, position)?;
+            position = next;
+
+            if operation.opcode == 0x2D {
+                self.frame()?.position = position;
+                return Ok(Function { program: frame.program, start: frame.position, end: position });
+            }
+        }
+
+        Err(String::from("Found no ENDF for the definition."))
+    }
+
+    //*************************************************************************
+    fn call(
+                &mut self,
+                function: Function,
+                count:    i32
+            ) -> Result<(), String> {
+        if self.calls.len() >= MAX_CALLS {
+            return Err(String::from("Calls are nested too deeply."));
+        }
+        if count > 0 {
+            self.calls.push(Frame {
+                program:  function.program,
+                position: function.start,
+                start:    function.start,
+                end:      function.end,
+                count
+            });
+        }
+
+        Ok(())
+    }
+
+    //*************************************************************************
+    fn function(
+                &mut self,
+                number: i32
+            ) -> Result<Function, String> {
+        self.functions.get(&number)
+            .copied()
+            .ok_or_else(||format!("Function {} is not defined.", number))
+    }
+
+    //*************************************************************************
+    fn finish(&mut self) {
+        // A LOOPCALL runs the function again until the count runs out.
+        if let Some(frame) = self.calls.last_mut() {
+            if frame.count > 1 {
+                frame.count   -= 1;
+                frame.position = frame.start;
+                return;
+            }
+        }
+        self.calls.pop();
+    }
+
+    //*************************************************************************
+    fn delta_point(
+                &mut self,
+                base: i32
+            ) -> Result<(), String> {
+        let count = self.pop()?;
+
+        for _ in 0..count {
+            let point    = self.pop()?;
+            let argument = self.pop()?;
+
+            if self.ppem == base.wrapping_add(self.state.delta_base).wrapping_add((argument >> 4) & 15) {
+                let steps    = (argument & 15) - 8;
+                let steps    = if steps >= 0 { steps + 1 } else { steps };
+                let distance = steps * 64 / (1 << self.state.delta_shift);
+                // Fonts delta points they don't have, so those are skipped.
+                if let Ok(point) = self.point(0, point) {
+                    self.move_point(point, distance, true);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //*************************************************************************
+    fn delta_value(
+                &mut self,
+                base: i32
+            ) -> Result<(), String> {
+        let count = self.pop()?;
+
+        for _ in 0..count {
+            let index    = self.pop()?;
+            let argument = self.pop()?;
+
+            if self.ppem == base.wrapping_add(self.state.delta_base).wrapping_add((argument >> 4) & 15) {
+                let steps    = (argument & 15) - 8;
+                let steps    = if steps >= 0 { steps + 1 } else { steps };
+                let distance = steps * 64 / (1 << self.state.delta_shift);
+
+                if let Some(value) = self.read_cvt(index) {
+                    self.write_cvt(index, value.wrapping_add(distance));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    //*************************************************************************
+    fn read_cvt(
+                &self,
+                index: i32
+            ) -> Option<i32> {
+        // Like FreeType, entries outside the table are quietly ignored.
+        usize::try_from(index).ok()
+            .and_then(|x|self.cvt.get(x))
+            .copied()
+    }
+
+    //*************************************************************************
+    fn write_cvt(
+                &mut self,
+                index: i32,
+                value: i32
+            ) {
+        if let Some(entry) = usize::try_from(index).ok().and_then(|x|self.cvt.get_mut(x)) {
+            *entry = value;
+        }
+    }
+
+    //*************************************************************************
+    fn displacement(
+                &self,
+                opcode: u8
+            ) -> Result<((usize, usize), i32), String> {
+        // SHP, SHC and SHZ move by as much as the reference point has moved.
+        let reference = if opcode & 1 == 0 {
+            self.point(1, self.state.rp[2] as i32)?
+        } else {
+            self.point(0, self.state.rp[1] as i32)?
+        };
+
+        Ok((reference, self.project(self.current(reference), self.original(reference))))
+    }
+
+    //*************************************************************************
+    fn interpolate(
+                &mut self,
+                x: bool
+            ) {
+        let zone  = &mut self.zones[1];
+        let axis  = |p: Point| if x { p.0 } else { p.1 };
+        let mut start = 0;
+
+        for &end in zone.ends.iter() {
+            if end < start || end >= zone.current.len() {
+                break;
+            }
+            let touched = (start..=end)
+                .filter(|&i|if x { zone.touched[i].0 } else { zone.touched[i].1 })
+                .collect::<Vec<usize>>();
+
+            // Untouched points between two touched ones are interpolated, a
+            // single touched point shifts the whole contour.
+            match touched.len() {
+                0 => {},
+                1 => {
+                    let t     = touched[0];
+                    let delta = axis(zone.current[t]).wrapping_sub(axis(zone.original[t]));
+
+                    for i in (start..=end).filter(|&i|i != t) {
+                        let point = &mut zone.current[i];
+                        if x { point.0 = point.0.wrapping_add(delta) } else { point.1 = point.1.wrapping_add(delta) }
+                    }
+                },
+                count => {
+                    for k in 0..count {
+                        let (mut t1, mut t2) = (touched[k], touched[(k + 1) % count]);
+                        let mut i = if t1 == end { start } else { t1 + 1 };
+                        let last  = t2;
+
+                        if axis(zone.unscaled[t1]) > axis(zone.unscaled[t2]) {
+                            (t1, t2) = (t2, t1);
+                        }
+                        let (u1, u2) = (axis(zone.unscaled[t1]), axis(zone.unscaled[t2]));
+                        let (o1, o2) = (axis(zone.original[t1]), axis(zone.original[t2]));
+                        let (c1, c2) = (axis(zone.current[t1]), axis(zone.current[t2]));
+                        let ratio    = if c1 == c2 || u1 == u2 {
+                            None
+                        } else {
+                            Some(mul_div(c2.wrapping_sub(c1), 0x10000, u2.wrapping_sub(u1)))
+                        };
+
+                        while i != last {
+                            let o = axis(zone.original[i]);
+                            let value = if o <= o1 {
+                                o.wrapping_add(c1.wrapping_sub(o1))
+                            } else if o >= o2 {
+                                o.wrapping_add(c2.wrapping_sub(o2))
+                            } else {
+                                match ratio {
+                                    Some(ratio) => c1.wrapping_add(mul_fix(axis(zone.unscaled[i]).wrapping_sub(u1), ratio)),
+                                    None        => c1
+                                }
+                            };
+                            if x { zone.current[i].0 = value } else { zone.current[i].1 = value }
+
+                            i = if i == end { start } else { i + 1 };
+                        }
+                    }
+                }
+            }
+            start = end + 1;
+        }
+    }
+
+    //*************************************************************************
+    fn start(
+                &mut self,
+                program: Program
+            ) {
+        // Glyph programs start from what the control program left, apart
+        // from the vectors, zones and rounding.
+        if program == Program::Glyph {
+            self.state.reset();
+        }
+        self.stack.clear();
+
+        let end = self.codes[program.index()].len();
+        self.calls.push(Frame { program, position: 0, start: 0, end, count: 1 });
+    }
+
+    //*************************************************************************
+    fn load(
+                &mut self,
+                glyph:  &GlyphPoints,
+                points: Option<Vec<Point>>
+            ) {
+        // A simple glyph is scaled here, a composite comes with its parts
+        // already hinted and in place, and serves as its own units.
+        self.units = if points.is_some() { 0x10000 } else { self.scale };
+
+        let (current, original, unscaled) = match points {
+            Some(points) => {
+                // A composite's phantom points are only rounded for its
+                // instructions.
+                let mut current = points.clone();
+                if !glyph.instructions.is_empty() {
+                    for point in current.iter_mut().rev().take(PHANTOM) {
+                        *point = (point.0.wrapping_add(32) & !63, point.1.wrapping_add(32) & !63);
+                    }
+                }
+                (current, points.clone(), points)
+            },
+            None         => {
+                let (origin, advance) = (glyph.origin.round() as i32, (glyph.origin + glyph.advance).round() as i32);
+                let original = glyph.points.iter()
+                    .map(|&(x, y)|(self.scale(x), self.scale(y)))
+                    .chain(self.phantom(glyph))
+                    .collect::<Vec<Point>>();
+                let mut current = original.clone();
+                for point in current.iter_mut().rev().take(PHANTOM) {
+                    *point = (point.0.wrapping_add(32) & !63, point.1.wrapping_add(32) & !63);
+                }
+                let unscaled = glyph.points.iter()
+                    .map(|&(x, y)|(x.round() as i32, y.round() as i32))
+                    .chain([(origin, 0), (advance, 0), (0, 0), (0, 0)])
+                    .collect();
+
+                (current, original, unscaled)
+            }
+        };
+
+        let mut on_curve = glyph.on_curve.clone();
+        on_curve.extend([true; PHANTOM]);
+
+        self.codes[2] = glyph.instructions.clone();
+        self.zones[1] = Zone {
+            touched:  vec![(false, false); current.len()],
+            original,
+            current,
+            unscaled,
+            ends:     glyph.ends.clone(),
+            on_curve
+        };
+    }
+
+    //*************************************************************************
+    fn step(&mut self) -> Result<Option<Trace>, String> {
+        // Programs that run off their end return.
+        let frame = loop {
+            let Some(frame) = self.calls.last().copied() else {
+                return Ok(None);
+            };
+            if frame.position < frame.end {
+                break frame;
+            }
+            self.finish();
+        };
+
+        let (operation, next) = bytecode::decode(&self.codes[frame.program.index()], frame.position)?;
+        self.frame()?.position = next;
+
+        self.execute(&operation, frame.program)?;
+
+        Ok(Some(Trace { program: frame.program, operation, depth: self.calls.len() - 1 }))
+    }
+
+    //*************************************************************************
+    fn execute(
+                &mut self,
+                operation: &Operation,
+                program:   Program
+            ) -> Result<(), String> {
+        let opcode = operation.opcode;
+
+        match opcode {
+            // Vectors
+            0x00..=0x05 => {
+                let vector = if opcode & 1 != 0 { (ONE, 0) } else { (0, ONE) };
+
+                if opcode < 0x04 {
+                    self.state.projection = vector;
+                    self.state.dual       = vector;
+                }
+                if !(0x02..0x04).contains(&opcode) {
+                    self.state.freedom = vector;
+                }
+            },
+            0x06..=0x09 => {
+                let p2 = self.pop()?;
+                let p1 = self.pop()?;
+                let (a, b) = (self.point(1, p1)?, self.point(2, p2)?);
+
+                if opcode < 0x08 {
+                    self.state.projection = self.line_vector(a, b, opcode & 1 != 0, false);
+                    self.state.dual       = self.state.projection;
+                } else {
+                    self.state.freedom = self.line_vector(a, b, opcode & 1 != 0, false);
+                }
+            },
+            0x0A | 0x0B => {
+                let y = self.pop()? as i16 as i32;
+                let x = self.pop()? as i16 as i32;
+
+                if let Some(vector) = normalize(x, y) {
+                    if opcode == 0x0A {
+                        self.state.projection = vector;
+                        self.state.dual       = vector;
+                    } else {
+                        self.state.freedom = vector;
+                    }
+                }
+            },
+            0x0C | 0x0D => {
+                let (x, y) = if opcode == 0x0C { self.state.projection } else { self.state.freedom };
+                self.stack.extend([x, y]);
+            },
+            0x0E => {
+                self.state.freedom = self.state.projection;
+            },
+            0x0F => {
+                let b1 = self.pop()?;
+                let b0 = self.pop()?;
+                let a1 = self.pop()?;
+                let a0 = self.pop()?;
+                let p  = self.pop()?;
+                let (a0, a1) = (self.current(self.point(1, a0)?), self.current(self.point(1, a1)?));
+                let (b0, b1) = (self.current(self.point(0, b0)?), self.current(self.point(0, b1)?));
+                let p        = self.point(2, p)?;
+
+                let (dax, day) = (a1.0.wrapping_sub(a0.0), a1.1.wrapping_sub(a0.1));
+                let (dbx, dby) = (b1.0.wrapping_sub(b0.0), b1.1.wrapping_sub(b0.1));
+                let (dx, dy)   = (b0.0.wrapping_sub(a0.0), b0.1.wrapping_sub(a0.1));
+                let cross      = mul_div(dax, dby.wrapping_neg(), 64).wrapping_add(mul_div(day, dbx, 64));
+                let dot        = mul_div(dax, dbx, 64).wrapping_add(mul_div(day, dby, 64));
+
+                // Lines within 3 degrees of parallel meet half way between
+                // their middles.
+                let at = if 19 * cross.unsigned_abs() as i64 > dot.unsigned_abs() as i64 {
+                    let value = mul_div(dx, dby.wrapping_neg(), 64).wrapping_add(mul_div(dy, dbx, 64));
+                    (a0.0.wrapping_add(mul_div(value, dax, cross)), a0.1.wrapping_add(mul_div(value, day, cross)))
+                } else {
+                    let average = |a: i32, b: i32, c: i32, d: i32|((a as i64 + b as i64 + c as i64 + d as i64) / 4) as i32;
+                    (average(a0.0, a1.0, b0.0, b1.0), average(a0.1, a1.1, b0.1, b1.1))
+                };
+                self.zones[p.0].current[p.1] = at;
+                self.zones[p.0].touched[p.1] = (true, true);
+            },
+
+            // Graphics state
+            0x10..=0x12 => {
+                self.state.rp[(opcode - 0x10) as usize] = self.pop()?.max(0) as usize;
+            },
+            0x13..=0x16 => {
+                let zone = self.pop()?;
+                if !(0..=1).contains(&zone) {
+                    return Err(format!("Zone {} does not exist.", zone));
+                }
+                match opcode {
+                    0x16 => self.state.zp = [zone as usize; 3],
+                    _    => self.state.zp[(opcode - 0x13) as usize] = zone as usize
+                }
+            },
+            0x17 => {
+                let count = self.pop()?;
+                if count < 0 {
+                    return Err(format!("Can't loop {} times.", count));
+                }
+                self.state.loop_count = count.min(0xFFFF);
+            },
+            0x18 => self.state.round        = Round::Grid,
+            0x19 => self.state.round        = Round::HalfGrid,
+            0x1A => self.state.min_distance = self.pop()?,
+            0x1B => {
+                self.skip(&[0x59])?;
+            },
+            0x1C => {
+                let offset = self.pop()?;
+                self.frame()?.position = (operation.offset as i64 + offset as i64).max(0) as usize;
+            },
+            0x1D => self.state.cut_in              = self.pop()?,
+            0x1E => self.state.single_width_cut_in = self.pop()?,
+            0x1F => {
+                let value = self.pop()?;
+                self.state.single_width = mul_fix(value, self.scale);
+            },
+
+            // Stack
+            0x20 => {
+                let value = *self.stack.last().ok_or("Stack underflow.")?;
+                self.stack.push(value);
+            },
+            0x21 => {
+                self.pop()?;
+            },
+            0x22 => self.stack.clear(),
+            0x23 => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                self.stack.extend([a, b]);
+            },
+            0x24 => self.stack.push(self.stack.len() as i32),
+            0x25 | 0x26 => {
+                let k     = self.pop()?;
+                let index = usize::try_from(k).ok()
+                    .filter(|&x|x >= 1 && x <= self.stack.len())
+                    .map(|x|self.stack.len() - x)
+                    .ok_or_else(||format!("Stack index {} is out of range.", k))?;
+
+                if opcode == 0x25 {
+                    self.stack.push(self.stack[index]);
+                } else {
+                    let value = self.stack.remove(index);
+                    self.stack.push(value);
+                }
+            },
+            0x27 => {
+                let p2 = self.pop()?;
+                let p1 = self.pop()?;
+                let (a, b)   = (self.point(1, p1)?, self.point(0, p2)?);
+                let distance = self.project(self.current(b), self.current(a)) / 2;
+
+                self.move_point(a, distance, true);
+                self.move_point(b, -distance, true);
+            },
+            0x29 => {
+                let p     = self.pop()?;
+                let point = self.point(0, p)?;
+                let (fx, fy) = self.state.freedom;
+                let touched  = &mut self.zones[point.0].touched[point.1];
+
+                if fx != 0 { touched.0 = false; }
+                if fy != 0 { touched.1 = false; }
+            },
+
+            // Functions
+            0x2A => {
+                let number   = self.pop()?;
+                let count    = self.pop()?;
+                let function = self.function(number)?;
+                self.call(function, count)?;
+            },
+            0x2B => {
+                let number   = self.pop()?;
+                let function = self.function(number)?;
+                self.call(function, 1)?;
+            },
+            0x2C => {
+                let number   = self.pop()?;
+                let function = self.define()?;
+                self.functions.insert(number, function);
+            },
+            0x2D => {
+                self.finish();
+            },
+            0x89 => {
+                let number   = self.pop()?;
+                let function = self.define()?;
+                self.definitions.insert(number as u8, function);
+            },
+
+            // Moving points
+            0x2E | 0x2F => {
+                let p        = self.pop()?;
+                let point    = self.point(0, p)?;
+                let position = self.project(self.current(point), (0, 0));
+                let distance = if opcode & 1 != 0 { self.round(position).wrapping_sub(position) } else { 0 };
+
+                self.move_point(point, distance, true);
+                self.state.rp[0] = point.1;
+                self.state.rp[1] = point.1;
+            },
+            0x30 | 0x31 => {
+                self.interpolate(opcode & 1 != 0);
+            },
+            0x32 | 0x33 => {
+                let (_, distance) = self.displacement(opcode)?;
+
+                for _ in 0..self.state.loop_count {
+                    let p = self.pop()?;
+                    let point = self.point(2, p)?;
+                    self.move_point(point, distance, true);
+                }
+                self.state.loop_count = 1;
+            },
+            0x34 | 0x35 => {
+                let (reference, distance) = self.displacement(opcode)?;
+                let contour = self.pop()?;
+                let zone    = self.state.zp[2];
+                let ends    = &self.zones[zone].ends;
+                let end     = *usize::try_from(contour).ok()
+                    .and_then(|x|ends.get(x))
+                    .ok_or_else(||format!("Contour {} does not exist.", contour))?;
+                let start   = if contour == 0 { 0 } else { ends[contour as usize - 1] + 1 };
+
+                for i in start..=end {
+                    if (zone, i) != reference {
+                        self.move_point((zone, i), distance, true);
+                    }
+                }
+            },
+            0x36 | 0x37 => {
+                let (reference, distance) = self.displacement(opcode)?;
+                let zone = self.pop()?;
+                if !(0..=1).contains(&zone) {
+                    return Err(format!("Zone {} does not exist.", zone));
+                }
+                // The zone popped is only checked, the points moved are
+                // in zp2.
+                let zone  = self.state.zp[2];
+                let count = match zone {
+                    0 => self.zones[0].current.len(),
+                    _ => self.zones[1].ends.last().map_or(0, |x|x + 1)
+                };
+                let (dx, dy) = self.shift(distance);
+
+                for i in 0..count {
+                    if (zone, i) != reference {
+                        let point = &mut self.zones[zone].current[i];
+                        *point = (point.0.wrapping_add(dx), point.1.wrapping_add(dy));
+                    }
+                }
+            },
+            0x38 => {
+                let distance = self.pop()?;
+                let (fx, fy) = self.state.freedom;
+                let (dx, dy) = (mul_14(distance, fx), mul_14(distance, fy));
+
+                for _ in 0..self.state.loop_count {
+                    let p = self.pop()?;
+                    let (zone, i) = self.point(2, p)?;
+                    let zone      = &mut self.zones[zone];
+
+                    zone.current[i] = (zone.current[i].0.wrapping_add(dx), zone.current[i].1.wrapping_add(dy));
+                    zone.touched[i].0 |= fx != 0;
+                    zone.touched[i].1 |= fy != 0;
+                }
+                self.state.loop_count = 1;
+            },
+            0x39 => {
+                let rp1 = self.point(0, self.state.rp[1] as i32)?;
+                let rp2 = self.point(1, self.state.rp[2] as i32)?;
+
+                let twilight       = self.state.zp.contains(&0);
+                let current_base   = self.current(rp1);
+                let original_range = self.original_units(rp2, rp1, twilight);
+                let current_range  = self.project(self.current(rp2), current_base);
+
+                for _ in 0..self.state.loop_count {
+                    let p     = self.pop()?;
+                    let point = self.point(2, p)?;
+
+                    let original = self.original_units(point, rp1, twilight);
+                    let current  = self.project(self.current(point), current_base);
+                    let wanted   = match (original, original_range) {
+                        (0, _) => 0,
+                        (_, 0) => original,
+                        _      => mul_div(original, current_range, original_range)
+                    };
+
+                    self.move_point(point, wanted.wrapping_sub(current), true);
+                }
+                self.state.loop_count = 1;
+            },
+            0x3A | 0x3B => {
+                let distance = self.pop()?;
+                let p        = self.pop()?;
+                let rp0      = self.point(0, self.state.rp[0] as i32)?;
+                let point    = self.point(1, p)?;
+
+                if point.0 == 0 {
+                    let (dx, dy) = self.shift(distance);
+                    let origin   = self.original(rp0);
+                    let at       = (origin.0.wrapping_add(dx), origin.1.wrapping_add(dy));
+                    self.zones[0].original[point.1] = at;
+                    self.zones[0].current[point.1]  = at;
+                }
+                let current = self.project(self.current(point), self.current(rp0));
+                self.move_point(point, distance.wrapping_sub(current), true);
+
+                self.state.rp[1] = self.state.rp[0];
+                self.state.rp[2] = point.1;
+                if opcode & 1 != 0 {
+                    self.state.rp[0] = point.1;
+                }
+            },
+            0x3C => {
+                let rp0 = self.point(0, self.state.rp[0] as i32)?;
+
+                for _ in 0..self.state.loop_count {
+                    let p        = self.pop()?;
+                    let point    = self.point(1, p)?;
+                    let distance = self.project(self.current(point), self.current(rp0));
+                    self.move_point(point, distance.wrapping_neg(), true);
+                }
+                self.state.loop_count = 1;
+            },
+            0x3D => self.state.round = Round::DoubleGrid,
+            0x3E | 0x3F => {
+                let index    = self.pop()?;
+                let p        = self.pop()?;
+                let point    = self.point(0, p)?;
+
+                self.state.rp[0] = point.1;
+                self.state.rp[1] = point.1;
+
+                let Some(mut wanted) = self.read_cvt(index) else {
+                    return Ok(());
+                };
+
+                // Points in the twilight zone start out on the freedom
+                // vector at the distance.
+                if point.0 == 0 {
+                    let (fx, fy) = self.state.freedom;
+                    let at = (mul_14(wanted, fx), mul_14(wanted, fy));
+                    self.zones[0].original[point.1] = at;
+                    self.zones[0].current[point.1]  = at;
+                }
+                let current = self.project(self.current(point), (0, 0));
+
+                if opcode & 1 != 0 {
+                    if gap(wanted, current) > self.state.cut_in as i64 {
+                        wanted = current;
+                    }
+                    wanted = self.round(wanted);
+                }
+                self.move_point(point, wanted.wrapping_sub(current), true);
+            },
+
+            // Pushing and storage
+            0x40 | 0x41 | 0xB0..=0xBF => {
+                self.stack.extend(operation.data.iter());
+            },
+            0x42 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                let index = usize::try_from(index).map_err(|_|format!("Storage {} is out of range.", index))?;
+
+                // The storage area is as large as maxp says, as in FreeType.
+                *self.storage.get_mut(index).ok_or_else(||format!("Storage {} is out of range.", index))? = value;
+            },
+            0x43 => {
+                let index = self.pop()?;
+                let value = usize::try_from(index).ok().and_then(|x|self.storage.get(x)).copied().unwrap_or(0);
+                self.stack.push(value);
+            },
+            0x44 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                self.write_cvt(index, value);
+            },
+            0x70 => {
+                let value = self.pop()?;
+                let index = self.pop()?;
+                self.write_cvt(index, mul_fix(value, self.scale));
+            },
+            0x45 => {
+                let index = self.pop()?;
+                let value = self.read_cvt(index).unwrap_or(0);
+                self.stack.push(value);
+            },
+
+            // Measuring
+            0x46 | 0x47 => {
+                let p     = self.pop()?;
+                let point = self.point(2, p)?;
+                let value = if opcode & 1 == 0 {
+                    self.project(self.current(point), (0, 0))
+                } else {
+                    self.dual_project(self.original(point), (0, 0))
+                };
+                self.stack.push(value);
+            },
+            0x48 => {
+                let wanted  = self.pop()?;
+                let p       = self.pop()?;
+                let point   = self.point(2, p)?;
+                let current = self.project(self.current(point), (0, 0));
+
+                self.move_point(point, wanted.wrapping_sub(current), true);
+                if point.0 == 0 {
+                    self.zones[0].original[point.1] = self.zones[0].current[point.1];
+                }
+            },
+            0x49 | 0x4A => {
+                let k = self.pop()?;
+                let l = self.pop()?;
+                let (a, b) = (self.point(0, l)?, self.point(1, k)?);
+                let value  = if opcode & 1 == 0 {
+                    self.original_distance(a, b, false)
+                } else {
+                    self.project(self.current(a), self.current(b))
+                };
+                self.stack.push(value);
+            },
+            0x4B | 0x4C => self.stack.push(self.ppem),
+            0x4D => self.state.auto_flip = true,
+            0x4E => self.state.auto_flip = false,
+            0x4F => {
+                self.pop()?;
+            },
+
+            // Logic and arithmetic
+            0x50..=0x55 | 0x5A | 0x5B | 0x60..=0x63 | 0x8B | 0x8C => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                let value = match opcode {
+                    0x50 => (a < b) as i32,
+                    0x51 => (a <= b) as i32,
+                    0x52 => (a > b) as i32,
+                    0x53 => (a >= b) as i32,
+                    0x54 => (a == b) as i32,
+                    0x55 => (a != b) as i32,
+                    0x5A => (a != 0 && b != 0) as i32,
+                    0x5B => (a != 0 || b != 0) as i32,
+                    0x60 => a.wrapping_add(b),
+                    0x61 => a.wrapping_sub(b),
+                    0x62 => {
+                        if b == 0 {
+                            return Err(String::from("Division by zero."));
+                        }
+                        (a as i64 * 64 / b as i64) as i32
+                    },
+                    0x63 => mul_div(a, b, 64),
+                    0x8B => a.max(b),
+                    _    => a.min(b)
+                };
+                self.stack.push(value);
+            },
+            0x56 | 0x57 => {
+                let value = self.pop()?;
+                let odd   = (self.round(value) & 64) != 0;
+                self.stack.push((odd == (opcode == 0x56)) as i32);
+            },
+            0x58 => {
+                if self.pop()? == 0 {
+                    self.skip(&[0x1B, 0x59])?;
+                }
+            },
+            0x59 => {},
+            0x5C => {
+                let value = self.pop()?;
+                self.stack.push((value == 0) as i32);
+            },
+            0x5D => self.delta_point(0)?,
+            0x71 => self.delta_point(16)?,
+            0x72 => self.delta_point(32)?,
+            0x73 => self.delta_value(0)?,
+            0x74 => self.delta_value(16)?,
+            0x75 => self.delta_value(32)?,
+            0x5E => self.state.delta_base  = self.pop()?,
+            0x5F => self.state.delta_shift = self.pop()?.clamp(0, 6),
+            0x64..=0x6F => {
+                let value = self.pop()?;
+                let value = match opcode {
+                    0x64 => value.wrapping_abs(),
+                    0x65 => value.wrapping_neg(),
+                    0x66 => value & !63,
+                    0x67 => value.wrapping_add(63) & !63,
+                    0x68..=0x6B => self.round(value),
+                    _           => value
+                };
+                self.stack.push(value);
+            },
+
+            // Rounding
+            0x76 | 0x77 => {
+                let value = self.pop()?;
+                self.state.round = Machine::super_round(value, if opcode == 0x76 { ONE } else { 0x2D41 });
+            },
+            0x78 | 0x79 => {
+                let condition = self.pop()?;
+                let offset    = self.pop()?;
+
+                if (condition != 0) == (opcode == 0x78) {
+                    self.frame()?.position = (operation.offset as i64 + offset as i64).max(0) as usize;
+                }
+            },
+            0x7A => self.state.round = Round::Off,
+            0x7C => self.state.round = Round::UpToGrid,
+            0x7D => self.state.round = Round::DownToGrid,
+            0x7E | 0x7F | 0x85 | 0x8D => {
+                self.pop()?;
+            },
+
+            // Points on and off the curve
+            0x80 => {
+                for _ in 0..self.state.loop_count {
+                    let p = self.pop()?;
+                    let i = self.glyph_point(p)?;
+                    self.zones[1].on_curve[i] = !self.zones[1].on_curve[i];
+                }
+                self.state.loop_count = 1;
+            },
+            0x81 | 0x82 => {
+                let high = self.pop()?;
+                let low  = self.pop()?;
+
+                for p in low..=high {
+                    let i = self.glyph_point(p)?;
+                    self.zones[1].on_curve[i] = opcode == 0x81;
+                }
+            },
+
+            // Others
+            0x86 | 0x87 => {
+                let p2 = self.pop()?;
+                let p1 = self.pop()?;
+                let (a, b) = (self.point(2, p1)?, self.point(1, p2)?);
+
+                self.state.projection = self.line_vector(a, b, opcode & 1 != 0, false);
+                self.state.dual       = self.line_vector(a, b, opcode & 1 != 0, true);
+            },
+            0x88 => {
+                // Version 35 is the Windows 3.1 rasterizer, black and white.
+                let selector = self.pop()?;
+                self.stack.push(if selector & 1 != 0 { 35 } else { 0 });
+            },
+            0x8A => {
+                let a = self.pop()?;
+                let b = self.pop()?;
+                let c = self.pop()?;
+                self.stack.extend([b, a, c]);
+            },
+            0x8E => {
+                let selector = self.pop()?;
+                let value    = self.pop()?;
+
+                if program == Program::Control && (1..=2).contains(&selector) {
+                    let bit = 1 << (selector - 1);
+                    self.state.instruct_control = (self.state.instruct_control & !bit) | (value & bit);
+                }
+            },
+            0x91 => {
+                // The default instance.
+                self.stack.push(0);
+            },
+            0x92 => self.stack.push(17),
+
+            // Direct and indirect relative points
+            0xC0..=0xFF => {
+                let index = if opcode >= 0xE0 { Some(self.pop()?) } else { None };
+                let p     = self.pop()?;
+                let rp0   = self.point(0, self.state.rp[0] as i32)?;
+                let point = self.point(1, p)?;
+
+                let cvt = match index {
+                    Some(-1) => Some(0),
+                    Some(x)  => self.read_cvt(x),
+                    None     => Some(0)
+                };
+                let Some(mut distance) = cvt else {
+                    self.state.rp[1] = self.state.rp[0];
+                    self.state.rp[2] = point.1;
+                    if opcode & 0x10 != 0 {
+                        self.state.rp[0] = point.1;
+                    }
+                    return Ok(());
+                };
+                if index.is_some() {
+                    if gap(distance, self.state.single_width) < self.state.single_width_cut_in as i64 {
+                        distance = if distance >= 0 { self.state.single_width } else { self.state.single_width.wrapping_neg() };
+                    }
+                    // A twilight point starts the distance away from rp0.
+                    if point.0 == 0 {
+                        let (fx, fy) = self.state.freedom;
+                        let origin   = self.original(rp0);
+                        let at       = (
+                            origin.0.wrapping_add(mul_14(distance, fx)),
+                            origin.1.wrapping_add(mul_14(distance, fy))
+                        );
+                        self.zones[0].original[point.1] = at;
+                        self.zones[0].current[point.1]  = at;
+                    }
+                }
+
+                let original = match index {
+                    Some(_) => self.dual_project(self.original(point), self.original(rp0)),
+                    None    => self.original_distance(point, rp0, false)
+                };
+                let current  = self.project(self.current(point), self.current(rp0));
+
+                match index {
+                    Some(_) => {
+                        if self.state.auto_flip && (original ^ distance) < 0 {
+                            distance = distance.wrapping_neg();
+                        }
+                        if opcode & 0x04 != 0 {
+                            if point.0 == rp0.0 && gap(distance, original) > self.state.cut_in as i64 {
+                                distance = original;
+                            }
+                            distance = self.round(distance);
+                        }
+                    },
+                    None => {
+                        distance = original;
+                        if gap(distance, self.state.single_width) < self.state.single_width_cut_in as i64 {
+                            distance = if distance >= 0 { self.state.single_width } else { self.state.single_width.wrapping_neg() };
+                        }
+                        if opcode & 0x04 != 0 {
+                            distance = self.round(distance);
+                        }
+                    }
+                }
+
+                if opcode & 0x08 != 0 {
+                    let minimum = self.state.min_distance;
+                    if original >= 0 {
+                        distance = distance.max(minimum);
+                    } else {
+                        distance = distance.min(minimum.wrapping_neg());
+                    }
+                }
+                self.move_point(point, distance.wrapping_sub(current), true);
+
+                self.state.rp[1] = self.state.rp[0];
+                self.state.rp[2] = point.1;
+                if opcode & 0x10 != 0 {
+                    self.state.rp[0] = point.1;
+                }
+            },
+
+            opcode => {
+                // Instructions the font defined itself.
+                let function = self.definitions.get(&opcode)
+                    .copied()
+                    .ok_or_else(||format!("Unknown instruction 0x{:02X}.", opcode))?;
+                self.call(function, 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//*****************************************************************************
+// What the glyph looked like after a number of steps.
+pub struct Hinted {
+    pub machine: Machine,
+    pub step:    usize,
+    pub trace:   Vec<Trace>,
+    pub error:   Option<(usize, String)>
+}
+
+impl Hinted {
+    //*************************************************************************
+    pub fn next(&self) -> Option<&Trace> {
+        self.trace.get(self.step)
+    }
+
+    //*************************************************************************
+    pub fn describe(
+                &self,
+                trace: &Trace
+            ) -> String {
+        format!("{} {:5} {}", trace.program, trace.operation.offset, trace.operation)
+    }
+
+    //*************************************************************************
+    pub fn to_font(
+                &self,
+                (x, y): Point
+            ) -> (f32, f32) {
+        let scale = self.machine.scale as f32 / 65536.0;
+
+        (x as f32 / scale, y as f32 / scale)
+    }
+
+    //*************************************************************************
+    pub fn moves(
+                &self,
+                glyph: &GlyphPoints
+            ) -> Vec<(f32, f32)> {
+        // How far each point ended up from where scaling alone put it, in
+        // pixels.
+        let scale = self.machine.scale as f32 / 65536.0 / 64.0;
+
+        self.points(glyph).points.iter()
+            .zip(glyph.points.iter())
+            .map(|(a, b)|((a.0 - b.0) * scale, (a.1 - b.1) * scale))
+            .collect()
+    }
+
+    //*************************************************************************
+    fn shift(&self) -> i32 {
+        // The outline moves with the origin, if the hinting moved it.
+        let zone = &self.machine.zones[1];
+
+        match zone.current.len().checked_sub(PHANTOM) {
+            Some(count) => zone.current[count].0.wrapping_sub(zone.original[count].0),
+            None        => 0
+        }
+    }
+
+    //*************************************************************************
+    pub fn position(
+                &self,
+                zone:  usize,
+                point: usize
+            ) -> Option<(f32, f32)> {
+        let (x, y) = *self.machine.zones.get(zone)?.current.get(point)?;
+        let shift  = if zone == 1 { self.shift() } else { 0 };
+
+        Some(self.to_font((x.wrapping_sub(shift), y)))
+    }
+
+    //*************************************************************************
+    pub fn points(
+                &self,
+                glyph: &GlyphPoints
+            ) -> GlyphPoints {
+        // Until the glyph's own program starts the outline is only scaled.
+        let zone  = &self.machine.zones[1];
+        let count = glyph.points.len();
+
+        if zone.current.len() != count + PHANTOM || self.machine.codes[2] != glyph.instructions {
+            return glyph.clone();
+        }
+
+        let (origin, advance) = (zone.current[count].0, zone.current[count + 1].0);
+        let shift             = self.shift();
+
+        GlyphPoints {
+            points:       zone.current[..count].iter().map(|&(x, y)|self.to_font((x.wrapping_sub(shift), y))).collect(),
+            on_curve:     zone.on_curve[..count].to_vec(),
+            origin:       glyph.origin,
+            advance:      self.to_font((advance.wrapping_sub(origin), 0)).0,
+            ends:         glyph.ends.clone(),
+            instructions: glyph.instructions.clone(),
+            parts:        glyph.parts.clone()
+        }
+    }
+}
+
+//*****************************************************************************
+pub struct Interpreter {
+    font:         Vec<u8>,
+    control:      Vec<u8>,
+    values:       Vec<i16>,
+    twilight:     usize,
+    storage:      usize,
+    units_per_em: i32
+}
+
+impl Interpreter {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Option<Self>, String> {
+        if sfnt::table(data, b"glyf").is_none() {
+            return Ok(None);
+        }
+
+        let head = sfnt::table(data, b"head").ok_or("Missing head table.")?;
+        let maxp = sfnt::table(data, b"maxp").ok_or("Missing maxp table.")?;
+        let cvt  = sfnt::table(data, b"cvt ").unwrap_or_default();
+
+        let values = (0..cvt.len() / 2)
+            .map(|i|read_u16(cvt, i * 2).map(|x|x as i16))
+            .collect::<Result<Vec<i16>, String>>()?;
+
+        Ok(Some(Self {
+            font:         sfnt::table(data, b"fpgm").unwrap_or_default().to_vec(),
+            control:      sfnt::table(data, b"prep").unwrap_or_default().to_vec(),
+            twilight:     read_u16(maxp, 16).unwrap_or(0) as usize,
+            storage:      read_u16(maxp, 18).unwrap_or(0) as usize,
+            units_per_em: read_u16(head, 18)?.max(1) as i32,
+            values
+        }))
+    }
+
+    //*************************************************************************
+    pub fn hint(
+                &self,
+                glyph: &GlyphPoints,
+                ppem:  i32,
+                step:  Option<usize>
+            ) -> Hinted {
+        let scale = mul_div(ppem * 64, 0x10000, self.units_per_em);
+
+        let mut machine = Machine {
+            state:       GraphicsState::new(),
+            stack:       Vec::new(),
+            cvt:         self.values.iter().map(|&x|mul_fix(x as i32 * 64, scale >> 6)).collect(),
+            storage:     vec![0; self.storage],
+            zones:       [Zone::new(self.twilight), Zone::default()],
+            calls:       Vec::new(),
+            codes:       [self.font.clone(), self.control.clone(), Vec::new()],
+            functions:   HashMap::new(),
+            definitions: HashMap::new(),
+            ppem,
+            scale,
+            units:       scale
+        };
+
+        let mut trace    = Vec::new();
+        let mut snapshot = None;
+
+        let result = Interpreter::run(&mut machine, glyph, step, &mut trace, &mut snapshot);
+        let error  = result.err().map(|x|(trace.len(), x));
+        let step   = step.unwrap_or(trace.len()).min(trace.len());
+
+        Hinted {
+            machine: snapshot.unwrap_or(machine),
+            step,
+            trace,
+            error
+        }
+    }
+
+    //*************************************************************************
+    fn run(
+                machine:  &mut Machine,
+                glyph:    &GlyphPoints,
+                step:     Option<usize>,
+                trace:    &mut Vec<Trace>,
+                snapshot: &mut Option<Machine>
+            ) -> Result<(), String> {
+        // The font program only defines functions, it isn't stepped through.
+        machine.start(Program::Font);
+        let mut finished = false;
+        for _ in 0..MAX_STEPS {
+            if machine.step()?.is_none() {
+                finished = true;
+                break;
+            }
+        }
+        if !finished {
+            machine.calls.clear();
+            return Err(String::from("The font program ran too long."));
+        }
+
+        Interpreter::trace(machine, Program::Control, step, trace, snapshot)?;
+
+        if machine.state.instruct_control & 1 != 0 {
+            return Ok(());
+        }
+
+        let control = machine.clone();
+        Interpreter::run_glyph(machine, &control, glyph, step, trace, snapshot).map(|_|())
+    }
+
+    //*************************************************************************
+    fn run_glyph(
+                machine:  &mut Machine,
+                control:  &Machine,
+                glyph:    &GlyphPoints,
+                step:     Option<usize>,
+                trace:    &mut Vec<Trace>,
+                snapshot: &mut Option<Machine>
+            ) -> Result<Vec<Point>, String> {
+        // The components of a composite are hinted first, then moved into
+        // place for the composite's own instructions.
+        if glyph.parts.is_empty() {
+            machine.load(glyph, None);
+        } else {
+            let mut points  = Vec::new();
+            let mut phantom = machine.phantom(glyph);
+            for part in glyph.parts.iter() {
+                let hinted = Interpreter::run_glyph(machine, control, &part.points, step, trace, snapshot)?;
+
+                let (xx, xy, yx, yy) = part.transform;
+                let [xx, xy, yx, yy] = [xx, xy, yx, yy].map(|x|(x * 65536.0).round() as i32);
+                let mut offset       = (machine.scale(part.offset.0), machine.scale(part.offset.1));
+                if part.round {
+                    offset = (offset.0.wrapping_add(32) & !63, offset.1.wrapping_add(32) & !63);
+                }
+
+                let count = part.points.points.len();
+                points.extend(hinted[..count].iter().map(|&(x, y)|(
+                    mul_fix(x, xx).wrapping_add(mul_fix(y, xy)).wrapping_add(offset.0),
+                    mul_fix(x, yx).wrapping_add(mul_fix(y, yy)).wrapping_add(offset.1)
+                )));
+                if part.metrics {
+                    phantom.copy_from_slice(&hinted[count..]);
+                }
+            }
+            points.extend(phantom);
+            machine.load(glyph, Some(points));
+        }
+
+        // Every glyph program starts from what the control program left,
+        // whatever the ones before it changed.
+        machine.state    = control.state.clone();
+        machine.cvt      = control.cvt.clone();
+        machine.storage  = control.storage.clone();
+        machine.zones[0] = control.zones[0].clone();
+
+        Interpreter::trace(machine, Program::Glyph, step, trace, snapshot)?;
+
+        Ok(machine.zones[1].current.clone())
+    }
+
+    //*************************************************************************
+    fn trace(
+                machine:  &mut Machine,
+                program:  Program,
+                step:     Option<usize>,
+                trace:    &mut Vec<Trace>,
+                snapshot: &mut Option<Machine>
+            ) -> Result<(), String> {
+        if machine.codes[program.index()].is_empty() {
+            return Ok(());
+        }
+        machine.start(program);
+
+        loop {
+            // Only a machine about to run an instruction is worth showing.
+            let before = (step == Some(trace.len()) && snapshot.is_none()).then(||machine.clone());
+
+            match machine.step()? {
+                Some(x) => trace.push(x),
+                None    => break
+            }
+            if before.is_some() {
+                *snapshot = before;
+            }
+            if trace.len() > MAX_STEPS {
+                return Err(String::from("The program ran too long."));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pushes i32::MIN, which only arithmetic can make.
+    const MINIMUM: [u8; 9] = [0xB8, 0x80, 0x00, 0x20, 0x63, 0xB8, 0x20, 0x00, 0x63];
+
+    //*************************************************************************
+    fn hint(
+                font:         &[u8],
+                instructions: &[u8]
+            ) -> Hinted {
+        let interpreter = Interpreter {
+            font:         font.to_vec(),
+            control:      Vec::new(),
+            values:       vec![0; 4],
+            twilight:     5,
+            storage:      4,
+            units_per_em: 1000
+        };
+        let square = GlyphPoints {
+            points:       vec![(0.0, 0.0), (0.0, 500.0), (500.0, 500.0), (500.0, 0.0)],
+            on_curve:     vec![true; 4],
+            ends:         vec![3],
+            instructions: instructions.to_vec(),
+            origin:       0.0,
+            advance:      600.0,
+            parts:        Vec::new()
+        };
+
+        interpreter.hint(&square, 10, None)
+    }
+
+    //*************************************************************************
+    fn error(
+                hinted: &Hinted
+            ) -> Option<&str> {
+        hinted.error.as_ref().map(|x|x.1.as_str())
+    }
+
+    //*************************************************************************
+    #[test]
+    fn absolute_and_negative_values_wrap() {
+        let hinted = hint(&[], &[&MINIMUM[..], &[0x64]].concat());
+        assert_eq!(error(&hinted), None);
+        assert_eq!(hinted.machine.stack, vec![i32::MIN]);
+
+        let hinted = hint(&[], &[&MINIMUM[..], &[0x65]].concat());
+        assert_eq!(error(&hinted), None);
+        assert_eq!(hinted.machine.stack, vec![i32::MIN]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn arithmetic_matches_freetype() {
+        // 1.5 + 2, 3 * 2.5, 3 / 2 and -2.25 floored, ceiled and negated.
+        let hinted = hint(&[], &[
+            0xB1, 96, 128, 0x60,
+            0xB1, 192, 160, 0x63,
+            0xB1, 192, 128, 0x62,
+            0xB8, 0xFF, 0x70, 0x66,
+            0xB8, 0xFF, 0x70, 0x67,
+            0xB8, 0xFF, 0x70, 0x65
+        ]);
+        assert_eq!(error(&hinted), None);
+        assert_eq!(hinted.machine.stack, vec![224, 480, 96, -192, -128, 144]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn division_by_zero_is_an_error() {
+        let hinted = hint(&[], &[0xB1, 64, 0, 0x62]);
+        assert_eq!(error(&hinted), Some("Division by zero."));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn flipping_points_changes_the_glyph_zone() {
+        let hinted = hint(&[], &[0xB0, 1, 0x80, 0xB1, 2, 3, 0x82]);
+        assert_eq!(error(&hinted), None);
+        assert_eq!(&hinted.machine.zones[1].on_curve[..4], &[true, false, false, false]);
+
+        // The twilight zone in zp0 doesn't change which zone is flipped.
+        let hinted = hint(&[], &[0xB1, 3, 0, 0x13, 0x80, 0xB1, 0, 0, 0x82]);
+        assert_eq!(error(&hinted), None);
+        assert_eq!(&hinted.machine.zones[1].on_curve[..4], &[false, true, true, false]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn flipping_points_past_the_glyph_is_an_error() {
+        let hinted = hint(&[], &[0xB1, 30, 0, 0x13, 0x80]);
+        assert_eq!(error(&hinted), Some("Point 30 is outside zone 1."));
+
+        let hinted = hint(&[], &[0xB1, 2, 30, 0x81]);
+        assert_eq!(error(&hinted), Some("Point 8 is outside zone 1."));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn storage_is_limited_to_maxp() {
+        let hinted = hint(&[], &[0xB1, 3, 7, 0x42, 0xB0, 3, 0x43]);
+        assert_eq!(error(&hinted), None);
+        assert_eq!(hinted.machine.stack, vec![7]);
+
+        let hinted = hint(&[], &[0xB1, 4, 7, 0x42]);
+        assert_eq!(error(&hinted), Some("Storage 4 is out of range."));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn shifting_points_wraps() {
+        // Twice i32::MIN moves the point back where it was.
+        let shift  = [&[0xB0, 1][..], &MINIMUM[..], &[0x38]].concat();
+        let hinted = hint(&[], &[&shift[..], &shift[..]].concat());
+        assert_eq!(error(&hinted), None);
+        assert_eq!(hinted.machine.zones[1].current[1].0, 0);
+        assert_eq!(hinted.machine.zones[1].touched[1], (true, false));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn interpolating_after_a_huge_shift_wraps() {
+        // Point 1 by i32::MAX then IUP[x] shifts the other points by it.
+        let shift  = [&[0xB0, 1][..], &MINIMUM[..], &[0xB0, 1, 0x61, 0x38]].concat();
+        let hinted = hint(&[], &[&shift[..], &[0x31]].concat());
+        assert_eq!(error(&hinted), None);
+
+        // Point 3 by i32::MIN as well so the others lie between two.
+        let other  = [&[0xB0, 3][..], &MINIMUM[..], &[0x38]].concat();
+        let hinted = hint(&[], &[&shift[..], &other[..], &[0x31, 0x30]].concat());
+        assert_eq!(error(&hinted), None);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn delta_base_wraps() {
+        // i32::MAX as the delta base, then a delta at the highest ppem.
+        let hinted = hint(&[], &[&MINIMUM[..], &[0xB0, 1, 0x61, 0x5E, 0xB2, 0xF0, 1, 1, 0x71]].concat());
+        assert_eq!(error(&hinted), None);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn endless_font_program_is_an_error() {
+        // A jump back to the push before it.
+        let hinted = hint(&[0xB8, 0xFF, 0xFD, 0x1C], &[]);
+        assert_eq!(error(&hinted), Some("The font program ran too long."));
+        assert!(hinted.machine.calls.is_empty());
+    }
+}
@@ -0,0 +1,339 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::collections::BTreeMap;
+
+use crate::{
+    layout::{Classes, Coverage, Layout},
+    sfnt::{self, read_u16, read_u32}
+};
+
+//*****************************************************************************
+const PAIR_ADJUSTMENT: u16 = 2;
+const EXTENSION:       u16 = 9;
+
+//*****************************************************************************
+fn value_size(
+            format: u16
+        ) -> usize {
+    (format & 0x00FF).count_ones() as usize * 2
+}
+
+//*****************************************************************************
+fn read_value(
+            data:   &[u8],
+            offset: usize,
+            format: u16,
+            flag:   u16
+        ) -> Result<f32, String> {
+    // Fields are only present when their flag is set, in flag order.
+    if format & flag == 0 {
+        return Ok(0.0);
+    }
+    let position = offset + (format & (flag - 1)).count_ones() as usize * 2;
+
+    read_u16(data, position).map(|x|x as i16 as f32)
+}
+
+//*****************************************************************************
+// The first glyph moves the second one with its advance, the second can
+// also place itself.
+fn read_pair(
+            data:    &[u8],
+            offset:  usize,
+            formats: (u16, u16)
+        ) -> Result<f32, String> {
+    let first  = read_value(data, offset, formats.0, 0x0004)?;
+    let second = read_value(data, offset + value_size(formats.0), formats.1, 0x0001)?;
+
+    Ok(first + second)
+}
+
+//*****************************************************************************
+enum Pairs {
+    Glyphs {
+        coverage: Coverage,
+        pairs:    Vec<Vec<(u16, f32)>>
+    },
+    Classes {
+        coverage: Coverage,
+        left:     Classes,
+        right:    Classes,
+        count:    usize,
+        values:   Vec<f32>
+    }
+}
+
+impl Pairs {
+    //*************************************************************************
+    fn read(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Self, String> {
+        let format   = read_u16(data, offset)?;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+        let formats  = (read_u16(data, offset + 4)?, read_u16(data, offset + 6)?);
+        let size     = value_size(formats.0) + value_size(formats.1);
+
+        match format {
+            1 => {
+                let count = read_u16(data, offset + 8)? as usize;
+                let pairs = (0..count)
+                    .map(|i|{
+                        let set   = offset + read_u16(data, offset + 10 + i * 2)? as usize;
+                        let count = read_u16(data, set)? as usize;
+
+                        (0..count)
+                            .map(|j|{
+                                let position = set + 2 + j * (2 + size);
+                                Ok((read_u16(data, position)?, read_pair(data, position + 2, formats)?))
+                            })
+                            .collect::<Result<Vec<(u16, f32)>, String>>()
+                    })
+                    .collect::<Result<Vec<Vec<(u16, f32)>>, String>>()?;
+
+                Ok(Pairs::Glyphs { coverage, pairs })
+            },
+            2 => {
+                let left   = Classes::read(data, offset + read_u16(data, offset + 8)? as usize)?;
+                let right  = Classes::read(data, offset + read_u16(data, offset + 10)? as usize)?;
+                let rows   = read_u16(data, offset + 12)? as usize;
+                let count  = read_u16(data, offset + 14)? as usize;
+                let values = (0..rows * count)
+                    .map(|i|read_pair(data, offset + 16 + i * size, formats))
+                    .collect::<Result<Vec<f32>, String>>()?;
+
+                Ok(Pairs::Classes { coverage, left, right, count, values })
+            },
+            _ => Err(format!("Unknown pair adjustment format {}.", format))
+        }
+    }
+
+    //*************************************************************************
+    fn get(
+                &self,
+                left:  u16,
+                right: u16
+            ) -> Option<f32> {
+        match self {
+            Pairs::Glyphs { coverage, pairs } => {
+                pairs.get(coverage.index(left)?)?
+                    .iter()
+                    .find(|x|x.0 == right)
+                    .map(|x|x.1)
+            },
+            Pairs::Classes { coverage, left: classes, right: others, count, values } => {
+                coverage.index(left)?;
+
+                let row    = classes.class(left) as usize;
+                let column = others.class(right) as usize;
+                values.get(row * count + column).copied()
+            }
+        }
+    }
+
+    //*************************************************************************
+    fn covers_all(
+                &self,
+                left: u16
+            ) -> bool {
+        // Classes give every right glyph a value, so no later subtable is
+        // ever reached for this left glyph.
+        match self {
+            Pairs::Glyphs { .. }             => false,
+            Pairs::Classes { coverage, .. } => coverage.index(left).is_some()
+        }
+    }
+
+    //*************************************************************************
+    fn pairs(
+                &self,
+                left: u16
+            ) -> Vec<(u16, f32)> {
+        match self {
+            Pairs::Glyphs { coverage, pairs } => {
+                coverage.index(left)
+                    .and_then(|x|pairs.get(x))
+                    .cloned()
+                    .unwrap_or_default()
+            },
+            Pairs::Classes { coverage, left: classes, right: others, count, values } => {
+                if coverage.index(left).is_none() {
+                    return Vec::new();
+                }
+                let row = classes.class(left) as usize;
+
+                // Class 0 holds every glyph not listed, so only the listed
+                // classes make pairs. Each glyph pairs once however many
+                // ranges list it.
+                let mut pairs: BTreeMap<u16, f32> = BTreeMap::new();
+                for (x, value) in (1..*count).filter_map(|x|values.get(row * count + x).map(|&value|(x, value))) {
+                    if value == 0.0 {
+                        continue;
+                    }
+                    for (start, end) in others.ranges(x as u16) {
+                        for glyph in start..=end {
+                            pairs.entry(glyph).or_insert(value);
+                        }
+                    }
+                }
+                pairs.into_iter().collect()
+            }
+        }
+    }
+}
+
+//*****************************************************************************
+// The pair adjustments of each lookup add up, within a lookup the first
+// subtable with the pair wins.
+#[derive(Default)]
+pub struct Kerning {
+    lookups: Vec<Vec<Pairs>>
+}
+
+impl Kerning {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        // GPOS replaces the kern table when it has any kerning.
+        if let Some(table) = sfnt::table(data, b"GPOS") {
+            let kerning = Kerning::read_positioning(table)?;
+
+            if !kerning.lookups.is_empty() {
+                return Ok(kerning);
+            }
+        }
+
+        match sfnt::table(data, b"kern") {
+            Some(table) => Kerning::read_kern(table),
+            None        => Ok(Self::default())
+        }
+    }
+
+    //*************************************************************************
+    fn read_positioning(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let layout  = Layout::read(data, EXTENSION)?;
+        let lookups = layout.feature_lookups("kern")
+            .into_iter()
+            .filter_map(|x|layout.lookups.get(x as usize))
+            .filter(|x|x.kind == PAIR_ADJUSTMENT)
+            .map(|x|x.subtables.iter()
+                .map(|&x|Pairs::read(data, x))
+                .collect::<Result<Vec<Pairs>, String>>()
+            )
+            .collect::<Result<Vec<Vec<Pairs>>, String>>()?;
+
+        Ok(Self { lookups })
+    }
+
+    //*************************************************************************
+    fn read_kern(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        // The Apple version has a 32 bit header and subtable header.
+        let apple    = read_u16(data, 0)? == 1;
+        let count    = if apple { read_u32(data, 4)? as usize } else { read_u16(data, 2)? as usize };
+        let mut next = if apple { 8 } else { 4 };
+        let mut lookups = Vec::new();
+
+        for _ in 0..count {
+            let (length, format, horizontal, header) = if apple {
+                let coverage = read_u16(data, next + 4)?;
+                (read_u32(data, next)? as usize, coverage & 0x00FF, coverage & 0xE000 == 0, 8)
+            } else {
+                let coverage = read_u16(data, next + 4)?;
+                (read_u16(data, next + 2)? as usize, coverage >> 8, coverage & 0x0005 == 0x0001, 6)
+            };
+
+            if format != 0 {
+                next += length.max(header);
+                continue;
+            }
+
+            // Big tables overflow the 16 bit length, the pair count is the
+            // one to go by.
+            let start = next + header;
+            let pairs = read_u16(data, start)? as usize;
+            next      = start + 8 + pairs * 6;
+
+            if horizontal {
+                let mut map: BTreeMap<u16, Vec<(u16, f32)>> = BTreeMap::new();
+
+                for i in 0..pairs {
+                    let position = start + 8 + i * 6;
+                    map.entry(read_u16(data, position)?)
+                        .or_default()
+                        .push((read_u16(data, position + 2)?, read_u16(data, position + 4)? as i16 as f32));
+                }
+
+                let glyphs = map.keys().copied().collect();
+                lookups.push(vec![
+                    Pairs::Glyphs {
+                        coverage: Coverage::from_glyphs(glyphs),
+                        pairs:    map.into_values().collect()
+                    }
+                ]);
+            }
+        }
+
+        Ok(Self { lookups })
+    }
+
+    //*************************************************************************
+    pub fn get(
+                &self,
+                left:  u16,
+                right: u16
+            ) -> f32 {
+        self.lookups.iter()
+            .filter_map(|x|x.iter().find_map(|x|x.get(left, right)))
+            .sum()
+    }
+
+    //*************************************************************************
+    pub fn pairs(
+                &self,
+                left: u16
+            ) -> Vec<(u16, f32)> {
+        let mut result: BTreeMap<u16, f32> = BTreeMap::new();
+
+        for lookup in self.lookups.iter() {
+            let mut found: BTreeMap<u16, f32> = BTreeMap::new();
+
+            for subtable in lookup.iter() {
+                for (right, value) in subtable.pairs(left) {
+                    found.entry(right).or_insert(value);
+                }
+                if subtable.covers_all(left) {
+                    break;
+                }
+            }
+            for (right, value) in found {
+                *result.entry(right).or_default() += value;
+            }
+        }
+
+        result.into_iter().collect()
+    }
+}
@@ -0,0 +1,467 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::sfnt::{self, read_u16, read_u32};
+
+//*****************************************************************************
+// The glyphs a subtable applies to, as ranges of glyphs with the coverage
+// index of the first.
+pub struct Coverage {
+    ranges: Vec<(u16, u16, u16)>
+}
+
+impl Coverage {
+    //*************************************************************************
+    pub fn read(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Self, String> {
+        let format = read_u16(data, offset)?;
+        let count  = read_u16(data, offset + 2)? as usize;
+        let mut ranges = Vec::new();
+
+        match format {
+            1 => {
+                for i in 0..count {
+                    let glyph = read_u16(data, offset + 4 + i * 2)?;
+                    ranges.push((glyph, glyph, i as u16));
+                }
+            },
+            2 => {
+                for i in 0..count {
+                    let position = offset + 4 + i * 6;
+                    ranges.push((
+                        read_u16(data, position)?,
+                        read_u16(data, position + 2)?,
+                        read_u16(data, position + 4)?
+                    ));
+                }
+            },
+            _ => return Err(format!("Unknown coverage format {}.", format))
+        }
+
+        Ok(Self { ranges })
+    }
+
+    //*************************************************************************
+    pub fn from_glyphs(
+                glyphs: Vec<u16>
+            ) -> Self {
+        Self { ranges: glyphs.into_iter().enumerate().map(|(i, x)|(x, x, i as u16)).collect() }
+    }
+
+    //*************************************************************************
+    pub fn index(
+                &self,
+                glyph: u16
+            ) -> Option<usize> {
+        // The ranges are sorted, so the glyph can only be in the last one
+        // starting at or before it.
+        let count = self.ranges.partition_point(|x|x.0 <= glyph);
+        let &(start, end, index) = self.ranges.get(count.checked_sub(1)?)?;
+
+        (glyph <= end).then(||index as usize + (glyph - start) as usize)
+    }
+}
+
+//*****************************************************************************
+// Ranges of glyphs sharing a class, glyphs not listed are in class 0.
+pub struct Classes {
+    ranges: Vec<(u16, u16, u16)>
+}
+
+impl Classes {
+    //*************************************************************************
+    pub fn read(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Self, String> {
+        let format = read_u16(data, offset)?;
+        let mut ranges = Vec::new();
+
+        match format {
+            1 => {
+                let start = read_u16(data, offset + 2)?;
+                let count = read_u16(data, offset + 4)?;
+
+                for i in 0..count {
+                    let glyph = start.wrapping_add(i);
+                    ranges.push((glyph, glyph, read_u16(data, offset + 6 + i as usize * 2)?));
+                }
+            },
+            2 => {
+                let count = read_u16(data, offset + 2)? as usize;
+
+                for i in 0..count {
+                    let position = offset + 4 + i * 6;
+                    ranges.push((
+                        read_u16(data, position)?,
+                        read_u16(data, position + 2)?,
+                        read_u16(data, position + 4)?
+                    ));
+                }
+            },
+            _ => return Err(format!("Unknown class format {}.", format))
+        }
+
+        Ok(Self { ranges })
+    }
+
+    //*************************************************************************
+    pub fn class(
+                &self,
+                glyph: u16
+            ) -> u16 {
+        self.ranges.iter()
+            .find(|&&(start, end, _)|start <= glyph && glyph <= end)
+            .map(|x|x.2)
+            .unwrap_or(0)
+    }
+
+    //*************************************************************************
+    pub fn ranges(
+                &self,
+                class: u16
+            ) -> impl Iterator<Item = (u16, u16)> + '_ {
+        self.ranges.iter()
+            .filter(move |x|x.2 == class)
+            .map(|&(start, end, _)|(start, end))
+    }
+}
+
+//*****************************************************************************
+pub struct Language {
+    pub tag:      String,
+    pub required: Option<u16>,
+    pub features: Vec<u16>
+}
+
+//*****************************************************************************
+// The default language comes first, as "dflt".
+pub struct Script {
+    pub tag:       String,
+    pub languages: Vec<Language>
+}
+
+//*****************************************************************************
+pub struct Feature {
+    pub tag:     String,
+    pub lookups: Vec<u16>
+}
+
+//*****************************************************************************
+// Lookup flags choosing the glyphs a lookup passes over.
+const IGNORE_BASE_GLYPHS:     u16 = 0x0002;
+const IGNORE_LIGATURES:       u16 = 0x0004;
+const IGNORE_MARKS:           u16 = 0x0008;
+const USE_MARK_FILTERING_SET: u16 = 0x0010;
+
+//*****************************************************************************
+// The glyph classes of GDEF.
+const BASE:     u16 = 1;
+const LIGATURE: u16 = 2;
+const MARK:     u16 = 3;
+
+//*****************************************************************************
+pub struct Lookup {
+    pub kind:      u16,
+    pub flags:     u16,
+    pub subtables: Vec<usize>,
+    pub mark_set:  Option<u16>
+}
+
+//*****************************************************************************
+// The parts of GDEF that decide which glyphs a lookup ignores.
+#[derive(Default)]
+pub struct Definitions {
+    glyph_classes: Option<Classes>,
+    mark_classes:  Option<Classes>,
+    mark_sets:     Vec<Coverage>
+}
+
+impl Definitions {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let Some(table) = sfnt::table(data, b"GDEF") else {
+            return Ok(Self::default());
+        };
+
+        let classes = |position: usize|match read_u16(table, position)? {
+            0      => Ok(None),
+            offset => Classes::read(table, offset as usize).map(Some)
+        };
+        let glyph_classes = classes(4)?;
+        let mark_classes  = classes(10)?;
+
+        // The mark glyph sets came with version 1.2.
+        let mut mark_sets = Vec::new();
+        let sets          = if read_u16(table, 2)? >= 2 { read_u16(table, 12)? as usize } else { 0 };
+
+        if sets != 0 {
+            let count = read_u16(table, sets + 2)? as usize;
+            for i in 0..count {
+                mark_sets.push(Coverage::read(table, sets + read_u32(table, sets + 4 + i * 4)? as usize)?);
+            }
+        }
+
+        Ok(Self { glyph_classes, mark_classes, mark_sets })
+    }
+
+    //*************************************************************************
+    pub fn ignores(
+                &self,
+                glyph:  u16,
+                lookup: &Lookup
+            ) -> bool {
+        let Some(classes) = self.glyph_classes.as_ref() else {
+            return false;
+        };
+
+        match classes.class(glyph) {
+            BASE     => lookup.flags & IGNORE_BASE_GLYPHS != 0,
+            LIGATURE => lookup.flags & IGNORE_LIGATURES != 0,
+            MARK     => {
+                let kind = lookup.flags >> 8;
+
+                if lookup.flags & IGNORE_MARKS != 0 {
+                    true
+                } else if let Some(set) = lookup.mark_set {
+                    self.mark_sets.get(set as usize).is_none_or(|x|x.index(glyph).is_none())
+                } else {
+                    kind != 0 && self.mark_classes.as_ref().map_or(0, |x|x.class(glyph)) != kind
+                }
+            },
+            _ => false
+        }
+    }
+}
+
+//*****************************************************************************
+// The parts GSUB and GPOS share, the offsets of the lookup subtables are
+// from the start of the table.
+pub struct Layout {
+    pub scripts:  Vec<Script>,
+    pub features: Vec<Feature>,
+    pub lookups:  Vec<Lookup>
+}
+
+impl Layout {
+    //*************************************************************************
+    pub fn read(
+                data:      &[u8],
+                extension: u16
+            ) -> Result<Self, String> {
+        let scripts  = Layout::read_scripts(data, read_u16(data, 4)? as usize)?;
+        let features = Layout::read_features(data, read_u16(data, 6)? as usize)?;
+        let lookups  = Layout::read_lookups(data, read_u16(data, 8)? as usize, extension)?;
+
+        Ok(Self { scripts, features, lookups })
+    }
+
+    //*************************************************************************
+    fn read_tag(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<String, String> {
+        data.get(offset..offset + 4)
+            .map(|x|String::from_utf8_lossy(x).into_owned())
+            .ok_or_else(||format!("Unexpected end of data at offset {}.", offset))
+    }
+
+    //*************************************************************************
+    fn read_language(
+                data:   &[u8],
+                offset: usize,
+                tag:    String
+            ) -> Result<Language, String> {
+        let required = read_u16(data, offset + 2)?;
+        let count    = read_u16(data, offset + 4)? as usize;
+
+        Ok(Language {
+            tag,
+            required: if required == 0xFFFF { None } else { Some(required) },
+            features: (0..count)
+                .map(|i|read_u16(data, offset + 6 + i * 2))
+                .collect::<Result<Vec<u16>, String>>()?
+        })
+    }
+
+    //*************************************************************************
+    fn read_scripts(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Vec<Script>, String> {
+        let count = read_u16(data, offset)? as usize;
+
+        (0..count)
+            .map(|i|{
+                let position  = offset + 2 + i * 6;
+                let script    = offset + read_u16(data, position + 4)? as usize;
+                let default   = read_u16(data, script)? as usize;
+                let languages = read_u16(data, script + 2)? as usize;
+                let mut list  = Vec::new();
+
+                if default != 0 {
+                    list.push(Layout::read_language(data, script + default, String::from("dflt"))?);
+                }
+                for j in 0..languages {
+                    let record = script + 4 + j * 6;
+                    let tag    = Layout::read_tag(data, record)?;
+                    list.push(Layout::read_language(data, script + read_u16(data, record + 4)? as usize, tag)?);
+                }
+
+                Ok(Script { tag: Layout::read_tag(data, position)?, languages: list })
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    fn read_features(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Vec<Feature>, String> {
+        let count = read_u16(data, offset)? as usize;
+
+        (0..count)
+            .map(|i|{
+                let position = offset + 2 + i * 6;
+                let feature  = offset + read_u16(data, position + 4)? as usize;
+                let lookups  = read_u16(data, feature + 2)? as usize;
+
+                Ok(Feature {
+                    tag:     Layout::read_tag(data, position)?,
+                    lookups: (0..lookups)
+                        .map(|j|read_u16(data, feature + 4 + j * 2))
+                        .collect::<Result<Vec<u16>, String>>()?
+                })
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    fn read_lookups(
+                data:      &[u8],
+                offset:    usize,
+                extension: u16
+            ) -> Result<Vec<Lookup>, String> {
+        let count = read_u16(data, offset)? as usize;
+
+        (0..count)
+            .map(|i|{
+                let lookup    = offset + read_u16(data, offset + 2 + i * 2)? as usize;
+                let mut kind  = read_u16(data, lookup)?;
+                let flags     = read_u16(data, lookup + 2)?;
+                let subtables = read_u16(data, lookup + 4)? as usize;
+
+                let mut offsets = (0..subtables)
+                    .map(|j|Ok(lookup + read_u16(data, lookup + 6 + j * 2)? as usize))
+                    .collect::<Result<Vec<usize>, String>>()?;
+
+                // Extension subtables point on to the real ones, which all
+                // share one type.
+                if kind == extension {
+                    for subtable in offsets.iter_mut() {
+                        kind       = read_u16(data, *subtable + 2)?;
+                        *subtable += read_u32(data, *subtable + 4)? as usize;
+                    }
+                }
+
+                // The mark filtering set follows the subtable offsets.
+                let mark_set = if flags & USE_MARK_FILTERING_SET != 0 {
+                    Some(read_u16(data, lookup + 6 + subtables * 2)?)
+                } else {
+                    None
+                };
+
+                Ok(Lookup { kind, flags, subtables: offsets, mark_set })
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    pub fn feature_lookups(
+                &self,
+                tag: &str
+            ) -> Vec<u16> {
+        let mut result = self.features.iter()
+            .filter(|x|x.tag == tag)
+            .flat_map(|x|x.lookups.iter().copied())
+            .collect::<Vec<u16>>();
+
+        // Lookups run in list order, whichever script asked for them.
+        result.sort();
+        result.dedup();
+        result
+    }
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //*************************************************************************
+    fn words(
+                values: &[u16]
+            ) -> Vec<u8> {
+        values.iter().flat_map(|x|x.to_be_bytes()).collect()
+    }
+
+    //*************************************************************************
+    #[test]
+    fn coverage_lists_find_their_index() {
+        let coverage = Coverage::read(&words(&[1, 3, 4, 7, 9]), 0).unwrap();
+        assert_eq!(coverage.index(4), Some(0));
+        assert_eq!(coverage.index(9), Some(2));
+        assert_eq!(coverage.index(5), None);
+        assert_eq!(coverage.index(10), None);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn coverage_ranges_count_from_their_start_index() {
+        let coverage = Coverage::read(&words(&[2, 2, 5, 9, 0, 20, 22, 5]), 0).unwrap();
+        assert_eq!(coverage.index(4), None);
+        assert_eq!(coverage.index(7), Some(2));
+        assert_eq!(coverage.index(22), Some(7));
+        assert_eq!(coverage.index(23), None);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn coverage_ranges_are_not_expanded() {
+        // Every glyph in each of the most ranges a table can hold.
+        let ranges = (0..0xFFFF).flat_map(|_|[0, 0xFFFF, 0]).collect::<Vec<u16>>();
+        let coverage = Coverage::read(&words(&[&[2, 0xFFFF][..], &ranges[..]].concat()), 0).unwrap();
+        assert_eq!(coverage.index(0xFFFF), Some(0xFFFF));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn class_ranges_hold_their_class() {
+        let classes = Classes::read(&words(&[2, 2, 5, 9, 1, 20, 22, 2]), 0).unwrap();
+        assert_eq!(classes.class(7), 1);
+        assert_eq!(classes.class(10), 0);
+        assert_eq!(classes.ranges(2).collect::<Vec<_>>(), vec![(20, 22)]);
+    }
+}
@@ -35,6 +35,73 @@ pub enum Def {
     Cubic(Color, PlotPoint, PlotPoint, PlotPoint, PlotPoint)
 }
 
+impl Def {
+    //*************************************************************************
+    pub fn color(&self) -> Color {
+        match self {
+            Def::Line(c, _, _)         => *c,
+            Def::Quadratic(c, _, _, _) => *c,
+            Def::Cubic(c, _, _, _, _)  => *c
+        }
+    }
+
+    //*************************************************************************
+    pub fn with_color(
+                self,
+                color: Color
+            ) -> Def {
+        match self {
+            Def::Line(_, p0, p1)          => Def::Line(color, p0, p1),
+            Def::Quadratic(_, p0, p1, p2) => Def::Quadratic(color, p0, p1, p2),
+            Def::Cubic(_, p0, p1, p2, p3) => Def::Cubic(color, p0, p1, p2, p3)
+        }
+    }
+
+    //*************************************************************************
+    pub fn middle(&self) -> (PlotPoint, PlotPoint) {
+        // The point half way along the segment and the direction it is
+        // heading at that point.
+        match self {
+            Def::Line(_, p0, p1) => (
+                PlotPoint::new((p0.x + p1.x) / 2.0, (p0.y + p1.y) / 2.0),
+                PlotPoint::new(p1.x - p0.x, p1.y - p0.y)
+            ),
+            Def::Quadratic(_, p0, p1, p2) => (
+                PlotPoint::new(
+                    (p0.x + 2.0 * p1.x + p2.x) / 4.0,
+                    (p0.y + 2.0 * p1.y + p2.y) / 4.0
+                ),
+                PlotPoint::new(p2.x - p0.x, p2.y - p0.y)
+            ),
+            Def::Cubic(_, p0, p1, p2, p3) => (
+                PlotPoint::new(
+                    (p0.x + 3.0 * p1.x + 3.0 * p2.x + p3.x) / 8.0,
+                    (p0.y + 3.0 * p1.y + 3.0 * p2.y + p3.y) / 8.0
+                ),
+                PlotPoint::new(p3.x + p2.x - p1.x - p0.x, p3.y + p2.y - p1.y - p0.y)
+            )
+        }
+    }
+
+    //*************************************************************************
+    pub fn start(&self) -> PlotPoint {
+        match self {
+            Def::Line(_, p0, _)         => *p0,
+            Def::Quadratic(_, p0, _, _) => *p0,
+            Def::Cubic(_, p0, _, _, _)  => *p0
+        }
+    }
+
+    //*************************************************************************
+    pub fn end(&self) -> PlotPoint {
+        match self {
+            Def::Line(_, _, p1)         => *p1,
+            Def::Quadratic(_, _, _, p2) => *p2,
+            Def::Cubic(_, _, _, _, p3)  => *p3
+        }
+    }
+}
+
 //*****************************************************************************
 pub struct Line {
     draw: bool,
@@ -62,8 +129,8 @@ impl Line {
     //*************************************************************************
     pub fn view(
                 &self,
-                idx: usize
-            ) -> Element<Message> {
+                idx: (usize, usize)
+            ) -> Element<'_, Message> {
         let color;
         let data = match &self.def {
             Def::Line(c, p0, p1) => {
@@ -50,7 +50,7 @@ impl LineControls {
             ].spacing(5),
             scrollable(
                 Column::with_children(
-                    lines.iter().enumerate().map(|(i, x)|x.view(i))
+                    lines.contours().iter().enumerate().map(|(i, x)|x.view(i))
                 )
             ).spacing(4)
         ].padding(4)
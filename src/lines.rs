@@ -19,31 +19,74 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
-use std::slice::Iter;
-
-use super::{Color, Def, Line, PlotPoint};
+use super::{Color, Contour, Def, Line, PlotPoint};
 
 //*****************************************************************************
 pub struct Lines {
-    segments: Vec<Line>
+    contours: Vec<Contour>
 }
 
 impl Lines {
     //*************************************************************************
     pub fn new() -> Self {
         Self {
-            segments: Vec::new()
+            contours: Vec::new()
         }
     }
 
     //*************************************************************************
-    pub fn iter(&self) -> Iter<Line> {
-        self.segments.iter()
+    pub fn contours(&self) -> &[Contour] {
+        &self.contours
+    }
+
+    //*************************************************************************
+    pub fn iter(&self) -> impl Iterator<Item = &Line> {
+        self.contours
+            .iter()
+            .filter(|x|x.is_enabled())
+            .flat_map(|x|x.iter())
+    }
+
+    //*************************************************************************
+    pub fn closed(&self) -> Vec<Vec<&Def>> {
+        // A closed contour with any of its segments hidden no longer encloses
+        // anything.
+        self.contours
+            .iter()
+            .filter(|x|x.is_enabled() && x.is_closed())
+            .filter(|x|x.iter().all(|y|y.is_enabled()))
+            .map(|x|x.iter().map(|y|y.def()).collect())
+            .collect()
     }
 
     //*************************************************************************
     pub fn clear(&mut self) {
-        self.segments.clear();
+        self.contours.clear();
+    }
+
+    //*************************************************************************
+    pub fn open_contour(&mut self) {
+        self.contours.push(Contour::new());
+    }
+
+    //*************************************************************************
+    pub fn close_contour(&mut self) {
+        if let Some(contour) = self.contours.last_mut() {
+            contour.close();
+        }
+    }
+
+    //*************************************************************************
+    fn push(
+                &mut self,
+                def: Def
+            ) {
+        if self.contours.is_empty() {
+            self.open_contour();
+        }
+        if let Some(contour) = self.contours.last_mut() {
+            contour.push(def);
+        }
     }
 
     //*************************************************************************
@@ -51,34 +94,66 @@ impl Lines {
                 &mut self,
                 def: Def
             ) {
-        let line = Line::new(def);
-        self.segments.push(line);
+        // A segment carries on the last contour when it starts where that
+        // contour stopped, otherwise it starts a new one.
+        let chained = match self.contours.last() {
+            Some(contour) => !contour.is_closed() && contour.last().is_some_and(|x|x.end().touches(def.start())),
+            None          => false
+        };
+
+        if !chained {
+            self.open_contour();
+        }
+        self.push(def);
+        self.close_contour();
     }
 
     //*************************************************************************
     pub fn handle_line_change(
                 &mut self,
-                idx: usize,
+                idx: (usize, usize),
                 def: Def
             ) {
-        self.segments[idx].change(def);
+        self.contours[idx.0].handle_line_change(idx.1, def);
     }
 
     //*************************************************************************
     pub fn handle_line_toggle(
                 &mut self,
-                idx:    usize,
+                idx:    (usize, usize),
                 enable: bool
             ) {
-        self.segments[idx].enable(enable);
+        self.contours[idx.0].handle_line_toggle(idx.1, enable);
     }
 
     //*************************************************************************
     pub fn handle_line_remove(
                 &mut self,
-                idx: usize
+                idx: (usize, usize)
+            ) {
+        self.contours[idx.0].handle_line_remove(idx.1);
+
+        if self.contours[idx.0].is_empty() {
+            self.contours.remove(idx.0);
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_contour_toggle(
+                &mut self,
+                idx:    usize,
+                enable: bool
+            ) {
+        self.contours[idx].handle_toggle(enable);
+    }
+
+    //*************************************************************************
+    pub fn handle_contour_expand(
+                &mut self,
+                idx:    usize,
+                expand: bool
             ) {
-        self.segments.remove(idx);
+        self.contours[idx].handle_expand(expand);
     }
 
     //*************************************************************************
@@ -88,10 +163,9 @@ impl Lines {
                 p0:    PlotPoint,
                 p1:    PlotPoint
             ) {
-        let line = Line::new(
+        self.push(
             Def::Line(color, p0, p1)
         );
-        self.segments.push(line);
     }
 
     //*************************************************************************
@@ -102,10 +176,9 @@ impl Lines {
                 p1:    PlotPoint,
                 p2:    PlotPoint
             ) {
-        let curve = Line::new(
+        self.push(
             Def::Quadratic(color, p0, p1, p2)
         );
-        self.segments.push(curve);
     }
 
     //*************************************************************************
@@ -117,10 +190,9 @@ impl Lines {
                 p2:    PlotPoint,
                 p3:    PlotPoint
             ) {
-        let curve = Line::new(
+        self.push(
             Def::Cubic(color, p0, p1, p2, p3)
         );
-        self.segments.push(curve);
     }
 
-}
\ No newline at end of file
+}
@@ -20,35 +20,83 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use iced::{
+    mouse::ScrollDelta,
     widget::{
+        scrollable::Viewport,
         text_editor::Action,
         column, container, focus_next, row, vertical_rule
     },
     Element, Size, Task, Theme
 };
 
+use axes::Axes;
+use browse::Browse;
+use bytecode::Operation;
+use charstrings::Instruction;
 use color::Color;
+use contour::Contour;
 use define::Define;
-use font_controls::FontControls;
+use fill::Fill;
+use font_controls::{Face, FontControls};
+use glyph_info::{ComponentInfo, GlyphInfo};
 use glyphs::Glyphs;
+use guides::{Guide, Guides};
+use hinting::{Hinting, Mark};
+use interpreter::{Hinted, Interpreter};
+use kerning::Kerning;
+use labels::Labels;
 use line::{Def, Line};
 use line_controls::LineControls;
 use lines::Lines;
+use metadata::Metadata;
+use outlines::{Component, GlyphPoints, Outline, Outlines, Shift};
+use pairs::{PairInfo, Pairs};
 use plot_point::PlotPoint;
+use preview::Preview;
 use settings::Settings;
+use substitutions::{Step, Substitutions};
+use tab::Tab;
+use tables::Tables;
+use variations::{Axis, Instance, Variations};
 use view::View;
 
 //*****************************************************************************
+mod axes;
+mod browse;
+mod bytecode;
+mod cff2;
+mod charstrings;
 mod color;
+mod contour;
 mod define;
+mod fill;
 mod font_controls;
+mod glyph_info;
 mod glyphs;
+mod guides;
+mod hinting;
+mod interpreter;
+mod kerning;
+mod labels;
+mod layout;
 mod line;
 mod line_controls;
 mod lines;
+mod metadata;
+mod outlines;
+mod pairs;
 mod plot_point;
+mod preview;
 mod settings;
+mod sfnt;
+mod substitutions;
+mod tab;
+mod tables;
+mod unicode;
+mod variations;
 mod view;
+mod woff;
+mod woff2;
 
 //*****************************************************************************
 pub fn main() {
@@ -79,31 +127,75 @@ enum Popin {
 //*****************************************************************************
 #[derive(Debug, Clone)]
 enum Message {
+    ChangeAntiAlias(bool),
+    ChangeAxis(usize, f32),
     ChangeColor(Color),
+    ChangeDeltas(bool),
+    ChangeDirection(bool),
+    ChangeFill(Fill),
+    ChangeGuide(Guide, bool),
+    ChangeInstance(Instance),
+    ChangeLabels(Labels),
+    ChangePixels(bool),
+    ChangePoints(bool),
+    ChangePpem(i32),
+    ChangeTab(Tab),
     ChangeTheme(Theme),
     ChangeThickness(i32),
     ChangeFormula(Action),
+    CharstringHover(usize, bool),
+    ComponentToggle(usize, bool),
+    ContourExpand(usize, bool),
+    ContourToggle(usize, bool),
     CreateTests,
+    FontBrowse(Browse),
+    FontFace(Face),
     FontFilename(String),
     FontFilter(String),
+    FontGlyph(u16),
     FontLoad(String),
     FontView(char),
+    HintingCompare(bool),
+    HintingStep(Option<usize>),
+    HintingToggle(bool),
     LineAdd(Def),
-    LineChange(usize, Def),
-    LineRemove(usize),
-    LineShow(Popin, usize, Color, String),
-    LineToggle(usize, bool),
-    Show(Popin)
+    LineChange((usize, usize), Def),
+    LineRemove((usize, usize)),
+    LineShow(Popin, (usize, usize), Color, String),
+    LineToggle((usize, usize), bool),
+    PairGlyph(u16),
+    PairInput(String),
+    PairToggle(bool),
+    PreviewFeature(String, bool),
+    PreviewLanguage(String),
+    PreviewScript(String),
+    PreviewSubstitute(bool),
+    PreviewText(String),
+    Show(Popin),
+    TablePage(usize),
+    TableSelect(usize),
+    ViewActual,
+    ViewFit,
+    ViewGrab(bool),
+    ViewMove(iced::Point),
+    ViewScrolled(Viewport),
+    ViewZoom(ScrollDelta)
 }
 
 //*****************************************************************************
 struct FontView {
     define:     Define,
     settings:   Settings,
+    axes:       Axes,
+    pairs:      Pairs,
+    preview:    Preview,
+    hinting:    Hinting,
+    tables:     Tables,
     lines:      Lines,
     view:       View,
     l_controls: LineControls,
     f_controls: FontControls,
+    info:       Option<GlyphInfo>,
     theme:      Theme,
 }
 
@@ -113,10 +205,16 @@ impl Default for FontView {
         Self {
             define:     Define::default(),
             settings:   Settings::new(5),
+            axes:       Axes::new(),
+            pairs:      Pairs::new(),
+            preview:    Preview::new(),
+            hinting:    Hinting::new(),
+            tables:     Tables::new(),
             lines:      Lines::new(),
             view:       View::new(5),
             l_controls: LineControls::new(),
             f_controls: FontControls::new(),
+            info:       None,
             theme:      Theme::Dark,
         }
     }
@@ -125,13 +223,36 @@ impl Default for FontView {
 impl FontView {
     //*************************************************************************
     fn view(&self) -> Element<Message> {
+        // The substitutions being explored take the place of the glyph, the
+        // hinting takes the place of its details.
+        let hinted = self.info.as_ref().and_then(|x|x.hinted.as_ref());
+        let info   = match (self.preview.text(), self.preview.substitution(), self.info.as_ref()) {
+            (Some(_), Some(_), _) => self.preview.info_view(),
+            (None, _, Some(GlyphInfo { hinted: Some(hinted), moves, .. })) => {
+                self.hinting.info_view(hinted, moves)
+            },
+            _ => GlyphInfo::view(self.info.as_ref())
+        };
+
+        let main = match self.settings.tab() {
+            Tab::Glyph    => self.view.view(),
+            Tab::Metadata => Metadata::view(self.f_controls.metadata()),
+            Tab::Tables   => self.tables.view()
+        };
+
         let screen = column![
             self.settings.view(&self.theme),
-            self.view.view(),
+            self.axes.view(),
+            self.pairs.view(),
+            self.preview.view(),
+            self.hinting.view(hinted),
+            main,
             container(
                 row![
                     self.l_controls.view(&self.lines),
                     vertical_rule(8),
+                    info,
+                    vertical_rule(8),
                     self.f_controls.view(),
                 ]
             ).height(240)
@@ -147,10 +268,77 @@ impl FontView {
     //*************************************************************************
     fn update(&mut self, message: Message) -> Task<Message> {
         match message {
+            Message::ChangeAntiAlias(anti_alias) => {
+                self.settings.handle_anti_alias(anti_alias);
+                self.view.handle_anti_alias(anti_alias);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangeAxis(idx, value) => {
+                self.axes.handle_axis(idx, value);
+                self.handle_location();
+                Task::none()
+            },
             Message::ChangeColor(color) => {
                 self.define.handle_change_color(color);
                 Task::none()
             },
+            Message::ChangeDeltas(deltas) => {
+                self.settings.handle_deltas(deltas);
+                self.view.handle_deltas(deltas);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangeDirection(direction) => {
+                self.settings.handle_direction(direction);
+                self.view.handle_direction(direction);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangeFill(fill) => {
+                self.settings.handle_fill(fill);
+                self.view.handle_fill(fill);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangeGuide(guide, show) => {
+                self.settings.handle_guide(guide, show);
+                self.view.handle_guide(guide, show);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangeInstance(instance) => {
+                self.axes.handle_instance(instance);
+                self.handle_location();
+                Task::none()
+            },
+            Message::ChangeLabels(labels) => {
+                self.settings.handle_labels(labels);
+                self.view.handle_labels(labels);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangePixels(pixels) => {
+                self.settings.handle_pixels(pixels);
+                self.handle_ppem();
+                Task::none()
+            },
+            Message::ChangePoints(points) => {
+                self.settings.handle_points(points);
+                self.view.handle_points(points);
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ChangePpem(ppem) => {
+                self.settings.handle_ppem(ppem);
+                self.handle_ppem();
+                self.handle_hinting();
+                Task::none()
+            },
+            Message::ChangeTab(tab) => {
+                self.settings.handle_tab(tab);
+                Task::none()
+            },
             Message::ChangeTheme(theme) => {
                 self.handle_change_theme(theme);
                 Task::none()
@@ -165,10 +353,58 @@ impl FontView {
                 self.define.handle_change_formula(action);
                 Task::none()
             },
+            Message::ContourExpand(idx, expand) => {
+                self.lines.handle_contour_expand(idx, expand);
+                Task::none()
+            },
+            Message::CharstringHover(idx, hover) => {
+                if let Some(info) = self.info.as_mut() {
+                    info.handle_charstring_hover(idx, hover);
+                    self.view.handle_highlight(info.highlight());
+                }
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ComponentToggle(idx, show) => {
+                if let Some(info) = self.info.as_mut() {
+                    for contour in info.handle_component_toggle(idx, show) {
+                        self.lines.handle_contour_toggle(contour, show);
+                    }
+                }
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ContourToggle(idx, show) => {
+                self.lines.handle_contour_toggle(idx, show);
+                self.view.update(&self.lines);
+                Task::none()
+            },
             Message::CreateTests => {
                 self.handle_tests();
                 Task::none()
             },
+            Message::FontBrowse(browse) => {
+                self.f_controls.handle_font_browse(browse);
+                Task::none()
+            },
+            Message::FontFace(face) => {
+                self.f_controls.handle_font_face(face);
+                self.axes.handle_font(self.f_controls.variations());
+                self.pairs.clear();
+                self.preview.handle_font(self.f_controls.substitutions());
+                self.tables.handle_font(self.f_controls.data());
+                self.lines.clear();
+                self.info = None;
+                self.view.handle_guides(None);
+                self.view.handle_shifts(Vec::new());
+                self.view.handle_highlight(Vec::new());
+                self.view.handle_marks(Vec::new());
+                self.view.handle_overlay(Vec::new());
+                self.view.handle_numbered(Vec::new());
+                self.handle_ppem();
+                self.handle_hinting();
+                Task::none()
+            },
             Message::FontFilename(filename) => {
                 self.f_controls.handle_font_filename(filename);
                 Task::none()
@@ -177,13 +413,45 @@ impl FontView {
                 self.f_controls.handle_font_filter(filter);
                 Task::none()
             },
+            Message::FontGlyph(id) => {
+                self.hinting.handle_step(None);
+                self.f_controls.handle_font_hinting(self.hinting.settings(self.settings.size()));
+                self.info = self.f_controls.handle_font_glyph(id, &mut self.lines);
+                self.handle_info();
+                Task::none()
+            },
             Message::FontLoad(filename) => {
                 self.f_controls.handle_font_load(filename);
+                self.axes.handle_font(self.f_controls.variations());
+                self.pairs.clear();
+                self.preview.handle_font(self.f_controls.substitutions());
+                self.tables.handle_font(self.f_controls.data());
+                self.handle_ppem();
+                self.handle_hinting();
                 Task::none()
             },
             Message::FontView(glyph) => {
-                self.f_controls.handle_font_view(glyph, &mut self.lines);
-                self.view.update(&self.lines);
+                self.hinting.handle_step(None);
+                self.f_controls.handle_font_hinting(self.hinting.settings(self.settings.size()));
+                self.info = self.f_controls.handle_font_view(glyph, &mut self.lines);
+                self.handle_info();
+                Task::none()
+            },
+            Message::HintingCompare(compare) => {
+                self.hinting.handle_compare(compare);
+                self.handle_ppem();
+                self.handle_info();
+                Task::none()
+            },
+            Message::HintingStep(step) => {
+                self.hinting.handle_step(step);
+                self.handle_hinting();
+                Task::none()
+            },
+            Message::HintingToggle(show) => {
+                self.hinting.handle_toggle(show);
+                self.handle_ppem();
+                self.handle_hinting();
                 Task::none()
             },
             Message::LineAdd(def) => {
@@ -212,9 +480,87 @@ impl FontView {
                 self.view.update(&self.lines);
                 Task::none()
             },
+            Message::PairGlyph(id) => {
+                self.pairs.handle_glyph(id);
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PairInput(input) => {
+                let right = self.f_controls.find_glyph(&input);
+                self.pairs.handle_input(input, right);
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PairToggle(show) => {
+                self.pairs.handle_toggle(show);
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PreviewFeature(tag, enabled) => {
+                self.preview.handle_feature(tag, enabled);
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PreviewLanguage(language) => {
+                self.preview.handle_language(language, self.f_controls.substitutions());
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PreviewScript(script) => {
+                self.preview.handle_script(script, self.f_controls.substitutions());
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PreviewSubstitute(substitute) => {
+                self.preview.handle_substitute(substitute);
+                self.handle_redraw();
+                Task::none()
+            },
+            Message::PreviewText(text) => {
+                self.preview.handle_text(text);
+
+                // Without a glyph to go back to the view is left empty.
+                if self.info.is_none() {
+                    self.lines.clear();
+                }
+                self.handle_redraw();
+                Task::none()
+            },
             Message::Show(popin) => {
                 self.define.handle_show(popin);
                 focus_next()
+            },
+            Message::TablePage(page) => {
+                self.tables.handle_page(page);
+                Task::none()
+            },
+            Message::TableSelect(idx) => {
+                self.tables.handle_select(idx);
+                Task::none()
+            },
+            Message::ViewActual => {
+                self.view.handle_actual();
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ViewFit => {
+                self.view.handle_fit();
+                self.view.update(&self.lines);
+                Task::none()
+            },
+            Message::ViewGrab(grab) => {
+                self.view.handle_grab(grab);
+                Task::none()
+            },
+            Message::ViewMove(cursor) => {
+                self.view.handle_move(cursor)
+            },
+            Message::ViewScrolled(viewport) => {
+                self.view.handle_scrolled(viewport);
+                Task::none()
+            },
+            Message::ViewZoom(delta) => {
+                self.view.handle_zoom(delta, &self.lines)
             }
         }
     }
@@ -227,9 +573,93 @@ impl FontView {
         self.theme = theme;
     }
 
+    //*************************************************************************
+    fn handle_ppem(&mut self) {
+        // Comparing the hinting needs the pixels behind it.
+        let ppem  = self.settings.ppem().or_else(||self.hinting.is_comparing().then_some(self.settings.size() as f32));
+        let scale = ppem.map(|x|x / self.f_controls.units_per_em());
+
+        self.view.handle_ppem(scale);
+        self.view.update(&self.lines);
+    }
+
+    //*************************************************************************
+    fn handle_hinting(&mut self) {
+        // The glyph on show is hinted again up to the chosen step.
+        self.f_controls.handle_font_hinting(self.hinting.settings(self.settings.size()));
+        self.handle_redraw();
+    }
+
+    //*************************************************************************
+    fn handle_location(&mut self) {
+        self.f_controls.handle_font_location(self.axes.location());
+        self.handle_redraw();
+    }
+
+    //*************************************************************************
+    fn handle_redraw(&mut self) {
+        // The glyph on show is drawn again with the new settings.
+        self.info = self.f_controls.handle_font_redraw(self.info.as_ref(), &mut self.lines);
+        self.handle_info();
+    }
+
+    //*************************************************************************
+    fn handle_info(&mut self) {
+        // Text to preview takes the place of the glyph.
+        if let Some(text) = self.preview.text() {
+            let guides = match self.preview.substitution() {
+                Some((script, language, features)) => {
+                    let (guides, steps) = self.f_controls.handle_font_substitute(
+                        text, script, language, &features, &mut self.lines
+                    );
+                    self.preview.handle_steps(steps);
+                    guides
+                },
+                None => self.f_controls.handle_font_text(text, &mut self.lines)
+            };
+
+            self.view.handle_guides(guides);
+            self.view.handle_shifts(Vec::new());
+            self.view.handle_highlight(Vec::new());
+            self.view.handle_marks(Vec::new());
+            self.view.handle_overlay(Vec::new());
+            self.view.handle_numbered(Vec::new());
+            self.view.update(&self.lines);
+            return;
+        }
+
+        let (value, pairs) = match self.info.as_ref() {
+            Some(info) if self.pairs.is_shown() => {
+                self.f_controls.handle_font_pair(info, self.pairs.right(), &mut self.lines)
+            },
+            _ => (None, Vec::new())
+        };
+        self.pairs.handle_pairs(value, pairs);
+
+        self.view.handle_guides(self.info.as_ref().and_then(|x|x.guides));
+        self.view.handle_shifts(self.info.as_ref().map(|x|x.shifts.clone()).unwrap_or_default());
+        self.view.handle_highlight(self.info.as_ref().map(|x|x.highlight()).unwrap_or_default());
+        self.view.handle_marks(self.hinting.marks(self.info.as_ref().and_then(|x|x.hinted.as_ref())));
+        self.view.handle_overlay(
+            self.info.as_ref()
+                .filter(|_|self.hinting.is_comparing())
+                .map(|x|x.original.clone())
+                .unwrap_or_default()
+        );
+        self.view.handle_numbered(self.info.as_ref().map(|x|x.numbered.clone()).unwrap_or_default());
+        self.view.update(&self.lines);
+    }
+
     //*************************************************************************
     fn handle_tests(&mut self) {
         self.lines.clear();
+        self.info = None;
+        self.view.handle_guides(None);
+        self.view.handle_shifts(Vec::new());
+        self.view.handle_highlight(Vec::new());
+        self.view.handle_marks(Vec::new());
+        self.view.handle_overlay(Vec::new());
+        self.view.handle_numbered(Vec::new());
 
         // Horizontal
         self.lines.handle_line_add(
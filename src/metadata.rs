@@ -0,0 +1,530 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{column, container, horizontal_space, row, scrollable, text, vertical_space, Column},
+    Element
+};
+
+use crate::sfnt::{self, read_u16, read_u32};
+use super::Message;
+
+//*****************************************************************************
+const NAMES: [&str; 26] = [
+    "Copyright", "Family", "Subfamily", "Unique ID", "Full name", "Version",
+    "PostScript name", "Trademark", "Manufacturer", "Designer", "Description",
+    "Vendor URL", "Designer URL", "License", "License URL", "Reserved",
+    "Typographic family", "Typographic subfamily", "Compatible full name",
+    "Sample text", "PostScript CID name", "WWS family", "WWS subfamily",
+    "Light palette", "Dark palette", "Variations prefix"
+];
+
+//*****************************************************************************
+const HEAD_FLAGS: [(u32, &str); 9] = [
+    (0x0001, "BASELINE_AT_0"),
+    (0x0002, "LSB_AT_0"),
+    (0x0004, "INSTRUCTIONS_DEPEND_ON_SIZE"),
+    (0x0008, "INTEGER_PPEM"),
+    (0x0010, "INSTRUCTIONS_ALTER_ADVANCE"),
+    (0x0800, "LOSSLESS"),
+    (0x1000, "CONVERTED"),
+    (0x2000, "CLEARTYPE"),
+    (0x4000, "LAST_RESORT")
+];
+
+//*****************************************************************************
+const MAC_STYLE: [(u32, &str); 7] = [
+    (0x0001, "BOLD"),
+    (0x0002, "ITALIC"),
+    (0x0004, "UNDERLINE"),
+    (0x0008, "OUTLINE"),
+    (0x0010, "SHADOW"),
+    (0x0020, "CONDENSED"),
+    (0x0040, "EXTENDED")
+];
+
+//*****************************************************************************
+const FS_TYPE: [(u32, &str); 5] = [
+    (0x0002, "RESTRICTED"),
+    (0x0004, "PREVIEW_AND_PRINT"),
+    (0x0008, "EDITABLE"),
+    (0x0100, "NO_SUBSETTING"),
+    (0x0200, "BITMAP_ONLY")
+];
+
+//*****************************************************************************
+const FS_SELECTION: [(u32, &str); 10] = [
+    (0x0001, "ITALIC"),
+    (0x0002, "UNDERSCORE"),
+    (0x0004, "NEGATIVE"),
+    (0x0008, "OUTLINED"),
+    (0x0010, "STRIKEOUT"),
+    (0x0020, "BOLD"),
+    (0x0040, "REGULAR"),
+    (0x0080, "USE_TYPO_METRICS"),
+    (0x0100, "WWS"),
+    (0x0200, "OBLIQUE")
+];
+
+//*****************************************************************************
+const WEIGHTS: [&str; 9] = [
+    "Thin", "Extra-light", "Light", "Regular", "Medium", "Semi-bold", "Bold", "Extra-bold", "Black"
+];
+
+//*****************************************************************************
+const WIDTHS: [&str; 9] = [
+    "Ultra-condensed", "Extra-condensed", "Condensed", "Semi-condensed", "Medium",
+    "Semi-expanded", "Expanded", "Extra-expanded", "Ultra-expanded"
+];
+
+//*****************************************************************************
+const UNICODE_RANGES: [&str; 123] = [
+    "Basic Latin", "Latin-1 Supplement", "Latin Extended-A", "Latin Extended-B",
+    "IPA Extensions", "Spacing Modifier Letters", "Combining Diacritical Marks",
+    "Greek and Coptic", "Coptic", "Cyrillic", "Armenian", "Hebrew", "Vai", "Arabic",
+    "NKo", "Devanagari", "Bengali", "Gurmukhi", "Gujarati", "Oriya", "Tamil",
+    "Telugu", "Kannada", "Malayalam", "Thai", "Lao", "Georgian", "Balinese",
+    "Hangul Jamo", "Latin Extended Additional", "Greek Extended",
+    "General Punctuation", "Superscripts and Subscripts", "Currency Symbols",
+    "Combining Diacritical Marks for Symbols", "Letterlike Symbols", "Number Forms",
+    "Arrows", "Mathematical Operators", "Miscellaneous Technical",
+    "Control Pictures", "Optical Character Recognition", "Enclosed Alphanumerics",
+    "Box Drawing", "Block Elements", "Geometric Shapes", "Miscellaneous Symbols",
+    "Dingbats", "CJK Symbols and Punctuation", "Hiragana", "Katakana", "Bopomofo",
+    "Hangul Compatibility Jamo", "Phags-pa", "Enclosed CJK Letters and Months",
+    "CJK Compatibility", "Hangul Syllables", "Non-Plane 0", "Phoenician",
+    "CJK Unified Ideographs", "Private Use Area (plane 0)", "CJK Strokes",
+    "Alphabetic Presentation Forms", "Arabic Presentation Forms-A",
+    "Combining Half Marks", "Vertical Forms", "Small Form Variants",
+    "Arabic Presentation Forms-B", "Halfwidth and Fullwidth Forms", "Specials",
+    "Tibetan", "Syriac", "Thaana", "Sinhala", "Myanmar", "Ethiopic", "Cherokee",
+    "Unified Canadian Aboriginal Syllabics", "Ogham", "Runic", "Khmer", "Mongolian",
+    "Braille Patterns", "Yi Syllables", "Tagalog", "Old Italic", "Gothic", "Deseret",
+    "Byzantine Musical Symbols", "Mathematical Alphanumeric Symbols",
+    "Private Use (plane 15)", "Variation Selectors", "Tags", "Limbu", "Tai Le",
+    "New Tai Lue", "Buginese", "Glagolitic", "Tifinagh", "Yijing Hexagram Symbols",
+    "Syloti Nagri", "Linear B Syllabary", "Ancient Greek Numbers", "Ugaritic",
+    "Old Persian", "Shavian", "Osmanya", "Cypriot Syllabary", "Kharoshthi",
+    "Tai Xuan Jing Symbols", "Cuneiform", "Counting Rod Numerals", "Sundanese",
+    "Lepcha", "Ol Chiki", "Saurashtra", "Kayah Li", "Rejang", "Cham",
+    "Ancient Symbols", "Phaistos Disc", "Carian", "Domino Tiles"
+];
+
+//*****************************************************************************
+// The head table counts seconds from 1904, this is 1970 in those seconds.
+const UNIX_EPOCH: i64 = 2_082_844_800;
+
+//*****************************************************************************
+fn read_i16(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<String, String> {
+    Ok(format!("{}", read_u16(data, offset)? as i16))
+}
+
+//*****************************************************************************
+fn read_fixed(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<String, String> {
+    Ok(format!("{}", read_u32(data, offset)? as i32 as f64 / 65536.0))
+}
+
+//*****************************************************************************
+fn read_date(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<String, String> {
+    let seconds = (read_u32(data, offset)? as i64) << 32 | read_u32(data, offset + 4)? as i64;
+    let Some(seconds) = seconds.checked_sub(UNIX_EPOCH) else {
+        return Ok(String::from("invalid"));
+    };
+    let days    = seconds.div_euclid(86400);
+    let time    = seconds.rem_euclid(86400);
+
+    // Days to a civil date, from Howard Hinnant's date algorithms.
+    let z     = days + 719_468;
+    let era   = z.div_euclid(146_097);
+    let doe   = z.rem_euclid(146_097);
+    let yoe   = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy   = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp    = (5 * doy + 2) / 153;
+    let day   = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year  = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    Ok(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, time / 3600, time / 60 % 60, time % 60
+    ))
+}
+
+//*****************************************************************************
+fn flags(
+            value: u32,
+            names: &[(u32, &str)]
+        ) -> String {
+    let names = names.iter()
+        .filter(|(mask, _)|value & mask != 0)
+        .map(|(_, name)|*name)
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    format!("0x{:04X} {}", value, names).trim_end().to_string()
+}
+
+//*****************************************************************************
+fn class(
+            value: u16,
+            names: &[&str],
+            scale: u16
+        ) -> String {
+    // The names fall on multiples of the scale, anything between is left
+    // as a number.
+    match names.get((value / scale).wrapping_sub(1) as usize) {
+        Some(name) if value.is_multiple_of(scale) => format!("{} ({})", value, name),
+        _                                         => format!("{}", value)
+    }
+}
+
+//*****************************************************************************
+struct Section {
+    title:  &'static str,
+    fields: Vec<(String, String)>
+}
+
+impl Section {
+    //*************************************************************************
+    fn new(title: &'static str) -> Self {
+        Self { title, fields: Vec::new() }
+    }
+
+    //*************************************************************************
+    fn add(
+                &mut self,
+                label: &str,
+                value: String
+            ) {
+        self.fields.push((label.to_string(), value));
+    }
+
+    //*************************************************************************
+    fn view(&self) -> Element<'_, Message> {
+        Column::with_children(
+            self.fields.iter().map(|(label, value)|{
+                row![
+                    text(label.as_str()).width(200),
+                    text(value.as_str())
+                ].spacing(8)
+                 .into()
+            })
+        ).push(vertical_space().height(8))
+         .spacing(4)
+         .into()
+    }
+}
+
+//*****************************************************************************
+pub struct Metadata {
+    sections: Vec<Section>
+}
+
+impl Metadata {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let mut sections = Vec::new();
+
+        for tag in [b"name", b"head", b"OS/2", b"hhea", b"vhea"] {
+            if let Some(section) = sfnt::table(data, tag).and_then(|x|Metadata::read_section(tag, x)) {
+                sections.push(section?);
+            }
+        }
+
+        Ok(Self { sections })
+    }
+
+    //*************************************************************************
+    fn read_section(
+                tag:  &[u8; 4],
+                data: &[u8]
+            ) -> Option<Result<Section, String>> {
+        match tag {
+            b"name" => Some(Metadata::read_names(data)),
+            b"head" => Some(Metadata::read_head(data)),
+            b"OS/2" => Some(Metadata::read_windows(data)),
+            b"hhea" => Some(Metadata::read_header(data, false)),
+            b"vhea" => Some(Metadata::read_header(data, true)),
+            _       => None
+        }
+    }
+
+    //*************************************************************************
+    pub fn read_fields(
+                tag:  &[u8; 4],
+                data: &[u8]
+            ) -> Option<Result<Vec<(String, String)>, String>> {
+        Metadata::read_section(tag, data).map(|x|x.map(|x|x.fields))
+    }
+
+    //*************************************************************************
+    fn decode(
+                platform: u16,
+                bytes:    &[u8]
+            ) -> Option<String> {
+        match platform {
+            // Unicode and Windows strings are UTF-16, Macintosh strings are
+            // taken as Latin-1, which is right for the ASCII they mostly are.
+            0 | 3 => {
+                let units = bytes.chunks_exact(2)
+                    .map(|x|u16::from_be_bytes([x[0], x[1]]))
+                    .collect::<Vec<u16>>();
+                Some(String::from_utf16_lossy(&units))
+            },
+            1 => Some(bytes.iter().map(|&x|x as char).collect()),
+            _ => None
+        }
+    }
+
+    //*************************************************************************
+    fn read_names(
+                data: &[u8]
+            ) -> Result<Section, String> {
+        let count   = read_u16(data, 2)? as usize;
+        let storage = read_u16(data, 4)? as usize;
+
+        // English is preferred, then whatever comes first.
+        let mut found: Vec<(u16, bool, String)> = Vec::new();
+
+        for i in 0..count {
+            let record   = 6 + i * 12;
+            let platform = read_u16(data, record)?;
+            let language = read_u16(data, record + 4)?;
+            let id       = read_u16(data, record + 6)?;
+            let length   = read_u16(data, record + 8)? as usize;
+            let offset   = storage + read_u16(data, record + 10)? as usize;
+
+            let english = (platform == 3 && language == 0x0409) || (platform == 1 && language == 0);
+            let Some(value) = data.get(offset..offset + length).and_then(|x|Metadata::decode(platform, x)) else {
+                continue;
+            };
+
+            match found.iter_mut().find(|x|x.0 == id) {
+                Some(entry) if english && !entry.1 => *entry = (id, english, value),
+                Some(_)                            => {},
+                None                               => found.push((id, english, value))
+            }
+        }
+        found.sort_by_key(|x|x.0);
+
+        let mut section = Section::new("name");
+        for (id, _, value) in found {
+            let label = match NAMES.get(id as usize) {
+                Some(name) => format!("{} ({})", name, id),
+                None       => format!("Name {}", id)
+            };
+            section.add(&label, value);
+        }
+
+        Ok(section)
+    }
+
+    //*************************************************************************
+    fn read_head(
+                data: &[u8]
+            ) -> Result<Section, String> {
+        let mut section = Section::new("head");
+
+        section.add("Version", format!("{}.{}", read_u16(data, 0)?, read_u16(data, 2)?));
+        section.add("Font revision", read_fixed(data, 4)?);
+        section.add("Checksum adjustment", format!("0x{:08X}", read_u32(data, 8)?));
+        section.add("Magic number", format!("0x{:08X}", read_u32(data, 12)?));
+        section.add("Flags", flags(read_u16(data, 16)? as u32, &HEAD_FLAGS));
+        section.add("Units per em", format!("{}", read_u16(data, 18)?));
+        section.add("Created", read_date(data, 20)?);
+        section.add("Modified", read_date(data, 28)?);
+        section.add("Bounding box", format!(
+            "({}, {}) - ({}, {})",
+            read_i16(data, 36)?, read_i16(data, 38)?, read_i16(data, 40)?, read_i16(data, 42)?
+        ));
+        section.add("Mac style", flags(read_u16(data, 44)? as u32, &MAC_STYLE));
+        section.add("Lowest readable ppem", format!("{}", read_u16(data, 46)?));
+        section.add("Direction hint", read_i16(data, 48)?);
+        section.add("Index to loca format", read_i16(data, 50)?);
+        section.add("Glyph data format", read_i16(data, 52)?);
+
+        Ok(section)
+    }
+
+    //*************************************************************************
+    fn read_windows(
+                data: &[u8]
+            ) -> Result<Section, String> {
+        let mut section = Section::new("OS/2");
+        let version     = read_u16(data, 0)?;
+
+        let panose = data.get(32..42)
+            .ok_or_else(||String::from("Unexpected end of data at offset 32."))?
+            .iter()
+            .map(|x|format!("{}", x))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        // Four words of bits, the unicode ranges are named by bit number.
+        let mut ranges = Vec::new();
+        for word in 0..4 {
+            let value = read_u32(data, 42 + word * 4)?;
+            for bit in (0..32).filter(|x|value & (1 << x) != 0) {
+                let index = word * 32 + bit;
+                ranges.push(match UNICODE_RANGES.get(index) {
+                    Some(name) => format!("{} {}", index, name),
+                    None       => format!("{}", index)
+                });
+            }
+        }
+        let family = read_u16(data, 30)?;
+        let vendor = data.get(58..62).map(|x|String::from_utf8_lossy(x).into_owned()).unwrap_or_default();
+
+        section.add("Version", format!("{}", version));
+        section.add("Average char width", read_i16(data, 2)?);
+        section.add("Weight class", class(read_u16(data, 4)?, &WEIGHTS, 100));
+        section.add("Width class", class(read_u16(data, 6)?, &WIDTHS, 1));
+        section.add("fsType", flags(read_u16(data, 8)? as u32, &FS_TYPE));
+        section.add("Subscript size", format!("{} x {}", read_i16(data, 10)?, read_i16(data, 12)?));
+        section.add("Subscript offset", format!("({}, {})", read_i16(data, 14)?, read_i16(data, 16)?));
+        section.add("Superscript size", format!("{} x {}", read_i16(data, 18)?, read_i16(data, 20)?));
+        section.add("Superscript offset", format!("({}, {})", read_i16(data, 22)?, read_i16(data, 24)?));
+        section.add("Strikeout size", read_i16(data, 26)?);
+        section.add("Strikeout position", read_i16(data, 28)?);
+        section.add("Family class", format!("{} / {}", family >> 8, family & 0xFF));
+        section.add("Panose", panose);
+        section.add("Vendor", vendor);
+        section.add("fsSelection", flags(read_u16(data, 62)? as u32, &FS_SELECTION));
+        section.add("First / last char", format!("U+{:04X} / U+{:04X}", read_u16(data, 64)?, read_u16(data, 66)?));
+        section.add("Typo ascender", read_i16(data, 68)?);
+        section.add("Typo descender", read_i16(data, 70)?);
+        section.add("Typo line gap", read_i16(data, 72)?);
+        section.add("Win ascent", format!("{}", read_u16(data, 74)?));
+        section.add("Win descent", format!("{}", read_u16(data, 76)?));
+
+        if version >= 1 {
+            section.add("Code page ranges", format!("0x{:08X} 0x{:08X}", read_u32(data, 78)?, read_u32(data, 82)?));
+        }
+        if version >= 2 {
+            section.add("x-height", read_i16(data, 86)?);
+            section.add("Cap height", read_i16(data, 88)?);
+            section.add("Default / break char", format!("U+{:04X} / U+{:04X}", read_u16(data, 90)?, read_u16(data, 92)?));
+            section.add("Max context", format!("{}", read_u16(data, 94)?));
+        }
+        if version >= 5 {
+            section.add("Optical point size", format!(
+                "{} - {}", read_u16(data, 96)? as f32 / 20.0, read_u16(data, 98)? as f32 / 20.0
+            ));
+        }
+        section.add("Unicode ranges", ranges.join("\n"));
+
+        Ok(section)
+    }
+
+    //*************************************************************************
+    fn read_header(
+                data:     &[u8],
+                vertical: bool
+            ) -> Result<Section, String> {
+        // The two tables share a layout, only the direction differs.
+        let (title, advance, before, after, extent, count) = if vertical {
+            ("vhea", "Advance height max", "Min top side bearing", "Min bottom side bearing", "y max extent", "Long vertical metrics")
+        } else {
+            ("hhea", "Advance width max", "Min left side bearing", "Min right side bearing", "x max extent", "Long horizontal metrics")
+        };
+        let mut section = Section::new(title);
+
+        section.add("Version", format!("{}.{}", read_u16(data, 0)?, read_u16(data, 2)?));
+        section.add("Ascender", read_i16(data, 4)?);
+        section.add("Descender", read_i16(data, 6)?);
+        section.add("Line gap", read_i16(data, 8)?);
+        section.add(advance, format!("{}", read_u16(data, 10)?));
+        section.add(before, read_i16(data, 12)?);
+        section.add(after, read_i16(data, 14)?);
+        section.add(extent, read_i16(data, 16)?);
+        section.add("Caret slope", format!("{} / {}", read_i16(data, 18)?, read_i16(data, 20)?));
+        section.add("Caret offset", read_i16(data, 22)?);
+        section.add("Metric data format", read_i16(data, 32)?);
+        section.add(count, format!("{}", read_u16(data, 34)?));
+
+        Ok(section)
+    }
+
+    //*************************************************************************
+    pub fn view(
+                metadata: Option<&Metadata>
+            ) -> Element<'_, Message> {
+        let Some(metadata) = metadata else {
+            return container(
+                column![
+                    vertical_space(),
+                    row![
+                        horizontal_space(),
+                        text("No file loaded."),
+                        horizontal_space(),
+                    ],
+                    vertical_space()
+                ]
+            ).into();
+        };
+
+        let sections = Column::with_children(
+            metadata.sections.iter().map(|x|{
+                column![
+                    text(x.title).size(20),
+                    x.view()
+                ].spacing(4)
+                 .into()
+            })
+        );
+
+        scrollable(sections.padding(8))
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill)
+            .into()
+    }
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //*************************************************************************
+    #[test]
+    fn dates_count_from_1904() {
+        let data = 3_786_912_000_u64.to_be_bytes();
+        assert_eq!(read_date(&data, 0).unwrap(), "2024-01-01 00:00:00");
+    }
+
+    //*************************************************************************
+    #[test]
+    fn dates_too_early_are_invalid() {
+        let data = 0x8000_0000_0000_0000_u64.to_be_bytes();
+        assert_eq!(read_date(&data, 0).unwrap(), "invalid");
+    }
+}
@@ -0,0 +1,1249 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::{
+    io::{Cursor, Error, ErrorKind},
+    ops::Range
+};
+
+use font::{
+    glyph::{Contour, Segment},
+    opentype::{
+        self,
+        postscript::{
+            compact1::{
+                font_set::{character_id_keyed::Encoding, Record},
+                CharacterSet, FontSet
+            },
+            type2::{Operator, Program}
+        },
+        truetype::tables::{
+            glyph_data::{self, Arguments, CompositeDescription, Description, Options, SimpleDescription},
+            FontHeader, GlyphData, GlyphMapping, HorizontalHeader, HorizontalMetrics,
+            MaximumProfile, PostScript
+        }
+    },
+    Glyph, Offset
+};
+
+use crate::{charstrings::{Disassembler, Instruction}, cff2::Cff2, sfnt, variations::{Delta, Variations}, PlotPoint};
+
+//*****************************************************************************
+const MAX_DEPTH: usize = 8;
+
+//*****************************************************************************
+fn malformed() -> Error {
+    Error::new(ErrorKind::InvalidData, "found a malformed glyph")
+}
+
+//*****************************************************************************
+fn invalid(
+            message: String
+        ) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+//*****************************************************************************
+// A component's transform as (xx, xy, yx, yy), so that x' = xx * x + xy * y.
+// The font crate lists a matrix as (xscale, scale01, scale10, yscale), and
+// scale10 is the one that scales y into x.
+fn matrix(
+            options: Options
+        ) -> (f32, f32, f32, f32) {
+    match options {
+        Options::None               => (1.0, 0.0, 0.0, 1.0),
+        Options::Scalar(x)          => (x.into(), 0.0, 0.0, x.into()),
+        Options::Vector(x, y)       => (x.into(), 0.0, 0.0, y.into()),
+        Options::Matrix(a, b, c, d) => (a.into(), c.into(), b.into(), d.into())
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone)]
+pub struct Component {
+    pub glyph_id:  u16,
+    pub depth:     usize,
+    pub arguments: Arguments,
+    pub options:   Options,
+    pub flags:     u16,
+    pub contours:  Range<usize>
+}
+
+//*****************************************************************************
+// Where a point sits in the default glyph and where the variation moved it.
+#[derive(Debug, Clone, Copy)]
+pub struct Shift {
+    pub contour:  usize,
+    pub from:     PlotPoint,
+    pub to:       PlotPoint,
+    pub explicit: bool
+}
+
+//*****************************************************************************
+// The points of a TrueType glyph in font units, with composites flattened
+// and their components kept as parts, as the hinting instructions see them.
+#[derive(Debug, Clone, Default)]
+pub struct GlyphPoints {
+    pub points:       Vec<(f32, f32)>,
+    pub on_curve:     Vec<bool>,
+    pub ends:         Vec<usize>,
+    pub instructions: Vec<u8>,
+    pub origin:       f32,
+    pub advance:      f32,
+    pub parts:        Vec<Part>
+}
+
+//*****************************************************************************
+// A component of a composite, hinted on its own before it's moved into
+// place. Its metrics replace the composite's when it says so.
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub points:    GlyphPoints,
+    pub transform: (f32, f32, f32, f32),
+    pub offset:    (f32, f32),
+    pub round:     bool,
+    pub metrics:   bool
+}
+
+//*****************************************************************************
+pub struct Outline {
+    pub glyph:      Glyph,
+    pub components: Vec<Component>,
+    pub shifts:     Vec<Shift>
+}
+
+//*****************************************************************************
+// Builds a glyph the same way the font crate does, so glyphs read by ID
+// look exactly like glyphs read by character.
+struct Builder {
+    contour:    Contour,
+    glyph:      Glyph,
+    offset:     Offset,
+    origin:     Offset,
+    scale:      (f32, f32, f32, f32),
+    depth:      usize,
+    components: Vec<Component>,
+    shifts:     Vec<Shift>
+}
+
+impl Builder {
+    //*************************************************************************
+    fn new() -> Self {
+        Self {
+            contour:    Contour::default(),
+            glyph:      Glyph::default(),
+            offset:     Offset::default(),
+            origin:     Offset::default(),
+            scale:      (1.0, 0.0, 0.0, 1.0),
+            depth:      0,
+            components: Vec::new(),
+            shifts:     Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    fn flush(&mut self) {
+        if !self.contour.is_empty() {
+            self.glyph.contours.push(std::mem::take(&mut self.contour));
+        }
+    }
+
+    //*************************************************************************
+    fn transform(
+                &self,
+                value: Offset
+            ) -> Offset {
+        let (xx, xy, yx, yy) = self.scale;
+
+        Offset(xx * value.0 + xy * value.1, yx * value.0 + yy * value.1)
+    }
+
+    //*************************************************************************
+    fn nest<F>(
+                &mut self,
+                offset: Offset,
+                origin: Offset,
+                scale:  (f32, f32, f32, f32),
+                build:  F
+            ) -> Result<(), Error>
+            where F: Fn(&mut Builder) -> Result<(), Error> {
+        let previous_offset = self.offset;
+        let previous_origin = self.origin;
+        let previous_scale  = self.scale;
+        let (a, b, c, d)    = self.scale;
+        let (e, f, g, h)    = scale;
+
+        // The component sits where its parent's transform puts it, and is
+        // transformed by its own matrix first.
+        self.offset += self.transform(offset);
+        self.origin += self.transform(origin);
+        self.scale   = (a * e + b * g, a * f + b * h, c * e + d * g, c * f + d * h);
+
+        let result = build(self);
+
+        self.scale  = previous_scale;
+        self.origin = previous_origin;
+        self.offset = previous_offset;
+
+        result
+    }
+
+    //*************************************************************************
+    fn move_absolute(
+                &mut self,
+                position: Offset
+            ) {
+        let last = self.glyph.contours.last().map(|x|x.position).unwrap_or_default();
+        let position = self.offset + self.transform(position);
+
+        self.contour.offset   = position - last;
+        self.contour.position = position;
+    }
+
+    //*************************************************************************
+    fn move_relative(
+                &mut self,
+                offset: Offset
+            ) {
+        let offset = self.transform(offset);
+
+        self.contour.offset   += offset;
+        self.contour.position += offset;
+    }
+
+    //*************************************************************************
+    fn move_control(
+                &mut self,
+                control: Offset
+            ) {
+        let control = self.transform(control);
+
+        match self.contour.segments.get_mut(0) {
+            Some(Segment::Quadratic(x, _)) => *x = control,
+            Some(Segment::Cubic(x, _, _))  => *x = control,
+            _                              => {}
+        }
+    }
+
+    //*************************************************************************
+    fn add_linear(
+                &mut self,
+                a: Offset
+            ) {
+        self.contour.segments.push(Segment::Linear(self.transform(a)));
+    }
+
+    //*************************************************************************
+    fn add_quadratic(
+                &mut self,
+                a: Offset,
+                b: Offset
+            ) {
+        self.contour.segments.push(Segment::Quadratic(self.transform(a), self.transform(b)));
+    }
+
+    //*************************************************************************
+    fn add_cubic(
+                &mut self,
+                a: Offset,
+                b: Offset,
+                c: Offset
+            ) {
+        self.contour.segments.push(
+            Segment::Cubic(self.transform(a), self.transform(b), self.transform(c))
+        );
+    }
+
+    //*************************************************************************
+    fn add_shift(
+                &mut self,
+                contour:  usize,
+                from:     Offset,
+                to:       Offset,
+                explicit: bool
+            ) {
+        // The default point sits where the unvaried components put it.
+        let from = self.origin + self.transform(from);
+        let to   = self.offset + self.transform(to);
+
+        self.shifts.push(
+            Shift {
+                contour,
+                from:     PlotPoint::new(from.0, from.1),
+                to:       PlotPoint::new(to.0, to.1),
+                explicit
+            }
+        );
+    }
+
+    //*************************************************************************
+    fn set_metrics(
+                &mut self,
+                (advance, bearing): (f32, f32)
+            ) {
+        self.glyph.advance_width    = advance;
+        self.glyph.side_bearings.0 = bearing;
+    }
+
+    //*************************************************************************
+    fn bounds(&self) -> (f32, f32, f32, f32) {
+        let mut min = Offset::undefined();
+        let mut max = Offset::undefined();
+        let mut p0  = Offset::default();
+
+        // Every point counts, on the curve or off it.
+        for contour in self.glyph.contours.iter() {
+            p0 += contour.offset;
+            min = min.min(p0);
+            max = max.max(p0);
+
+            for segment in contour.segments.iter() {
+                let offsets = match *segment {
+                    Segment::Linear(a)          => vec![a],
+                    Segment::Quadratic(a, b)    => vec![a, b],
+                    Segment::Cubic(a, b, c)     => vec![a, b, c]
+                };
+                for offset in offsets {
+                    p0 += offset;
+                    min = min.min(p0);
+                    max = max.max(p0);
+                }
+            }
+        }
+
+        (min.0, min.1, max.0, max.1)
+    }
+
+    //*************************************************************************
+    fn finish(
+                self,
+                bounding_box: (f32, f32, f32, f32)
+            ) -> Outline {
+        let mut glyph = self.glyph;
+
+        glyph.bounding_box     = bounding_box;
+        glyph.side_bearings.1 = glyph.advance_width - (glyph.side_bearings.0 + bounding_box.2 - bounding_box.0);
+
+        Outline { glyph, components: self.components, shifts: self.shifts }
+    }
+}
+
+//*****************************************************************************
+enum Source {
+    TrueType(GlyphData),
+    PostScript(Box<FontSet>),
+    Cff2(Box<Cff2>),
+    None
+}
+
+//*****************************************************************************
+pub struct Outlines {
+    source:      Source,
+    metrics:     Option<HorizontalMetrics>,
+    names:       Vec<String>,
+    count:       usize,
+    variations:  Variations,
+    coordinates: Vec<f32>
+}
+
+impl Outlines {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, Error> {
+        let mut tape = Cursor::new(data);
+        let font     = opentype::Font::read(&mut tape)?;
+
+        let header:  Option<FontHeader>       = font.take(&mut tape)?;
+        let profile: Option<MaximumProfile>   = font.take(&mut tape)?;
+        let hhea:    Option<HorizontalHeader> = font.take(&mut tape)?;
+        let count = profile.as_ref().map(|x|x.glyph_count()).unwrap_or(0);
+
+        let metrics = match (&hhea, &profile) {
+            (Some(hhea), Some(profile)) => font.take_given(&mut tape, (hhea, profile))?,
+            _                           => None
+        };
+
+        let mapping: Option<GlyphMapping> = match (&header, &profile) {
+            (Some(header), Some(profile)) => font.take_given(&mut tape, (header, profile))?,
+            _                             => None
+        };
+        let glyph_data = match &mapping {
+            Some(mapping) => font.take_given::<_, GlyphData>(&mut tape, mapping)?,
+            None          => None
+        };
+        let font_set = match glyph_data {
+            Some(_) => None,
+            None    => font.take::<_, FontSet>(&mut tape)?
+        };
+
+        let names = match &font_set {
+            Some(font_set) => Outlines::cff_names(font_set, count),
+            None           => Outlines::post_names(font.take(&mut tape)?, count)
+        };
+
+        let source = match (glyph_data, font_set, sfnt::table(data, b"CFF2")) {
+            (Some(glyph_data), _, _) => Source::TrueType(glyph_data),
+            (_, Some(font_set), _)   => Source::PostScript(Box::new(font_set)),
+            (_, _, Some(cff2))       => Source::Cff2(Box::new(Cff2::read(cff2).map_err(invalid)?)),
+            _                        => Source::None
+        };
+
+        let variations = Variations::read(data).unwrap_or_else(|err|{
+            eprintln!("Error reading variations: {}", err);
+            Variations::default()
+        });
+        let coordinates = vec![0.0; variations.axes().len()];
+
+        Ok(Self { source, metrics, names, count, variations, coordinates })
+    }
+
+    //*************************************************************************
+    fn post_names(
+                post:  Option<PostScript>,
+                count: usize
+            ) -> Vec<String> {
+        // Only version 2 names the glyphs, the first 258 names are the
+        // standard Macintosh ones.
+        let Some(PostScript::Version2(post)) = post else {
+            return Vec::new();
+        };
+
+        (0..count)
+            .map(|i|match post.glyph_name_indices.get(i).map(|&x|x as usize) {
+                Some(x) if x < 258 => MACINTOSH_NAMES[x].to_string(),
+                Some(x)            => post.glyph_names.get(x - 258).cloned().unwrap_or_default(),
+                None               => String::new()
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    fn cff_names(
+                font_set: &FontSet,
+                count:    usize
+            ) -> Vec<String> {
+        let Some(char_set) = font_set.character_sets.first() else {
+            return Vec::new();
+        };
+
+        // The character set leaves out .notdef, glyph 0.
+        let mut ids = vec![0_u16];
+
+        match char_set {
+            CharacterSet::Format0(x) => ids.extend(x.glyphs.iter()),
+            CharacterSet::Format1(x) => {
+                for range in x.ranges.iter() {
+                    ids.extend((0..=range.left_count as u16).map(|i|range.first_string_id + i));
+                }
+            },
+            CharacterSet::Format2(x) => {
+                for range in x.ranges.iter() {
+                    ids.extend((0..=range.left_count).map(|i|range.first_string_id + i));
+                }
+            },
+            predefined => {
+                return (0..count)
+                    .map(|i|predefined.get(i as u16).unwrap_or_default().to_string())
+                    .collect();
+            }
+        }
+
+        // Fonts keyed by CID have CIDs in place of string IDs.
+        let cid = matches!(font_set.records.first(), Some(Record::CharacterIDKeyed(_)));
+
+        ids.iter()
+            .take(count)
+            .map(|&x|if cid {
+                format!("cid{:05}", x)
+            } else {
+                font_set.strings.get(x).unwrap_or_default()
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    //*************************************************************************
+    pub fn name(
+                &self,
+                id: u16
+            ) -> Option<&str> {
+        self.names.get(id as usize)
+            .map(|x|x.as_str())
+            .filter(|x|!x.is_empty())
+    }
+
+    //*************************************************************************
+    pub fn variations(&self) -> &Variations {
+        &self.variations
+    }
+
+    //*************************************************************************
+    pub fn set_location(
+                &mut self,
+                location: &[f32]
+            ) {
+        self.coordinates = self.variations.normalize(location);
+    }
+
+    //*************************************************************************
+    fn is_varied(&self) -> bool {
+        self.coordinates.iter().any(|&x|x != 0.0)
+    }
+
+    //*************************************************************************
+    fn metrics(
+                &self,
+                id: u16
+            ) -> (f32, f32) {
+        let (advance, bearing) = self.metrics.as_ref().map(|x|x.get(id)).unwrap_or((0, 0));
+        let delta = if self.is_varied() {
+            self.variations.advance_delta(id, &self.coordinates).unwrap_or(0.0)
+        } else {
+            0.0
+        };
+
+        (advance as f32 + delta, bearing as f32)
+    }
+
+    //*************************************************************************
+    fn deltas(
+                &self,
+                id:     u16,
+                points: &[Offset],
+                ends:   &[usize]
+            ) -> Result<Option<Vec<Delta>>, Error> {
+        if !self.is_varied() {
+            return Ok(None);
+        }
+
+        // The four phantom points carry the changes to the metrics.
+        let mut points = points.to_vec();
+        points.extend([Offset::default(); 4]);
+
+        self.variations.glyph_deltas(id, &self.coordinates, &points, ends).map_err(invalid)
+    }
+
+    //*************************************************************************
+    pub fn glyph(
+                &self,
+                id: u16
+            ) -> Result<Option<Outline>, Error> {
+        if id as usize >= self.count {
+            return Ok(None);
+        }
+
+        match &self.source {
+            Source::TrueType(glyph_data) => self.draw_truetype(glyph_data, id).map(Some),
+            Source::PostScript(font_set) => self.draw_postscript(font_set, id).map(Some),
+            Source::Cff2(cff2)           => self.draw_cff2(cff2, id).map(Some),
+            Source::None                 => Ok(None)
+        }
+    }
+
+    //*************************************************************************
+    fn draw_truetype(
+                &self,
+                glyph_data: &GlyphData,
+                id:         u16
+            ) -> Result<Outline, Error> {
+        let mut builder = Builder::new();
+        builder.set_metrics(self.metrics(id));
+
+        match glyph_data.get(id as usize).ok_or_else(malformed)? {
+            Some(glyph) => {
+                let advance = self.draw_glyph(glyph_data, &mut builder, id, glyph)?;
+
+                if !self.is_varied() {
+                    let bounds = (glyph.min_x as f32, glyph.min_y as f32, glyph.max_x as f32, glyph.max_y as f32);
+                    return Ok(builder.finish(bounds));
+                }
+
+                // Fonts without an HVAR table move the advance with the
+                // phantom points.
+                let bounds = builder.bounds();
+                if self.variations.advance_delta(id, &self.coordinates).is_none() {
+                    builder.glyph.advance_width += advance;
+                }
+                builder.glyph.side_bearings.0 = bounds.0;
+
+                Ok(builder.finish(bounds))
+            },
+            None => Ok(builder.finish((f32::NAN, f32::NAN, f32::NAN, f32::NAN)))
+        }
+    }
+
+    //*************************************************************************
+    fn draw_glyph(
+                &self,
+                glyph_data: &GlyphData,
+                builder:    &mut Builder,
+                id:         u16,
+                glyph:      &glyph_data::Glyph
+            ) -> Result<f32, Error> {
+        match &glyph.description {
+            Description::Simple(x)    => self.draw_simple(builder, id, x),
+            Description::Composite(x) => self.draw_composite(glyph_data, builder, id, x)
+        }
+    }
+
+    //*************************************************************************
+    fn advance_delta(
+                deltas: &[Delta],
+                count:  usize
+            ) -> f32 {
+        match (deltas.get(count), deltas.get(count + 1)) {
+            (Some(left), Some(right)) => right.offset.0 - left.offset.0,
+            _                         => 0.0
+        }
+    }
+
+    //*************************************************************************
+    fn draw_composite(
+                &self,
+                glyph_data:  &GlyphData,
+                builder:     &mut Builder,
+                id:          u16,
+                description: &CompositeDescription
+            ) -> Result<f32, Error> {
+        if builder.depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidData, "found components nested too deeply"));
+        }
+
+        // Each component's offset varies like a point of a simple glyph.
+        let offsets = description.components.iter()
+            .map(|x|match x.arguments {
+                Arguments::Offsets(x, y) => Offset::from((x, y)),
+                Arguments::Indices(..)   => Offset::default()
+            })
+            .collect::<Vec<Offset>>();
+        let deltas = self.deltas(id, &offsets, &[])?;
+
+        for (i, component) in description.components.iter().enumerate() {
+            let offset = match component.arguments {
+                Arguments::Offsets(..)   => offsets[i] + deltas.as_ref().map(|x|x[i].offset).unwrap_or_default(),
+                Arguments::Indices(..)   => {
+                    return Err(Error::new(ErrorKind::InvalidData, "found a component anchored by points"));
+                }
+            };
+            let scale = matrix(component.options);
+
+            // Components are listed parent first, with the contours each one
+            // added to the glyph.
+            let index = builder.components.len();
+            let start = builder.glyph.contours.len();
+
+            builder.components.push(
+                Component {
+                    glyph_id:  component.glyph_id,
+                    depth:     builder.depth,
+                    arguments: component.arguments,
+                    options:   component.options,
+                    flags:     component.flags.0,
+                    contours:  start..start
+                }
+            );
+
+            let glyph = match glyph_data.get(component.glyph_id as usize) {
+                Some(Some(glyph)) => glyph,
+                Some(None)        => continue,
+                None              => return Err(malformed())
+            };
+
+            if component.flags.should_use_metrics() {
+                builder.set_metrics(self.metrics(component.glyph_id));
+            }
+
+            builder.depth += 1;
+            builder.nest(offset, offsets[i], scale, |builder|self.draw_glyph(glyph_data, builder, component.glyph_id, glyph).map(|_|()))?;
+            builder.depth -= 1;
+
+            builder.components[index].contours = start..builder.glyph.contours.len();
+        }
+
+        Ok(deltas.map(|x|Outlines::advance_delta(&x, offsets.len())).unwrap_or(0.0))
+    }
+
+    //*************************************************************************
+    fn draw_simple(
+                &self,
+                builder:     &mut Builder,
+                id:          u16,
+                description: &SimpleDescription
+            ) -> Result<f32, Error> {
+        let SimpleDescription { end_points, flags, x, y, .. } = description;
+        let count = flags.len();
+
+        if count != x.len() || count != y.len() {
+            return Err(malformed());
+        }
+
+        let mut points = x.iter()
+            .zip(y.iter())
+            .map(|(&x, &y)|Offset::from((x, y)))
+            .collect::<Vec<Offset>>();
+
+        // Deltas apply to the absolute positions of the points.
+        let ends   = end_points.iter().map(|&x|x as usize).collect::<Vec<usize>>();
+        let mut absolute = Vec::with_capacity(count);
+        let mut sum      = Offset::default();
+
+        for &point in points.iter() {
+            sum += point;
+            absolute.push(sum);
+        }
+
+        let mut advance = 0.0;
+
+        if let Some(deltas) = self.deltas(id, &absolute, &ends)? {
+            let mut last    = Offset::default();
+            let mut contour = builder.glyph.contours.len();
+
+            for (i, point) in points.iter_mut().enumerate() {
+                let current = absolute[i] + deltas[i].offset;
+                *point = current - last;
+                last   = current;
+
+                builder.add_shift(contour, absolute[i], current, deltas[i].explicit);
+                if ends.contains(&i) {
+                    contour += 1;
+                }
+            }
+            advance = Outlines::advance_delta(&deltas, count);
+        }
+
+        Outlines::draw_points(builder, end_points, flags, &points)?;
+
+        Ok(advance)
+    }
+
+    //*************************************************************************
+    fn draw_points(
+                builder:    &mut Builder,
+                end_points: &[u16],
+                flags:      &[glyph_data::PointFlags],
+                points:     &[Offset]
+            ) -> Result<(), Error> {
+        let count = flags.len();
+
+        // Coordinates are deltas, two off-curve points in a row have an
+        // implied on-curve point half way between them.
+        let mut i   = 0;
+        let mut sum = Offset::default();
+
+        for k in end_points.iter().map(|&k|k as usize) {
+            if i >= count || k >= count {
+                return Err(malformed());
+            }
+
+            let start       = points[i];
+            let mut control = if flags[i].is_on_curve() { None } else { Some(Offset::default()) };
+            let mut delta   = start;
+            let mut offset  = Offset::default();
+
+            for j in (i + 1)..=k {
+                let current = points[j];
+                delta += current;
+
+                match (flags[j].is_on_curve(), control) {
+                    (false, None) => {
+                        control = Some(current);
+                    },
+                    (false, Some(previous)) => {
+                        let current = current / 2.0;
+                        builder.add_quadratic(previous, current);
+                        offset += previous + current;
+                        control = Some(current);
+                    },
+                    (true, None) => {
+                        builder.add_linear(current);
+                        offset += current;
+                    },
+                    (true, Some(previous)) => {
+                        builder.add_quadratic(previous, current);
+                        offset += previous + current;
+                        control = None;
+                    }
+                }
+            }
+
+            match (flags[i].is_on_curve(), control) {
+                (false, None) => {
+                    let control = (sum + start) - (sum + delta);
+                    builder.move_control(control);
+                    builder.move_absolute(sum + delta);
+                },
+                (false, Some(control)) => {
+                    let current = ((sum + start) - (sum + delta)) / 2.0;
+                    if !control.is_zero() || !current.is_zero() {
+                        builder.add_quadratic(control, current);
+                    }
+                    builder.move_control(current);
+                    builder.move_absolute(sum + delta + current);
+                },
+                (true, None) => {
+                    let current = -offset;
+                    if !current.is_zero() {
+                        builder.add_linear(current);
+                    }
+                    builder.move_absolute(sum + start);
+                },
+                (true, Some(control)) => {
+                    let current = -offset - control;
+                    if !control.is_zero() || !current.is_zero() {
+                        builder.add_quadratic(control, current);
+                    }
+                    builder.move_absolute(sum + start);
+                }
+            }
+
+            builder.flush();
+            sum += delta;
+            i = k + 1;
+        }
+
+        Ok(())
+    }
+
+    //*************************************************************************
+    fn local_subroutines(
+                font_set: &FontSet,
+                id:       u16
+            ) -> Result<&[Vec<u8>], Error> {
+        // Fonts keyed by CID pick the local subroutines by glyph.
+        match font_set.records.first() {
+            Some(Record::CharacterNameKeyed(x)) => Ok(&x.subroutines),
+            Some(Record::CharacterIDKeyed(x))   => {
+                let index = match &x.encoding {
+                    Encoding::Format0(x) => x.dictionary_ids.get(id as usize).copied(),
+                    Encoding::Format3(x) => x.ranges.iter()
+                        .take_while(|x|x.first_glyph_id <= id)
+                        .last()
+                        .map(|x|x.dictionary_id)
+                };
+                Ok(&x.records.get(index.unwrap_or(0) as usize).ok_or_else(malformed)?.subroutines)
+            },
+            None => Err(malformed())
+        }
+    }
+
+    //*************************************************************************
+    pub fn charstring(
+                &self,
+                id: u16
+            ) -> Result<Vec<Instruction>, Error> {
+        match &self.source {
+            Source::PostScript(font_set) => {
+                let code = font_set.character_strings.first()
+                    .and_then(|x|x.get(id as usize))
+                    .ok_or_else(malformed)?;
+                let local = Outlines::local_subroutines(font_set, id)?;
+
+                Disassembler::new(&font_set.subroutines, local).run(code).map_err(invalid)
+            },
+            Source::Cff2(cff2) => cff2.disassemble(id).map_err(invalid),
+            _                  => Ok(Vec::new())
+        }
+    }
+
+    //*************************************************************************
+    pub fn instructions(
+                &self,
+                id: u16
+            ) -> Result<Vec<u8>, Error> {
+        let Source::TrueType(glyph_data) = &self.source else {
+            return Ok(Vec::new());
+        };
+
+        match glyph_data.get(id as usize).ok_or_else(malformed)? {
+            Some(glyph) => match &glyph.description {
+                Description::Simple(x)    => Ok(x.instructions.clone()),
+                Description::Composite(x) => Ok(x.instructions.clone())
+            },
+            None => Ok(Vec::new())
+        }
+    }
+
+    //*************************************************************************
+    pub fn points(
+                &self,
+                id: u16
+            ) -> Result<Option<GlyphPoints>, Error> {
+        let Source::TrueType(glyph_data) = &self.source else {
+            return Ok(None);
+        };
+
+        self.glyph_points(glyph_data, id, 0).map(Some)
+    }
+
+    //*************************************************************************
+    fn glyph_points(
+                &self,
+                glyph_data: &GlyphData,
+                id:         u16,
+                depth:      usize
+            ) -> Result<GlyphPoints, Error> {
+        if depth > MAX_DEPTH {
+            return Err(Error::new(ErrorKind::InvalidData, "found components nested too deeply"));
+        }
+
+        // The origin sits a side bearing to the left of the glyph's box.
+        let (advance, bearing) = self.metrics(id);
+        let mut result = GlyphPoints { origin: -bearing, advance, ..GlyphPoints::default() };
+
+        let Some(glyph) = glyph_data.get(id as usize).ok_or_else(malformed)? else {
+            return Ok(result);
+        };
+        result.origin += glyph.min_x as f32;
+
+        match &glyph.description {
+            Description::Simple(description) => {
+                let SimpleDescription { end_points, flags, x, y, .. } = description;
+                let ends = end_points.iter().map(|&x|x as usize).collect::<Vec<usize>>();
+
+                let mut sum      = Offset::default();
+                let mut absolute = Vec::with_capacity(x.len());
+                for (&x, &y) in x.iter().zip(y.iter()) {
+                    sum += Offset::from((x, y));
+                    absolute.push(sum);
+                }
+
+                if let Some(deltas) = self.deltas(id, &absolute, &ends)? {
+                    for (point, delta) in absolute.iter_mut().zip(deltas.iter()) {
+                        *point += delta.offset;
+                    }
+                }
+
+                result.points       = absolute.iter().map(|x|(x.0, x.1)).collect();
+                result.on_curve     = flags.iter().map(|x|x.is_on_curve()).collect();
+                result.instructions = description.instructions.clone();
+                result.ends         = ends;
+            },
+            Description::Composite(description) => {
+                let offsets = description.components.iter()
+                    .map(|x|match x.arguments {
+                        Arguments::Offsets(x, y) => Offset::from((x, y)),
+                        Arguments::Indices(..)   => Offset::default()
+                    })
+                    .collect::<Vec<Offset>>();
+                let deltas = self.deltas(id, &offsets, &[])?;
+
+                result.instructions = description.instructions.clone();
+
+                // Each component keeps its own points, as they're hinted
+                // before the composite.
+                for (i, component) in description.components.iter().enumerate() {
+                    if let Arguments::Indices(..) = component.arguments {
+                        return Err(Error::new(ErrorKind::InvalidData, "found a component anchored by points"));
+                    }
+                    let offset    = offsets[i] + deltas.as_ref().map(|x|x[i].offset).unwrap_or_default();
+                    let transform        = matrix(component.options);
+                    let (xx, xy, yx, yy) = transform;
+                    let points = self.glyph_points(glyph_data, component.glyph_id, depth + 1)?;
+
+                    let base = result.points.len();
+                    result.points.extend(points.points.iter().map(|&(x, y)|(xx * x + xy * y + offset.0, yx * x + yy * y + offset.1)));
+                    result.on_curve.extend(points.on_curve.iter().copied());
+                    result.ends.extend(points.ends.iter().map(|x|x + base));
+
+                    result.parts.push(Part {
+                        points,
+                        transform,
+                        offset:  (offset.0, offset.1),
+                        round:   component.flags.should_round_xy_to_grid(),
+                        metrics: component.flags.should_use_metrics()
+                    });
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    //*************************************************************************
+    pub fn outline_from_points(
+                &self,
+                id:     u16,
+                points: &GlyphPoints
+            ) -> Result<Outline, Error> {
+        let mut builder = Builder::new();
+        builder.set_metrics(self.metrics(id));
+
+        let flags      = points.on_curve.iter().map(|&x|glyph_data::PointFlags(x as u8)).collect::<Vec<_>>();
+        let end_points = points.ends.iter().map(|&x|x as u16).collect::<Vec<u16>>();
+
+        // Turn the points back into the deltas the glyph data holds.
+        let mut last    = (0.0, 0.0);
+        let mut offsets = Vec::with_capacity(points.points.len());
+        for &(x, y) in points.points.iter() {
+            offsets.push(Offset(x - last.0, y - last.1));
+            last = (x, y);
+        }
+
+        Outlines::draw_points(&mut builder, &end_points, &flags, &offsets)?;
+        builder.glyph.advance_width = points.advance;
+
+        let bounds = builder.bounds();
+        builder.glyph.side_bearings.0 = bounds.0;
+
+        Ok(builder.finish(bounds))
+    }
+
+    //*************************************************************************
+    fn draw_postscript(
+                &self,
+                font_set: &FontSet,
+                id:       u16
+            ) -> Result<Outline, Error> {
+        let code = font_set.character_strings.first()
+            .and_then(|x|x.get(id as usize))
+            .ok_or_else(malformed)?;
+        let local = Outlines::local_subroutines(font_set, id)?;
+
+        let mut program = Program::new(code, &font_set.subroutines, local);
+
+        self.draw_charstring(id, ||program.next())
+    }
+
+    //*************************************************************************
+    fn draw_cff2(
+                &self,
+                cff2: &Cff2,
+                id:   u16
+            ) -> Result<Outline, Error> {
+        let mut program = cff2.program(id, &self.coordinates).map_err(invalid)?;
+
+        self.draw_charstring(id, ||program.next().map_err(invalid))
+    }
+
+    //*************************************************************************
+    fn draw_charstring<F>(
+                &self,
+                id:   u16,
+                mut next: F
+            ) -> Result<Outline, Error>
+            where F: FnMut() -> Result<Option<(Operator, Vec<f32>)>, Error> {
+        let mut builder  = Builder::new();
+        let mut position = Offset::default();
+        let mut min      = Offset::undefined();
+        let mut max      = Offset::undefined();
+        let mut clear    = false;
+
+        // The bounds track where each segment starts and ends, the same as
+        // the font crate.
+        let mut track = |position: &mut Offset, offsets: &[Offset]| {
+            min = min.min(*position);
+            max = max.max(*position);
+
+            for &offset in offsets {
+                *position += offset;
+            }
+            min = min.min(*position);
+            max = max.max(*position);
+        };
+
+        while let Some((operator, operands)) = next()? {
+            let count = operands.len();
+            let o     = |i: usize| operands[i];
+
+            if matches!(operator, Operator::RMoveTo | Operator::HMoveTo | Operator::VMoveTo) {
+                builder.flush();
+            }
+
+            match operator {
+                Operator::RMoveTo => {
+                    if count != 2 && (clear || count != 3) {
+                        return Err(malformed());
+                    }
+                    let offset = Offset(o(0), o(1));
+                    builder.move_relative(offset);
+                    position += offset;
+                },
+                Operator::HMoveTo | Operator::VMoveTo => {
+                    if count != 1 && (clear || count != 2) {
+                        return Err(malformed());
+                    }
+                    let offset = if operator == Operator::HMoveTo { Offset(o(0), 0.0) } else { Offset(0.0, o(0)) };
+                    builder.move_relative(offset);
+                    position += offset;
+                },
+                Operator::RLineTo => {
+                    if count % 2 != 0 {
+                        return Err(malformed());
+                    }
+                    for j in (0..count).step_by(2) {
+                        let a = Offset(o(j), o(j + 1));
+                        builder.add_linear(a);
+                        track(&mut position, &[a]);
+                    }
+                },
+                Operator::HLineTo | Operator::VLineTo => {
+                    let horizontal = if operator == Operator::HLineTo { 0 } else { 1 };
+                    for (i, &x) in operands.iter().enumerate() {
+                        let a = if i % 2 == horizontal { Offset(x, 0.0) } else { Offset(0.0, x) };
+                        builder.add_linear(a);
+                        track(&mut position, &[a]);
+                    }
+                },
+                Operator::RRCurveTo => {
+                    if count % 6 != 0 {
+                        return Err(malformed());
+                    }
+                    for j in (0..count).step_by(6) {
+                        let (a, b, c) = (Offset(o(j), o(j + 1)), Offset(o(j + 2), o(j + 3)), Offset(o(j + 4), o(j + 5)));
+                        builder.add_cubic(a, b, c);
+                        track(&mut position, &[a, b, c]);
+                    }
+                },
+                Operator::HHCurveTo | Operator::VVCurveTo => {
+                    let (start, first) = match count % 4 {
+                        0 => (0, 0.0),
+                        1 => (1, o(0)),
+                        _ => return Err(malformed())
+                    };
+                    for (i, j) in (start..count).step_by(4).enumerate() {
+                        let first = if i == 0 { first } else { 0.0 };
+                        let (a, b, c) = if operator == Operator::HHCurveTo {
+                            (Offset(o(j), first), Offset(o(j + 1), o(j + 2)), Offset(o(j + 3), 0.0))
+                        } else {
+                            (Offset(first, o(j)), Offset(o(j + 1), o(j + 2)), Offset(0.0, o(j + 3)))
+                        };
+                        builder.add_cubic(a, b, c);
+                        track(&mut position, &[a, b, c]);
+                    }
+                },
+                Operator::HVCurveTo | Operator::VHCurveTo => {
+                    let (steps, last) = match count % 4 {
+                        0 => (count / 4, 0.0),
+                        1 => (count / 4, o(count - 1)),
+                        _ => return Err(malformed())
+                    };
+                    let horizontal = if operator == Operator::HVCurveTo { 0 } else { 1 };
+                    for i in 0..steps {
+                        let j    = 4 * i;
+                        let last = if i + 1 == steps { last } else { 0.0 };
+                        let (a, b, c) = if i % 2 == horizontal {
+                            (Offset(o(j), 0.0), Offset(o(j + 1), o(j + 2)), Offset(last, o(j + 3)))
+                        } else {
+                            (Offset(0.0, o(j)), Offset(o(j + 1), o(j + 2)), Offset(o(j + 3), last))
+                        };
+                        builder.add_cubic(a, b, c);
+                        track(&mut position, &[a, b, c]);
+                    }
+                },
+                Operator::RCurveLine => {
+                    if count < 2 || (count - 2) % 6 != 0 {
+                        return Err(malformed());
+                    }
+                    for j in (0..count - 2).step_by(6) {
+                        let (a, b, c) = (Offset(o(j), o(j + 1)), Offset(o(j + 2), o(j + 3)), Offset(o(j + 4), o(j + 5)));
+                        builder.add_cubic(a, b, c);
+                        track(&mut position, &[a, b, c]);
+                    }
+                    let a = Offset(o(count - 2), o(count - 1));
+                    builder.add_linear(a);
+                    track(&mut position, &[a]);
+                },
+                Operator::RLineCurve => {
+                    if count < 6 || (count - 6) % 2 != 0 {
+                        return Err(malformed());
+                    }
+                    for j in (0..count - 6).step_by(2) {
+                        let a = Offset(o(j), o(j + 1));
+                        builder.add_linear(a);
+                        track(&mut position, &[a]);
+                    }
+                    let j = count - 6;
+                    let (a, b, c) = (Offset(o(j), o(j + 1)), Offset(o(j + 2), o(j + 3)), Offset(o(j + 4), o(j + 5)));
+                    builder.add_cubic(a, b, c);
+                    track(&mut position, &[a, b, c]);
+                },
+                Operator::CntrMask | Operator::HintMask | Operator::HStem | Operator::HStemHM |
+                Operator::VStem | Operator::VStemHM | Operator::Flex | Operator::Flex1 |
+                Operator::HFlex | Operator::HFlex1 => {},
+                operator => {
+                    return Err(Error::new(ErrorKind::InvalidData, format!("found an unknown operator {:?}", operator)));
+                }
+            }
+
+            if matches!(operator,
+                    Operator::HMoveTo | Operator::VMoveTo | Operator::RMoveTo | Operator::HStem |
+                    Operator::HStemHM | Operator::VStem | Operator::VStemHM | Operator::CntrMask |
+                    Operator::HintMask) {
+                clear = true;
+            }
+        }
+        builder.flush();
+        builder.set_metrics(self.metrics(id));
+
+        if self.is_varied() {
+            builder.glyph.side_bearings.0 = min.0;
+        }
+
+        Ok(builder.finish((min.0, min.1, max.0, max.1)))
+    }
+}
+
+//*****************************************************************************
+const MACINTOSH_NAMES: [&str; 258] = [
+    ".notdef", ".null", "nonmarkingreturn", "space", "exclam", "quotedbl",
+    "numbersign", "dollar", "percent", "ampersand", "quotesingle", "parenleft",
+    "parenright", "asterisk", "plus", "comma", "hyphen", "period", "slash",
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight",
+    "nine", "colon", "semicolon", "less", "equal", "greater", "question", "at",
+    "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M", "N", "O",
+    "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z", "bracketleft",
+    "backslash", "bracketright", "asciicircum", "underscore", "grave", "a",
+    "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p",
+    "q", "r", "s", "t", "u", "v", "w", "x", "y", "z", "braceleft", "bar",
+    "braceright", "asciitilde", "Adieresis", "Aring", "Ccedilla", "Eacute",
+    "Ntilde", "Odieresis", "Udieresis", "aacute", "agrave", "acircumflex",
+    "adieresis", "atilde", "aring", "ccedilla", "eacute", "egrave",
+    "ecircumflex", "edieresis", "iacute", "igrave", "icircumflex",
+    "idieresis", "ntilde", "oacute", "ograve", "ocircumflex", "odieresis",
+    "otilde", "uacute", "ugrave", "ucircumflex", "udieresis", "dagger",
+    "degree", "cent", "sterling", "section", "bullet", "paragraph",
+    "germandbls", "registered", "copyright", "trademark", "acute", "dieresis",
+    "notequal", "AE", "Oslash", "infinity", "plusminus", "lessequal",
+    "greaterequal", "yen", "mu", "partialdiff", "summation", "product", "pi",
+    "integral", "ordfeminine", "ordmasculine", "Omega", "ae", "oslash",
+    "questiondown", "exclamdown", "logicalnot", "radical", "florin",
+    "approxequal", "Delta", "guillemotleft", "guillemotright", "ellipsis",
+    "nonbreakingspace", "Agrave", "Atilde", "Otilde", "OE", "oe", "endash",
+    "emdash", "quotedblleft", "quotedblright", "quoteleft", "quoteright",
+    "divide", "lozenge", "ydieresis", "Ydieresis", "fraction", "currency",
+    "guilsinglleft", "guilsinglright", "fi", "fl", "daggerdbl",
+    "periodcentered", "quotesinglbase", "quotedblbase", "perthousand",
+    "Acircumflex", "Ecircumflex", "Aacute", "Edieresis", "Egrave", "Iacute",
+    "Icircumflex", "Idieresis", "Igrave", "Oacute", "Ocircumflex", "apple",
+    "Ograve", "Uacute", "Ucircumflex", "Ugrave", "dotlessi", "circumflex",
+    "tilde", "macron", "breve", "dotaccent", "ring", "cedilla",
+    "hungarumlaut", "ogonek", "caron", "Lslash", "lslash", "Scaron", "scaron",
+    "Zcaron", "zcaron", "brokenbar", "Eth", "eth", "Yacute", "yacute", "Thorn",
+    "thorn", "minus", "multiply", "onesuperior", "twosuperior",
+    "threesuperior", "onehalf", "onequarter", "threequarters", "franc",
+    "Gbreve", "gbreve", "Idotaccent", "Scedilla", "scedilla", "Cacute",
+    "cacute", "Ccaron", "ccaron", "dcroat"
+];
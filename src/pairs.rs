@@ -0,0 +1,164 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{
+        scrollable::{Direction, Scrollbar},
+        button, checkbox, row, scrollable, text, text_input, Row
+    },
+    Element
+};
+
+use super::Message;
+
+//*****************************************************************************
+#[derive(Debug, Clone)]
+pub struct PairInfo {
+    pub id:    u16,
+    pub code:  String,
+    pub label: String,
+    pub value: f32
+}
+
+//*****************************************************************************
+pub struct Pairs {
+    show:  bool,
+    input: String,
+    right: Option<u16>,
+    value: Option<f32>,
+    pairs: Vec<PairInfo>
+}
+
+impl Pairs {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            show:  false,
+            input: String::new(),
+            right: None,
+            value: None,
+            pairs: Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    pub fn view(&self) -> Element<'_, Message> {
+        let result = row![
+            checkbox("Kerning pair", self.show).on_toggle(Message::PairToggle)
+        ].padding(4)
+         .spacing(8);
+
+        if !self.show {
+            return result.into();
+        }
+
+        let value = match self.value {
+            Some(value) => format!("Kerning: {}", value),
+            None        => String::from("Kerning: -")
+        };
+
+        let pairs = Row::with_children(
+            self.pairs.iter().map(|x|{
+                let style = if Some(x.id) == self.right {
+                    button::success
+                } else {
+                    button::secondary
+                };
+                button(text(format!("{} {}", x.label, x.value)))
+                    .on_press(Message::PairGlyph(x.id))
+                    .style(style)
+                    .into()
+            })
+        ).spacing(8);
+
+        result.push(text("Right:"))
+            .push(
+                text_input("A or #36", &self.input)
+                    .on_input(Message::PairInput)
+                    .width(100)
+            )
+            .push(text(value).width(120))
+            .push(text(format!("{} pairs:", self.pairs.len())))
+            .push(
+                scrollable(pairs)
+                    .direction(Direction::Horizontal(Scrollbar::default()))
+                    .spacing(4)
+            )
+            .into()
+    }
+
+    //*************************************************************************
+    pub fn is_shown(&self) -> bool {
+        self.show
+    }
+
+    //*************************************************************************
+    pub fn right(&self) -> Option<u16> {
+        self.right
+    }
+
+    //*************************************************************************
+    pub fn clear(&mut self) {
+        self.input = String::new();
+        self.right = None;
+        self.value = None;
+        self.pairs = Vec::new();
+    }
+
+    //*************************************************************************
+    pub fn handle_toggle(
+                &mut self,
+                show: bool
+            ) {
+        self.show = show;
+    }
+
+    //*************************************************************************
+    pub fn handle_input(
+                &mut self,
+                input: String,
+                right: Option<u16>
+            ) {
+        self.input = input;
+        self.right = right;
+    }
+
+    //*************************************************************************
+    pub fn handle_glyph(
+                &mut self,
+                id: u16
+            ) {
+        if let Some(pair) = self.pairs.iter().find(|x|x.id == id) {
+            self.input = pair.code.clone();
+        }
+        self.right = Some(id);
+    }
+
+    //*************************************************************************
+    pub fn handle_pairs(
+                &mut self,
+                value: Option<f32>,
+                pairs: Vec<PairInfo>
+            ) {
+        self.value = value;
+        self.pairs = pairs;
+    }
+}
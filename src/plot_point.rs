@@ -45,6 +45,14 @@ impl PlotPoint {
     pub fn to_point(&self) -> Point {
         Point::new(self.x.trunc() as isize, self.y.trunc() as isize)
     }
+
+    //*************************************************************************
+    pub fn touches(
+                &self,
+                other: PlotPoint
+            ) -> bool {
+        (self.x - other.x).abs() < 0.01 && (self.y - other.y).abs() < 0.01
+    }
 }
 
 impl Add<Offset> for PlotPoint {
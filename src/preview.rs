@@ -0,0 +1,279 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{
+        scrollable::{Direction, Scrollbar},
+        checkbox, column, pick_list, row, scrollable, text, text_input, Column, Row
+    },
+    Element
+};
+
+use super::{Message, Substitutions};
+
+//*****************************************************************************
+// The features a shaper turns on without being asked, the joining forms
+// only apply where the letters join.
+const DEFAULT_FEATURES: [&str; 11] = [
+    "ccmp", "locl", "isol", "fina", "medi", "init", "rlig", "liga", "clig", "calt", "rclt"
+];
+
+//*****************************************************************************
+struct FeatureInfo {
+    tag:     String,
+    lookups: Vec<u16>,
+    enabled: bool
+}
+
+//*****************************************************************************
+pub struct Preview {
+    text:       String,
+    substitute: bool,
+    scripts:    Vec<(String, Vec<String>)>,
+    script:     Option<String>,
+    language:   Option<String>,
+    features:   Vec<FeatureInfo>,
+    lookups:    Vec<String>,
+    steps:      Vec<String>
+}
+
+impl Preview {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            text:       String::new(),
+            substitute: false,
+            scripts:    Vec::new(),
+            script:     None,
+            language:   None,
+            features:   Vec::new(),
+            lookups:    Vec::new(),
+            steps:      Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    pub fn view(&self) -> Element<'_, Message> {
+        let input = row![
+            text("Text:"),
+            text_input("Type a string to lay it out in place of the glyph", &self.text)
+                .on_input(Message::PreviewText),
+            checkbox("Substitutions", self.substitute)
+                .on_toggle(Message::PreviewSubstitute)
+        ].spacing(8);
+
+        if !self.substitute {
+            return input.padding(4).into();
+        }
+
+        let scripts   = self.scripts.iter().map(|x|x.0.clone()).collect::<Vec<String>>();
+        let languages = self.scripts.iter()
+            .find(|x|Some(&x.0) == self.script.as_ref())
+            .map(|x|x.1.clone())
+            .unwrap_or_default();
+
+        let features = Row::with_children(
+            self.features.iter().map(|x|{
+                let tag = x.tag.clone();
+                checkbox(x.tag.as_str(), x.enabled)
+                    .on_toggle(move |enabled|Message::PreviewFeature(tag.clone(), enabled))
+                    .into()
+            })
+        ).spacing(12);
+
+        column![
+            input,
+            row![
+                text("Script:"),
+                pick_list(scripts, self.script.clone(), Message::PreviewScript),
+                text("Language:"),
+                pick_list(languages, self.language.clone(), Message::PreviewLanguage),
+                scrollable(features.padding([0, 4]))
+                    .direction(Direction::Horizontal(Scrollbar::new()))
+            ].spacing(8)
+        ].padding(4)
+         .spacing(4)
+         .into()
+    }
+
+    //*************************************************************************
+    pub fn info_view(&self) -> Element<'_, Message> {
+        let mut fields = Column::new().spacing(4);
+
+        fields = fields.push(text("Applied:"));
+        if self.steps.is_empty() {
+            fields = fields.push(text("No substitutions.").size(12));
+        }
+        for step in self.steps.iter() {
+            fields = fields.push(text(step).size(12));
+        }
+
+        fields = fields.push(text("Features:"));
+        for feature in self.features.iter() {
+            let lookups = feature.lookups.iter()
+                .map(|x|format!("#{}", x))
+                .collect::<Vec<String>>()
+                .join(" ");
+            fields = fields.push(text(format!("{}: {}", feature.tag, lookups)).size(12));
+        }
+
+        fields = fields.push(text("Lookups:"));
+        for lookup in self.lookups.iter() {
+            fields = fields.push(text(lookup).size(12));
+        }
+
+        scrollable(fields.padding(4))
+            .width(300)
+            .into()
+    }
+
+    //*************************************************************************
+    pub fn text(&self) -> Option<&str> {
+        if self.text.is_empty() { None } else { Some(&self.text) }
+    }
+
+    //*************************************************************************
+    pub fn substitution(&self) -> Option<(&str, &str, Vec<String>)> {
+        if !self.substitute {
+            return None;
+        }
+
+        let features = self.features.iter()
+            .filter(|x|x.enabled)
+            .map(|x|x.tag.clone())
+            .collect();
+
+        Some((self.script.as_deref()?, self.language.as_deref()?, features))
+    }
+
+    //*************************************************************************
+    pub fn handle_text(
+                &mut self,
+                text: String
+            ) {
+        self.text = text;
+    }
+
+    //*************************************************************************
+    pub fn handle_substitute(
+                &mut self,
+                substitute: bool
+            ) {
+        self.substitute = substitute;
+        self.steps      = Vec::new();
+    }
+
+    //*************************************************************************
+    pub fn handle_steps(
+                &mut self,
+                steps: Vec<String>
+            ) {
+        self.steps = steps;
+    }
+
+    //*************************************************************************
+    pub fn handle_font(
+                &mut self,
+                substitutions: Option<&Substitutions>
+            ) {
+        self.scripts = Vec::new();
+        self.lookups = Vec::new();
+        self.steps   = Vec::new();
+
+        if let Some(substitutions) = substitutions {
+            self.scripts = substitutions.scripts()
+                .iter()
+                .map(|x|(x.tag.clone(), x.languages.iter().map(|x|x.tag.clone()).collect()))
+                .collect();
+            self.lookups = substitutions.lookups()
+                .iter()
+                .enumerate()
+                .map(|(i, (kind, flags, count))|format!("#{} {}, {} subtables, flags 0x{:04X}", i, kind, count, flags))
+                .collect();
+        }
+
+        // The default script is the best guess until the user picks one.
+        let script = self.scripts.iter()
+            .find(|x|x.0 == "DFLT")
+            .or_else(||self.scripts.first())
+            .map(|x|x.0.clone());
+
+        self.script = None;
+        match script {
+            Some(script) => self.handle_script(script, substitutions),
+            None         => {
+                self.language = None;
+                self.features = Vec::new();
+            }
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_script(
+                &mut self,
+                script:        String,
+                substitutions: Option<&Substitutions>
+            ) {
+        let language = self.scripts.iter()
+            .find(|x|x.0 == script)
+            .and_then(|x|x.1.first().cloned());
+
+        self.script = Some(script);
+        match language {
+            Some(language) => self.handle_language(language, substitutions),
+            None           => {
+                self.language = None;
+                self.features = Vec::new();
+            }
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_language(
+                &mut self,
+                language:      String,
+                substitutions: Option<&Substitutions>
+            ) {
+        let (Some(substitutions), Some(script)) = (substitutions, self.script.as_deref()) else {
+            return;
+        };
+
+        self.features = substitutions.features(script, &language)
+            .into_iter()
+            .map(|(tag, lookups)|FeatureInfo {
+                enabled: DEFAULT_FEATURES.contains(&tag.as_str()),
+                tag, lookups
+            })
+            .collect();
+        self.language = Some(language);
+    }
+
+    //*************************************************************************
+    pub fn handle_feature(
+                &mut self,
+                tag:     String,
+                enabled: bool
+            ) {
+        for feature in self.features.iter_mut().filter(|x|x.tag == tag) {
+            feature.enabled = enabled;
+        }
+    }
+}
@@ -21,13 +21,23 @@ SOFTWARE.
 */
 
 use iced::{Element, Theme};
-use iced::widget::{horizontal_space, pick_list, row, slider, text};
+use iced::widget::{checkbox, column, horizontal_space, pick_list, row, slider, text, Row};
 
-use super::Message;
+use super::{Fill, Guide, Labels, Message, Tab};
 
 //*****************************************************************************
 pub struct Settings {
-    thickness: i32,
+    thickness:  i32,
+    fill:       Fill,
+    anti_alias: bool,
+    points:     bool,
+    labels:     Labels,
+    direction:  bool,
+    deltas:     bool,
+    guides:     Vec<Guide>,
+    pixels:     bool,
+    ppem:       i32,
+    tab:        Tab
 }
 
 impl Settings {
@@ -36,6 +46,16 @@ impl Settings {
                 thickness: i32
             ) -> Self {
         Self {
+            fill:       Fill::Outline,
+            anti_alias: false,
+            points:     false,
+            labels:     Labels::None,
+            direction:  false,
+            deltas:     false,
+            guides:     Guide::DEFAULT.to_vec(),
+            pixels:     false,
+            ppem:       16,
+            tab:        Tab::Glyph,
             thickness
         }
     }
@@ -45,17 +65,69 @@ impl Settings {
                 &self,
                 theme: &Theme
             ) -> Element<Message> {
-        row![
+        let main = row![
             text("Thickness:"),
             slider(1..=31, self.thickness, Message::ChangeThickness)
                 .default(5)
                 .step(2),
             text(format!("{}", self.thickness)),
+            pick_list(Fill::ALL, Some(self.fill), Message::ChangeFill),
+            checkbox("Anti-alias", self.anti_alias).on_toggle(Message::ChangeAntiAlias),
+            checkbox("Points", self.points).on_toggle(Message::ChangePoints),
+            pick_list(Labels::ALL, Some(self.labels), Message::ChangeLabels),
+            checkbox("Direction", self.direction).on_toggle(Message::ChangeDirection),
+            checkbox("Deltas", self.deltas).on_toggle(Message::ChangeDeltas),
+            checkbox("Pixels", self.pixels).on_toggle(Message::ChangePixels),
+            slider(6..=96, self.ppem, Message::ChangePpem)
+                .default(16)
+                .width(160),
+            text(format!("{} ppem", self.ppem)),
             horizontal_space(),
+            pick_list(Tab::ALL, Some(self.tab), Message::ChangeTab),
             pick_list(Theme::ALL, Some(theme.clone()), Message::ChangeTheme)
         ].padding(4)
-         .spacing(8)
-         .into()
+         .spacing(8);
+
+        let guides = Row::with_children(
+            Guide::ALL.iter().map(|&guide| {
+                checkbox(guide.to_string(), self.guides.contains(&guide))
+                    .on_toggle(move |x|Message::ChangeGuide(guide, x))
+                    .into()
+            })
+        ).spacing(12);
+
+        column![
+            main,
+            row![
+                text("Guides:"),
+                guides
+            ].padding(4)
+             .spacing(8)
+        ].into()
+    }
+
+    //*************************************************************************
+    pub fn ppem(&self) -> Option<f32> {
+        if self.pixels { Some(self.ppem as f32) } else { None }
+    }
+
+    //*************************************************************************
+    pub fn size(&self) -> i32 {
+        // The ppem chosen, whether or not the pixels are shown.
+        self.ppem
+    }
+
+    //*************************************************************************
+    pub fn tab(&self) -> Tab {
+        self.tab
+    }
+
+    //*************************************************************************
+    pub fn handle_tab(
+                &mut self,
+                tab: Tab
+            ) {
+        self.tab = tab;
     }
 
     //*************************************************************************
@@ -65,4 +137,81 @@ impl Settings {
             ) {
         self.thickness = thickness;
     }
+
+    //*************************************************************************
+    pub fn handle_anti_alias(
+                &mut self,
+                anti_alias: bool
+            ) {
+        self.anti_alias = anti_alias;
+    }
+
+    //*************************************************************************
+    pub fn handle_fill(
+                &mut self,
+                fill: Fill
+            ) {
+        self.fill = fill;
+    }
+
+    //*************************************************************************
+    pub fn handle_points(
+                &mut self,
+                points: bool
+            ) {
+        self.points = points;
+    }
+
+    //*************************************************************************
+    pub fn handle_labels(
+                &mut self,
+                labels: Labels
+            ) {
+        self.labels = labels;
+    }
+
+    //*************************************************************************
+    pub fn handle_direction(
+                &mut self,
+                direction: bool
+            ) {
+        self.direction = direction;
+    }
+
+    //*************************************************************************
+    pub fn handle_deltas(
+                &mut self,
+                deltas: bool
+            ) {
+        self.deltas = deltas;
+    }
+
+    //*************************************************************************
+    pub fn handle_guide(
+                &mut self,
+                guide: Guide,
+                show:  bool
+            ) {
+        self.guides.retain(|x|*x != guide);
+
+        if show {
+            self.guides.push(guide);
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_pixels(
+                &mut self,
+                pixels: bool
+            ) {
+        self.pixels = pixels;
+    }
+
+    //*************************************************************************
+    pub fn handle_ppem(
+                &mut self,
+                ppem: i32
+            ) {
+        self.ppem = ppem;
+    }
 }
\ No newline at end of file
@@ -0,0 +1,202 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::{woff, woff2};
+
+//*****************************************************************************
+const COLLECTION: &[u8; 4] = b"ttcf";
+
+//*****************************************************************************
+pub fn read_u8(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<u8, String> {
+    data.get(offset)
+        .copied()
+        .ok_or_else(||format!("Unexpected end of data at offset {}.", offset))
+}
+
+//*****************************************************************************
+pub fn read_u16(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<u16, String> {
+    data.get(offset..offset + 2)
+        .map(|x|u16::from_be_bytes([x[0], x[1]]))
+        .ok_or_else(||format!("Unexpected end of data at offset {}.", offset))
+}
+
+//*****************************************************************************
+pub fn read_u32(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<u32, String> {
+    data.get(offset..offset + 4)
+        .map(|x|u32::from_be_bytes([x[0], x[1], x[2], x[3]]))
+        .ok_or_else(||format!("Unexpected end of data at offset {}.", offset))
+}
+
+//*****************************************************************************
+pub fn checksum(
+            tag:  &[u8; 4],
+            data: &[u8]
+        ) -> u32 {
+    // The head table is summed without its checksum adjustment.
+    data.chunks(4)
+        .enumerate()
+        .filter(|(i, _)|tag != b"head" || *i != 2)
+        .map(|(_, x)|{
+            let mut word = [0; 4];
+            word[..x.len()].copy_from_slice(x);
+            u32::from_be_bytes(word)
+        })
+        .fold(0, u32::wrapping_add)
+}
+
+//*****************************************************************************
+pub fn table<'a>(
+            data: &'a [u8],
+            tag:  &[u8; 4]
+        ) -> Option<&'a [u8]> {
+    let num_tables = read_u16(data, 4).ok()? as usize;
+
+    (0..num_tables)
+        .map(|i|12 + i * 16)
+        .find(|&record|data.get(record..record + 4) == Some(&tag[..]))
+        .and_then(|record|{
+            let start  = read_u32(data, record + 8).ok()? as usize;
+            let length = read_u32(data, record + 12).ok()? as usize;
+
+            data.get(start..start + length)
+        })
+}
+
+//*****************************************************************************
+pub fn write_header(
+            output:     &mut Vec<u8>,
+            version:    u32,
+            num_tables: usize
+        ) -> Result<(), String> {
+    // The search fields are 16 bit as well, which keeps a font to fewer
+    // tables than the count could hold.
+    let field = |value: usize|u16::try_from(value)
+        .map_err(|_|format!("A font can't hold {} tables.", num_tables));
+
+    let mut power = 1_usize;
+    let mut log2  = 0_u16;
+
+    while power * 2 <= num_tables {
+        power *= 2;
+        log2  += 1;
+    }
+
+    let count = field(num_tables)?;
+    let range = field(power * 16)?;
+    let shift = field(num_tables * 16 - power * 16)?;
+
+    output.extend_from_slice(&version.to_be_bytes());
+    output.extend_from_slice(&count.to_be_bytes());
+    output.extend_from_slice(&range.to_be_bytes());
+    output.extend_from_slice(&log2.to_be_bytes());
+    output.extend_from_slice(&shift.to_be_bytes());
+
+    Ok(())
+}
+
+//*****************************************************************************
+pub fn build(
+            version: u32,
+            tables:  &[([u8; 4], u32, &[u8])]
+        ) -> Result<Vec<u8>, String> {
+    let mut output = Vec::new();
+    let mut offset = 12 + tables.len() * 16;
+
+    write_header(&mut output, version, tables.len())?;
+
+    // Every table starts on a four byte boundary.
+    for (tag, checksum, data) in tables.iter() {
+        output.extend_from_slice(tag);
+        output.extend_from_slice(&checksum.to_be_bytes());
+        output.extend_from_slice(&(offset as u32).to_be_bytes());
+        output.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += (data.len() + 3) & !3;
+    }
+    for (_, _, data) in tables.iter() {
+        output.extend_from_slice(data);
+        output.resize((output.len() + 3) & !3, 0);
+    }
+
+    Ok(output)
+}
+
+//*****************************************************************************
+fn extract(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<Vec<u8>, String> {
+    let version    = read_u32(data, offset)?;
+    let num_tables = read_u16(data, offset + 4)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+
+    for i in 0..num_tables {
+        let record   = offset + 12 + i * 16;
+        let tag      = data.get(record..record + 4).ok_or("Truncated table directory.")?;
+        let checksum = read_u32(data, record + 4)?;
+        let start    = read_u32(data, record + 8)? as usize;
+        let length   = read_u32(data, record + 12)? as usize;
+        let table    = data.get(start..start + length)
+            .ok_or_else(||format!("Table {} runs past the end of the file.", String::from_utf8_lossy(tag)))?;
+
+        tables.push(([tag[0], tag[1], tag[2], tag[3]], checksum, table));
+    }
+
+    build(version, &tables)
+}
+
+//*****************************************************************************
+pub fn faces(
+            data: &[u8]
+        ) -> Result<Vec<Vec<u8>>, String> {
+    // Web fonts are recognised by their signature, whatever the file is
+    // called.
+    if data.starts_with(woff::SIGNATURE) {
+        return Ok(vec![woff::decode(data)?]);
+    }
+    if data.starts_with(woff2::SIGNATURE) {
+        return woff2::decode(data);
+    }
+    if !data.starts_with(COLLECTION) {
+        return Ok(vec![data.to_vec()]);
+    }
+
+    // Each face of a collection becomes a stand alone font, tables shared
+    // between the faces are copied into each one.
+    // Every face takes four bytes of the header, which bounds the count.
+    let count = read_u32(data, 8)? as usize;
+    let mut result = Vec::with_capacity(count.min(data.len() / 4));
+
+    for i in 0..count {
+        let offset = read_u32(data, 12 + i * 4)? as usize;
+        result.push(extract(data, offset)?);
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,982 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::fmt::Display;
+
+use crate::{
+    layout::{Classes, Coverage, Definitions, Layout, Lookup, Script},
+    sfnt::{self, read_u16}
+};
+
+//*****************************************************************************
+const EXTENSION: u16   = 7;
+const MAX_DEPTH: usize = 8;
+
+//*****************************************************************************
+// Features that only apply to glyphs in the matching joining form.
+const FORMS: [&str; 4] = ["isol", "fina", "medi", "init"];
+
+//*****************************************************************************
+const RIGHT_TO_LEFT: [&str; 6] = ["arab", "hebr", "syrc", "thaa", "nko ", "adlm"];
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Joining {
+    Dual,
+    Right,
+    Transparent,
+    None
+}
+
+impl From<char> for Joining {
+    fn from(value: char) -> Self {
+        match value as u32 {
+            0x0610..=0x061A | 0x064B..=0x065F | 0x0670 | 0x06D6..=0x06DC |
+            0x06DF..=0x06E4 | 0x06E7 | 0x06E8 | 0x06EA..=0x06ED => Self::Transparent,
+
+            0x0622..=0x0625 | 0x0627 | 0x0629 | 0x062F..=0x0632 | 0x0648 |
+            0x0671..=0x0673 | 0x0675..=0x0677 | 0x0688..=0x0699 | 0x06C0 |
+            0x06C3..=0x06CB | 0x06CD | 0x06CF | 0x06D2 | 0x06D3 | 0x06D5 |
+            0x06EE | 0x06EF => Self::Right,
+
+            // The tatweel and the zero width joiner join on both sides.
+            0x0620 | 0x0626 | 0x0628 | 0x062A..=0x062E | 0x0633..=0x063F |
+            0x0640..=0x0647 | 0x0649 | 0x064A | 0x066E | 0x066F |
+            0x0678..=0x0687 | 0x069A..=0x06BF | 0x06C1 | 0x06C2 | 0x06CC |
+            0x06CE | 0x06D0 | 0x06D1 | 0x06FA..=0x06FC | 0x06FF | 0x200D => Self::Dual,
+
+            _ => Self::None
+        }
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Single,
+    Multiple,
+    Alternate,
+    Ligature,
+    Context,
+    ChainedContext,
+    ReverseChained,
+    Unknown(u16)
+}
+
+impl From<u16> for Kind {
+    fn from(value: u16) -> Self {
+        match value {
+            1 => Self::Single,
+            2 => Self::Multiple,
+            3 => Self::Alternate,
+            4 => Self::Ligature,
+            5 => Self::Context,
+            6 => Self::ChainedContext,
+            8 => Self::ReverseChained,
+            x => Self::Unknown(x)
+        }
+    }
+}
+
+//*****************************************************************************
+impl Display for Kind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Single         => f.write_str("Single"),
+            Self::Multiple       => f.write_str("Multiple"),
+            Self::Alternate      => f.write_str("Alternate"),
+            Self::Ligature       => f.write_str("Ligature"),
+            Self::Context        => f.write_str("Context"),
+            Self::ChainedContext => f.write_str("Chained context"),
+            Self::ReverseChained => f.write_str("Reverse chained"),
+            Self::Unknown(x)     => write!(f, "Unknown type {}", x)
+        }
+    }
+}
+
+//*****************************************************************************
+// One lookup changing the glyphs, with the glyphs it took out and the ones
+// it put in their place.
+#[derive(Debug, Clone)]
+pub struct Step {
+    pub lookup:  usize,
+    pub feature: String,
+    pub kind:    Kind,
+    pub before:  Vec<u16>,
+    pub after:   Vec<u16>
+}
+
+//*****************************************************************************
+// A rule of a context subtable, the values are glyphs, classes or the index
+// of a coverage depending on the format. The input leaves out the first
+// glyph, which the subtable's coverage already matched.
+struct Rule {
+    backtrack: Vec<u16>,
+    input:     Vec<u16>,
+    lookahead: Vec<u16>,
+    records:   Vec<(usize, usize)>
+}
+
+impl Rule {
+    //*************************************************************************
+    fn read_list(
+                data:   &[u8],
+                offset: usize,
+                count:  usize
+            ) -> Result<Vec<u16>, String> {
+        (0..count).map(|i|read_u16(data, offset + i * 2)).collect()
+    }
+
+    //*************************************************************************
+    fn read_records(
+                data:   &[u8],
+                offset: usize,
+                count:  usize
+            ) -> Result<Vec<(usize, usize)>, String> {
+        (0..count)
+            .map(|i|Ok((
+                read_u16(data, offset + i * 4)? as usize,
+                read_u16(data, offset + i * 4 + 2)? as usize
+            )))
+            .collect()
+    }
+
+    //*************************************************************************
+    fn read(
+                data:    &[u8],
+                offset:  usize,
+                chained: bool
+            ) -> Result<Self, String> {
+        if !chained {
+            let count   = read_u16(data, offset)? as usize;
+            let records = read_u16(data, offset + 2)? as usize;
+            let input   = Rule::read_list(data, offset + 4, count.saturating_sub(1))?;
+
+            return Ok(Self {
+                backtrack: Vec::new(),
+                lookahead: Vec::new(),
+                records:   Rule::read_records(data, offset + 4 + input.len() * 2, records)?,
+                input
+            });
+        }
+
+        let mut position = offset;
+        let mut list = |skip: usize|{
+            let count = (read_u16(data, position)? as usize).checked_sub(skip)
+                .ok_or("Found a context rule without input.")?;
+            let list  = Rule::read_list(data, position + 2, count)?;
+            position += 2 + count * 2;
+            Ok::<Vec<u16>, String>(list)
+        };
+        let backtrack = list(0)?;
+        let input     = list(1)?;
+        let lookahead = list(0)?;
+        let records   = read_u16(data, position)? as usize;
+
+        Ok(Self {
+            records: Rule::read_records(data, position + 2, records)?,
+            backtrack, input, lookahead
+        })
+    }
+
+    //*************************************************************************
+    fn read_set(
+                data:    &[u8],
+                offset:  usize,
+                chained: bool
+            ) -> Result<Vec<Self>, String> {
+        let count = read_u16(data, offset)? as usize;
+
+        (0..count)
+            .map(|i|Rule::read(data, offset + read_u16(data, offset + 2 + i * 2)? as usize, chained))
+            .collect()
+    }
+
+    //*************************************************************************
+    fn matches<G, B, I, A>(
+                &self,
+                glyphs:    &[u16],
+                at:        usize,
+                ignore:    G,
+                backtrack: B,
+                input:     I,
+                lookahead: A
+            ) -> Option<Vec<usize>>
+            where G: Fn(u16) -> bool,
+                  B: Fn(u16, u16) -> bool,
+                  I: Fn(u16, u16) -> bool,
+                  A: Fn(u16, u16) -> bool {
+        // The backtrack runs away from the glyph, nearest first. The
+        // positions of the input glyphs are returned, the first included.
+        let before = neighbours(glyphs, at, self.backtrack.len(), false, &ignore)?;
+        let inputs = neighbours(glyphs, at, self.input.len(), true, &ignore)?;
+        let after  = neighbours(glyphs, inputs.last().copied().unwrap_or(at), self.lookahead.len(), true, &ignore)?;
+
+        let matched = self.backtrack.iter().zip(before).all(|(&x, i)|backtrack(glyphs[i], x))
+            && self.input.iter().zip(inputs.iter()).all(|(&x, &i)|input(glyphs[i], x))
+            && self.lookahead.iter().zip(after).all(|(&x, i)|lookahead(glyphs[i], x));
+
+        matched.then(||std::iter::once(at).chain(inputs).collect())
+    }
+}
+
+//*****************************************************************************
+// The positions of the glyphs before or after one, passing over those the
+// lookup ignores. Nothing is returned if the sequence runs out first.
+fn neighbours<G>(
+            glyphs:  &[u16],
+            at:      usize,
+            count:   usize,
+            forward: bool,
+            ignore:  G
+        ) -> Option<Vec<usize>>
+        where G: Fn(u16) -> bool {
+    let mut result   = Vec::with_capacity(count);
+    let mut position = at;
+
+    while result.len() < count {
+        position = if forward { position + 1 } else { position.checked_sub(1)? };
+        if !ignore(*glyphs.get(position)?) {
+            result.push(position);
+        }
+    }
+
+    Some(result)
+}
+
+//*****************************************************************************
+// The joining form of each character, for the Arabic style scripts.
+pub fn joining_forms(
+            text: &str
+        ) -> Vec<Option<&'static str>> {
+    let joining = text.chars().map(Joining::from).collect::<Vec<Joining>>();
+    let joins   = |x: &Joining|*x != Joining::Transparent;
+
+    (0..joining.len())
+        .map(|i|{
+            if !matches!(joining[i], Joining::Dual | Joining::Right) {
+                return None;
+            }
+
+            // The marks between the letters don't break the join.
+            let before = joining[..i].iter().rev().find(|x|joins(x));
+            let after  = joining[i + 1..].iter().find(|x|joins(x));
+
+            let previous = before == Some(&Joining::Dual);
+            let next     = joining[i] == Joining::Dual && matches!(after, Some(Joining::Dual | Joining::Right));
+
+            Some(match (previous, next) {
+                (false, false) => "isol",
+                (true, false)  => "fina",
+                (false, true)  => "init",
+                (true, true)   => "medi"
+            })
+        })
+        .collect()
+}
+
+//*****************************************************************************
+pub fn is_right_to_left(
+            script: &str
+        ) -> bool {
+    RIGHT_TO_LEFT.contains(&script)
+}
+
+//*****************************************************************************
+#[derive(Default)]
+pub struct Substitutions {
+    data:        Vec<u8>,
+    layout:      Option<Layout>,
+    definitions: Definitions
+}
+
+impl Substitutions {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let Some(table) = sfnt::table(data, b"GSUB") else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            data:        table.to_vec(),
+            layout:      Some(Layout::read(table, EXTENSION)?),
+            definitions: Definitions::read(data)?
+        })
+    }
+
+    //*************************************************************************
+    pub fn scripts(&self) -> &[Script] {
+        self.layout.as_ref().map(|x|x.scripts.as_slice()).unwrap_or_default()
+    }
+
+    //*************************************************************************
+    pub fn lookups(&self) -> Vec<(Kind, u16, usize)> {
+        self.layout.as_ref()
+            .map(|x|x.lookups.iter().map(|x|(Kind::from(x.kind), x.flags, x.subtables.len())).collect())
+            .unwrap_or_default()
+    }
+
+    //*************************************************************************
+    pub fn features(
+                &self,
+                script:   &str,
+                language: &str
+            ) -> Vec<(String, Vec<u16>)> {
+        let Some(layout) = self.layout.as_ref() else {
+            return Vec::new();
+        };
+        let Some(language) = layout.scripts.iter()
+            .find(|x|x.tag == script)
+            .and_then(|x|x.languages.iter().find(|x|x.tag == language)) else {
+            return Vec::new();
+        };
+
+        // A tag can appear more than once, with the lookups split between
+        // the records.
+        let mut result: Vec<(String, Vec<u16>)> = Vec::new();
+
+        for feature in language.required.iter().chain(language.features.iter()).filter_map(|&x|layout.features.get(x as usize)) {
+            match result.iter_mut().find(|x|x.0 == feature.tag) {
+                Some(entry) => entry.1.extend(feature.lookups.iter().copied()),
+                None        => result.push((feature.tag.clone(), feature.lookups.clone()))
+            }
+        }
+        for entry in result.iter_mut() {
+            entry.1.sort();
+            entry.1.dedup();
+        }
+
+        result
+    }
+
+    //*************************************************************************
+    pub fn apply(
+                &self,
+                glyphs:   &[u16],
+                forms:    &[Option<&'static str>],
+                script:   &str,
+                language: &str,
+                features: &[String]
+            ) -> Result<(Vec<u16>, Vec<Step>), String> {
+        let mut glyphs = glyphs.to_vec();
+        let mut forms  = forms.to_vec();
+        let mut steps  = Vec::new();
+
+        let Some(layout) = self.layout.as_ref() else {
+            return Ok((glyphs, steps));
+        };
+        let Some(system) = layout.scripts.iter()
+            .find(|x|x.tag == script)
+            .and_then(|x|x.languages.iter().find(|x|x.tag == language)) else {
+            return Ok((glyphs, steps));
+        };
+
+        // Every lookup of the chosen features runs over the whole sequence,
+        // in lookup list order. The required feature always runs.
+        let mut lookups = Vec::new();
+
+        for &index in system.required.iter().chain(system.features.iter()) {
+            let Some(feature) = layout.features.get(index as usize) else {
+                continue;
+            };
+            if Some(index) != system.required && !features.contains(&feature.tag) {
+                continue;
+            }
+            for &lookup in feature.lookups.iter() {
+                lookups.push((lookup as usize, feature.tag.clone()));
+            }
+        }
+        lookups.sort_by_key(|x|x.0);
+        lookups.dedup_by_key(|x|x.0);
+
+        for (lookup, feature) in lookups {
+            let Some(table) = layout.lookups.get(lookup) else {
+                continue;
+            };
+            let kind = Kind::from(table.kind);
+
+            // Reverse chained lookups work from the end backwards.
+            let mut at = if kind == Kind::ReverseChained { glyphs.len() } else { 0 };
+
+            loop {
+                let position = if kind == Kind::ReverseChained {
+                    if at == 0 {
+                        break;
+                    }
+                    at - 1
+                } else if at < glyphs.len() {
+                    at
+                } else {
+                    break;
+                };
+
+                // Glyphs the lookup flags ignore are passed over, as are
+                // those in the wrong joining form.
+                let ignored = self.definitions.ignores(glyphs[position], table)
+                    || (FORMS.contains(&feature.as_str()) && forms.get(position).copied().flatten() != Some(feature.as_str()));
+
+                if ignored {
+                    at = if kind == Kind::ReverseChained { position } else { position + 1 };
+                    continue;
+                }
+
+                let before = glyphs.clone();
+                let next   = self.apply_lookup(layout, lookup, &mut glyphs, position, 0)?;
+
+                if before != glyphs {
+                    // Glyphs put in place of others take on their form.
+                    let (prefix, suffix) = Substitutions::changed(&before, &glyphs);
+                    let form             = forms.get(prefix).copied().flatten();
+                    forms.splice(prefix..before.len() - suffix, vec![form; glyphs.len() - prefix - suffix]);
+
+                    steps.push(Substitutions::step(lookup, &feature, kind, &before, &glyphs));
+                }
+
+                at = match (kind, next) {
+                    (Kind::ReverseChained, _) => position,
+                    (_, Some(next))           => next.max(position + 1).min(glyphs.len().max(position + 1)),
+                    (_, None)                 => position + 1
+                };
+            }
+        }
+
+        Ok((glyphs, steps))
+    }
+
+    //*************************************************************************
+    fn changed(
+                before: &[u16],
+                after:  &[u16]
+            ) -> (usize, usize) {
+        // The number of glyphs the same at the start and the end.
+        let prefix = before.iter().zip(after.iter()).take_while(|(a, b)|a == b).count();
+        let suffix = before[prefix..].iter().rev()
+            .zip(after[prefix..].iter().rev())
+            .take_while(|(a, b)|a == b)
+            .count();
+
+        (prefix, suffix)
+    }
+
+    //*************************************************************************
+    fn step(
+                lookup:  usize,
+                feature: &str,
+                kind:    Kind,
+                before:  &[u16],
+                after:   &[u16]
+            ) -> Step {
+        // Only the glyphs that changed are kept.
+        let (prefix, suffix) = Substitutions::changed(before, after);
+
+        Step {
+            lookup,
+            feature: feature.to_string(),
+            kind,
+            before:  before[prefix..before.len() - suffix].to_vec(),
+            after:   after[prefix..after.len() - suffix].to_vec()
+        }
+    }
+
+    //*************************************************************************
+    fn apply_lookup(
+                &self,
+                layout: &Layout,
+                lookup: usize,
+                glyphs: &mut Vec<u16>,
+                at:     usize,
+                depth:  usize
+            ) -> Result<Option<usize>, String> {
+        if depth > MAX_DEPTH {
+            return Err(String::from("Lookups nest too deeply."));
+        }
+        let Some(lookup) = layout.lookups.get(lookup) else {
+            return Ok(None);
+        };
+
+        // The first subtable that applies is the only one.
+        for &subtable in lookup.subtables.iter() {
+            let next = match Kind::from(lookup.kind) {
+                Kind::Single         => self.apply_single(subtable, glyphs, at)?,
+                Kind::Multiple       => self.apply_multiple(subtable, glyphs, at)?,
+                Kind::Alternate      => self.apply_alternate(subtable, glyphs, at)?,
+                Kind::Ligature       => self.apply_ligature(lookup, subtable, glyphs, at)?,
+                Kind::Context        => self.apply_context(layout, lookup, subtable, glyphs, at, depth)?,
+                Kind::ChainedContext => self.apply_context(layout, lookup, subtable, glyphs, at, depth)?,
+                Kind::ReverseChained => self.apply_reverse(lookup, subtable, glyphs, at)?,
+                Kind::Unknown(_)     => None
+            };
+            if next.is_some() {
+                return Ok(next);
+            }
+        }
+
+        Ok(None)
+    }
+
+    //*************************************************************************
+    fn apply_single(
+                &self,
+                offset: usize,
+                glyphs: &mut [u16],
+                at:     usize
+            ) -> Result<Option<usize>, String> {
+        let data     = &self.data;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+
+        let Some(index) = coverage.index(glyphs[at]) else {
+            return Ok(None);
+        };
+
+        glyphs[at] = match read_u16(data, offset)? {
+            1 => glyphs[at].wrapping_add(read_u16(data, offset + 4)?),
+            2 => read_u16(data, offset + 6 + index * 2)?,
+            x => return Err(format!("Unknown single substitution format {}.", x))
+        };
+
+        Ok(Some(at + 1))
+    }
+
+    //*************************************************************************
+    fn apply_multiple(
+                &self,
+                offset: usize,
+                glyphs: &mut Vec<u16>,
+                at:     usize
+            ) -> Result<Option<usize>, String> {
+        let data     = &self.data;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+
+        let Some(index) = coverage.index(glyphs[at]) else {
+            return Ok(None);
+        };
+
+        let sequence = offset + read_u16(data, offset + 6 + index * 2)? as usize;
+        let count    = read_u16(data, sequence)? as usize;
+        let list     = Rule::read_list(data, sequence + 2, count)?;
+
+        glyphs.splice(at..at + 1, list);
+
+        Ok(Some(at + count))
+    }
+
+    //*************************************************************************
+    fn apply_alternate(
+                &self,
+                offset: usize,
+                glyphs: &mut [u16],
+                at:     usize
+            ) -> Result<Option<usize>, String> {
+        let data     = &self.data;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+
+        let Some(index) = coverage.index(glyphs[at]) else {
+            return Ok(None);
+        };
+
+        // Without a choice from the user the first alternate is taken.
+        let set = offset + read_u16(data, offset + 6 + index * 2)? as usize;
+        if read_u16(data, set)? == 0 {
+            return Ok(None);
+        }
+        glyphs[at] = read_u16(data, set + 2)?;
+
+        Ok(Some(at + 1))
+    }
+
+    //*************************************************************************
+    fn apply_ligature(
+                &self,
+                lookup: &Lookup,
+                offset: usize,
+                glyphs: &mut Vec<u16>,
+                at:     usize
+            ) -> Result<Option<usize>, String> {
+        let data     = &self.data;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+
+        let Some(index) = coverage.index(glyphs[at]) else {
+            return Ok(None);
+        };
+
+        // The ligatures are listed by preference, the first that matches
+        // wins. The glyphs passed over stay, after the ligature.
+        let set    = offset + read_u16(data, offset + 6 + index * 2)? as usize;
+        let count  = read_u16(data, set)? as usize;
+        let ignore = |x|self.definitions.ignores(x, lookup);
+
+        for i in 0..count {
+            let ligature   = set + read_u16(data, set + 2 + i * 2)? as usize;
+            let glyph      = read_u16(data, ligature)?;
+            let components = read_u16(data, ligature + 2)? as usize;
+            let rest       = Rule::read_list(data, ligature + 4, components.saturating_sub(1))?;
+
+            let Some(positions) = neighbours(glyphs, at, rest.len(), true, ignore) else {
+                continue;
+            };
+            if positions.iter().zip(rest.iter()).all(|(&x, &y)|glyphs[x] == y) {
+                for &position in positions.iter().rev() {
+                    glyphs.remove(position);
+                }
+                glyphs[at] = glyph;
+                return Ok(Some(at + 1));
+            }
+        }
+
+        Ok(None)
+    }
+
+    //*************************************************************************
+    fn apply_context(
+                &self,
+                layout: &Layout,
+                lookup: &Lookup,
+                offset: usize,
+                glyphs: &mut Vec<u16>,
+                at:     usize,
+                depth:  usize
+            ) -> Result<Option<usize>, String> {
+        let data    = &self.data;
+        let format  = read_u16(data, offset)?;
+        let glyph   = glyphs[at];
+        let chained = Kind::from(lookup.kind) == Kind::ChainedContext;
+        let ignore  = |x|self.definitions.ignores(x, lookup);
+
+        let rule = match (format, chained) {
+            (1, _) => {
+                let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+                let Some(index) = coverage.index(glyph) else {
+                    return Ok(None);
+                };
+                let set = read_u16(data, offset + 6 + index * 2)? as usize;
+                if set == 0 {
+                    return Ok(None);
+                }
+
+                let same = |a: u16, b: u16|a == b;
+                Rule::read_set(data, offset + set, chained)?
+                    .into_iter()
+                    .find_map(|x|x.matches(glyphs, at, ignore, same, same, same).map(|y|(x, y)))
+            },
+            (2, false) => {
+                let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+                if coverage.index(glyph).is_none() {
+                    return Ok(None);
+                }
+                let classes = Classes::read(data, offset + read_u16(data, offset + 4)? as usize)?;
+                let set     = read_u16(data, offset + 8 + classes.class(glyph) as usize * 2).unwrap_or(0) as usize;
+                if set == 0 {
+                    return Ok(None);
+                }
+
+                let class = |a: u16, b: u16|classes.class(a) == b;
+                Rule::read_set(data, offset + set, false)?
+                    .into_iter()
+                    .find_map(|x|x.matches(glyphs, at, ignore, class, class, class).map(|y|(x, y)))
+            },
+            (2, true) => {
+                let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+                if coverage.index(glyph).is_none() {
+                    return Ok(None);
+                }
+                let backtrack = Classes::read(data, offset + read_u16(data, offset + 4)? as usize)?;
+                let input     = Classes::read(data, offset + read_u16(data, offset + 6)? as usize)?;
+                let lookahead = Classes::read(data, offset + read_u16(data, offset + 8)? as usize)?;
+                let set       = read_u16(data, offset + 12 + input.class(glyph) as usize * 2).unwrap_or(0) as usize;
+                if set == 0 {
+                    return Ok(None);
+                }
+
+                Rule::read_set(data, offset + set, true)?
+                    .into_iter()
+                    .find_map(|x|x.matches(
+                        glyphs,
+                        at,
+                        ignore,
+                        |a, b|backtrack.class(a) == b,
+                        |a, b|input.class(a) == b,
+                        |a, b|lookahead.class(a) == b
+                    ).map(|y|(x, y)))
+            },
+            (3, _) => {
+                // Each position has its own coverage, the rule holds the
+                // index of the coverage to check.
+                let (rule, coverages) = self.read_coverages(offset, chained)?;
+
+                if coverages[rule.backtrack.len()].index(glyph).is_none() {
+                    return Ok(None);
+                }
+                let covered = |a: u16, b: u16|coverages[b as usize].index(a).is_some();
+                rule.matches(glyphs, at, ignore, covered, covered, covered).map(|x|(rule, x))
+            },
+            (x, _) => return Err(format!("Unknown context substitution format {}.", x))
+        };
+
+        let Some((rule, mut positions)) = rule else {
+            return Ok(None);
+        };
+
+        // The nested lookups may change the number of glyphs matched, moving
+        // those after the one changed.
+        let mut end = positions.last().map_or(at, |x|x + 1);
+
+        for (sequence, lookup) in rule.records {
+            let Some(&position) = positions.get(sequence) else {
+                continue;
+            };
+            if position >= glyphs.len() {
+                continue;
+            }
+            let count = glyphs.len();
+            self.apply_lookup(layout, lookup, glyphs, position, depth + 1)?;
+
+            let shift = |x: usize|(x + glyphs.len()).saturating_sub(count);
+            for later in positions.iter_mut().filter(|x|**x > position) {
+                *later = shift(*later);
+            }
+            end = shift(end);
+        }
+
+        Ok(Some(end))
+    }
+
+    //*************************************************************************
+    fn read_coverages(
+                &self,
+                offset:  usize,
+                chained: bool
+            ) -> Result<(Rule, Vec<Coverage>), String> {
+        let data         = &self.data;
+        let mut coverages = Vec::new();
+        let mut position = offset + 2;
+
+        // The coverages are read in order, backtrack, input and lookahead,
+        // so the rule can refer to them by index.
+        let mut list = |count: usize, position: usize|{
+            let start = coverages.len() as u16;
+            for i in 0..count {
+                coverages.push(Coverage::read(data, offset + read_u16(data, position + i * 2)? as usize)?);
+            }
+            Ok::<Vec<u16>, String>((start..coverages.len() as u16).collect())
+        };
+
+        let rule = if chained {
+            let count     = read_u16(data, position)? as usize;
+            let backtrack = list(count, position + 2)?;
+            position += 2 + count * 2;
+
+            let count = read_u16(data, position)? as usize;
+            let input = list(count, position + 2)?;
+            position += 2 + count * 2;
+
+            if input.is_empty() {
+                return Err(String::from("Found a context subtable without input."));
+            }
+
+            let count     = read_u16(data, position)? as usize;
+            let lookahead = list(count, position + 2)?;
+            position += 2 + count * 2;
+
+            let records = read_u16(data, position)? as usize;
+            Rule {
+                records: Rule::read_records(data, position + 2, records)?,
+                input:   input.into_iter().skip(1).collect(),
+                backtrack, lookahead
+            }
+        } else {
+            let count   = read_u16(data, position)? as usize;
+            let records = read_u16(data, position + 2)? as usize;
+            let input   = list(count, position + 4)?;
+            Rule {
+                backtrack: Vec::new(),
+                lookahead: Vec::new(),
+                records:   Rule::read_records(data, position + 4 + count * 2, records)?,
+                input:     input.into_iter().skip(1).collect()
+            }
+        };
+
+        if coverages.len() <= rule.backtrack.len() {
+            return Err(String::from("Found a context subtable without input."));
+        }
+
+        Ok((rule, coverages))
+    }
+
+    //*************************************************************************
+    fn apply_reverse(
+                &self,
+                lookup: &Lookup,
+                offset: usize,
+                glyphs: &mut [u16],
+                at:     usize
+            ) -> Result<Option<usize>, String> {
+        let data     = &self.data;
+        let coverage = Coverage::read(data, offset + read_u16(data, offset + 2)? as usize)?;
+
+        let Some(index) = coverage.index(glyphs[at]) else {
+            return Ok(None);
+        };
+
+        let mut position = offset + 4;
+        let mut list = ||{
+            let count = read_u16(data, position)? as usize;
+            let list  = (0..count)
+                .map(|i|Coverage::read(data, offset + read_u16(data, position + 2 + i * 2)? as usize))
+                .collect::<Result<Vec<Coverage>, String>>()?;
+            position += 2 + count * 2;
+            Ok::<Vec<Coverage>, String>(list)
+        };
+        let backtrack = list()?;
+        let lookahead = list()?;
+
+        let ignore = |x|self.definitions.ignores(x, lookup);
+        let before = neighbours(glyphs, at, backtrack.len(), false, ignore);
+        let after  = neighbours(glyphs, at, lookahead.len(), true, ignore);
+
+        let matched = before.zip(after).is_some_and(|(before, after)|{
+            backtrack.iter().zip(before).all(|(x, i)|x.index(glyphs[i]).is_some())
+                && lookahead.iter().zip(after).all(|(x, i)|x.index(glyphs[i]).is_some())
+        });
+
+        if !matched {
+            return Ok(None);
+        }
+        glyphs[at] = read_u16(data, position + 2 + index * 2)?;
+
+        Ok(Some(at + 1))
+    }
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARK: u16 = 9;
+
+    //*************************************************************************
+    fn words(
+                values: &[u16]
+            ) -> Vec<u8> {
+        values.iter().flat_map(|x|x.to_be_bytes()).collect()
+    }
+
+    //*************************************************************************
+    // A font with one calt lookup, replacing glyph 2 with 20 after 1 and
+    // before 3. Glyph 9 is a mark.
+    fn font(
+                flags: u16
+            ) -> Substitutions {
+        let gsub = words(&[
+            // Header, script list, script and language
+            1, 0, 10, 30, 44,
+            1, 0x6C61, 0x746E, 8,
+            4, 0,
+            0, 0xFFFF, 1, 0,
+            // Feature list and feature
+            1, 0x6361, 0x6C74, 8,
+            0, 1, 0,
+            // Lookup list, the chained context lookup and its coverages
+            2, 6, 52,
+            6, flags, 1, 8,
+            3, 1, 20, 1, 26, 1, 32, 1, 0, 1,
+            1, 1, 1,
+            1, 1, 2,
+            1, 1, 3,
+            // The single substitution it calls
+            1, 0, 1, 8,
+            2, 8, 1, 20,
+            1, 1, 2
+        ]);
+        let gdef = words(&[1, 0, 12, 0, 0, 0, 2, 2, 1, 8, 1, MARK, MARK, 3]);
+
+        let tables = [(*b"GDEF", 0, gdef.as_slice()), (*b"GSUB", 0, gsub.as_slice())];
+        Substitutions::read(&sfnt::build(0x00010000, &tables).unwrap()).unwrap()
+    }
+
+    //*************************************************************************
+    fn apply(
+                substitutions: &Substitutions,
+                glyphs:        &[u16]
+            ) -> Vec<u16> {
+        let forms = vec![None; glyphs.len()];
+        substitutions.apply(glyphs, &forms, "latn", "dflt", &[String::from("calt")]).unwrap().0
+    }
+
+    //*************************************************************************
+    #[test]
+    fn chained_context_needs_its_whole_context() {
+        let substitutions = font(0);
+
+        assert_eq!(apply(&substitutions, &[1, 2, 3]), vec![1, 20, 3]);
+        assert_eq!(apply(&substitutions, &[4, 1, 2, 3, 2, 3]), vec![4, 1, 20, 3, 2, 3]);
+        assert_eq!(apply(&substitutions, &[1, 2, 4]), vec![1, 2, 4]);
+        assert_eq!(apply(&substitutions, &[2, 3]), vec![2, 3]);
+        assert_eq!(apply(&substitutions, &[1, 2]), vec![1, 2]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn ignored_marks_are_passed_over() {
+        let glyphs = [1, MARK, 2, MARK, MARK, 3];
+
+        assert_eq!(apply(&font(0), &glyphs), glyphs.to_vec());
+        assert_eq!(apply(&font(0x0008), &glyphs), vec![1, MARK, 20, MARK, MARK, 3]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn marks_of_another_attachment_type_are_passed_over() {
+        // The GDEF here gives no mark attachment classes, so marks are in
+        // class 0 and any type but 0 skips them.
+        let glyphs = [1, MARK, 2, 3];
+
+        assert_eq!(apply(&font(0x0100), &glyphs), vec![1, MARK, 20, 3]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn chained_rules_without_input_are_an_error() {
+        // No backtrack, an input count of 0 and no lookahead.
+        let rule = words(&[0, 0, 0, 0]);
+
+        assert_eq!(Rule::read(&rule, 0, true).err(), Some(String::from("Found a context rule without input.")));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn rules_match_around_ignored_glyphs() {
+        let rule = Rule {
+            backtrack: vec![1],
+            input:     vec![3],
+            lookahead: vec![4],
+            records:   Vec::new()
+        };
+        let same   = |a: u16, b: u16|a == b;
+        let ignore = |x: u16|x == MARK;
+
+        assert_eq!(rule.matches(&[1, MARK, 2, MARK, 3, 4], 2, ignore, same, same, same), Some(vec![2, 4]));
+        assert_eq!(rule.matches(&[1, MARK, 2, MARK, 3, 4], 2, |_|false, same, same, same), None);
+        assert_eq!(rule.matches(&[1, 2, 3], 1, ignore, same, same, same), None);
+    }
+}
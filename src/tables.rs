@@ -0,0 +1,569 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use iced::{
+    widget::{
+        button, column, container, horizontal_space, row, scrollable, text,
+        vertical_rule, vertical_space, Column
+    },
+    Element, Font, Length
+};
+
+use crate::{
+    bytecode,
+    layout::Layout,
+    sfnt::{self, read_u16, read_u32},
+    substitutions::Kind
+};
+use super::{Message, Metadata};
+
+//*****************************************************************************
+const PAGE_SIZE: usize = 4096;
+const ROW_SIZE: usize  = 16;
+
+//*****************************************************************************
+const POSITIONING: [&str; 9] = [
+    "Single adjustment", "Pair adjustment", "Cursive attachment", "Mark to base",
+    "Mark to ligature", "Mark to mark", "Context", "Chained context", "Extension"
+];
+
+//*****************************************************************************
+const MAXIMUM: [&str; 13] = [
+    "Max points", "Max contours", "Max composite points", "Max composite contours",
+    "Max zones", "Max twilight points", "Max storage", "Max function defs",
+    "Max instruction defs", "Max stack elements", "Max size of instructions",
+    "Max component elements", "Max component depth"
+];
+
+//*****************************************************************************
+fn fixed(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<f64, String> {
+    Ok(read_u32(data, offset)? as i32 as f64 / 65536.0)
+}
+
+//*****************************************************************************
+fn version(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<(u32, String), String> {
+    // The minor version's top digit is the one after the point, 0x00005000
+    // is version 0.5.
+    let value = read_u32(data, offset)?;
+
+    Ok((value, format!("{}.{}", value >> 16, (value & 0xFFFF) >> 12)))
+}
+
+//*****************************************************************************
+pub struct Node {
+    label:    String,
+    children: Vec<Node>
+}
+
+impl Node {
+    //*************************************************************************
+    fn new(label: String) -> Self {
+        Self { label, children: Vec::new() }
+    }
+
+    //*************************************************************************
+    fn with(
+                label:    String,
+                children: Vec<Node>
+            ) -> Self {
+        Self { label, children }
+    }
+
+    //*************************************************************************
+    fn push(
+                &self,
+                depth:  u16,
+                column: Column<'static, Message>
+            ) -> Column<'static, Message> {
+        let mut column = column.push(
+            container(text(self.label.clone()).size(14)).padding([0, 16 * depth])
+        );
+
+        for child in self.children.iter() {
+            column = child.push(depth + 1, column);
+        }
+
+        column
+    }
+}
+
+//*****************************************************************************
+pub struct TableInfo {
+    tag:      [u8; 4],
+    offset:   u32,
+    length:   u32,
+    checksum: u32,
+    actual:   u32
+}
+
+impl TableInfo {
+    //*************************************************************************
+    fn name(&self) -> String {
+        String::from_utf8_lossy(&self.tag).into_owned()
+    }
+}
+
+//*****************************************************************************
+pub struct Tables {
+    data:     Vec<u8>,
+    tables:   Vec<TableInfo>,
+    selected: Option<usize>,
+    page:     usize,
+    tree:     Vec<Node>
+}
+
+impl Tables {
+    //*************************************************************************
+    pub fn new() -> Self {
+        Self {
+            data:     Vec::new(),
+            tables:   Vec::new(),
+            selected: None,
+            page:     0,
+            tree:     Vec::new()
+        }
+    }
+
+    //*************************************************************************
+    fn read_directory(
+                data: &[u8]
+            ) -> Result<Vec<TableInfo>, String> {
+        let count = read_u16(data, 4)? as usize;
+
+        (0..count)
+            .map(|i|{
+                let record = 12 + i * 16;
+                let tag    = data.get(record..record + 4).ok_or("Truncated table directory.")?;
+                let tag    = [tag[0], tag[1], tag[2], tag[3]];
+                let offset = read_u32(data, record + 8)?;
+                let length = read_u32(data, record + 12)?;
+                let table  = data.get(offset as usize..offset as usize + length as usize).unwrap_or_default();
+
+                Ok(TableInfo {
+                    checksum: read_u32(data, record + 4)?,
+                    actual:   sfnt::checksum(&tag, table),
+                    tag, offset, length
+                })
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    fn table(&self) -> Option<(&TableInfo, &[u8])> {
+        let info  = self.tables.get(self.selected?)?;
+        let start = info.offset as usize;
+
+        Some((info, self.data.get(start..start + info.length as usize).unwrap_or_default()))
+    }
+
+    //*************************************************************************
+    fn hex(
+                data:   &[u8],
+                offset: usize
+            ) -> String {
+        data.chunks(ROW_SIZE)
+            .enumerate()
+            .map(|(i, x)|{
+                let bytes = x.iter().map(|x|format!("{:02X}", x)).collect::<Vec<String>>().join(" ");
+                let chars = x.iter()
+                    .map(|&x|if x.is_ascii_graphic() || x == b' ' { x as char } else { '.' })
+                    .collect::<String>();
+                format!("{:08X}  {:<48} {}", offset + i * ROW_SIZE, bytes, chars)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    //*************************************************************************
+    fn table_button(
+                &self,
+                idx:  usize,
+                info: &TableInfo
+            ) -> Element<'_, Message> {
+        // Mismatched checksums stand out, with the sum the data really has.
+        let style = if Some(idx) == self.selected {
+            button::success
+        } else if info.checksum != info.actual {
+            button::danger
+        } else {
+            button::secondary
+        };
+        let mut label = format!(
+            "{}  {:08X}  {:8}  {:08X}", info.name(), info.offset, info.length, info.checksum
+        );
+        if info.checksum != info.actual {
+            label = format!("{}  != {:08X}", label, info.actual);
+        }
+
+        button(text(label).font(Font::MONOSPACE).size(14))
+            .on_press(Message::TableSelect(idx))
+            .style(style)
+            .width(Length::Fill)
+            .into()
+    }
+
+    //*************************************************************************
+    pub fn view(&self) -> Element<'_, Message> {
+        if self.tables.is_empty() {
+            return container(
+                column![
+                    vertical_space(),
+                    row![
+                        horizontal_space(),
+                        text("No file loaded."),
+                        horizontal_space(),
+                    ],
+                    vertical_space()
+                ]
+            ).into();
+        }
+
+        let list = Column::with_children(
+            self.tables.iter().enumerate().map(|(idx, info)|self.table_button(idx, info))
+        ).push(text(format!("{} tables", self.tables.len())))
+         .spacing(2)
+         .padding(4);
+
+        let mut detail = column![].spacing(4).padding(4);
+
+        if let Some((info, table)) = self.table() {
+            let pages = table.len().div_ceil(PAGE_SIZE).max(1);
+            let start = self.page * PAGE_SIZE;
+            let end   = (start + PAGE_SIZE).min(table.len());
+
+            let tree = self.tree.iter().fold(Column::new().spacing(2), |column, x|x.push(0, column));
+
+            detail = detail.push(
+                row![
+                    text(format!("{}: {} bytes", info.name(), info.length)),
+                    horizontal_space(),
+                    button("<").on_press_maybe((self.page > 0).then(||Message::TablePage(self.page - 1))),
+                    text(format!("Page {} of {}", self.page + 1, pages)),
+                    button(">").on_press_maybe((self.page + 1 < pages).then(||Message::TablePage(self.page + 1)))
+                ].spacing(8)
+            ).push(
+                row![
+                    scrollable(tree).width(Length::FillPortion(1)).height(Length::Fill),
+                    vertical_rule(8),
+                    scrollable(
+                        text(Tables::hex(&table[start..end], start)).font(Font::MONOSPACE).size(14)
+                    ).height(Length::Fill)
+                ]
+            );
+        }
+
+        row![
+            scrollable(list).width(460).height(Length::Fill),
+            vertical_rule(8),
+            detail.width(Length::Fill)
+        ].height(Length::Fill)
+         .into()
+    }
+
+    //*************************************************************************
+    pub fn handle_font(
+                &mut self,
+                data: Option<&[u8]>
+            ) {
+        self.data     = data.map(|x|x.to_vec()).unwrap_or_default();
+        self.tables   = Vec::new();
+        self.selected = None;
+        self.page     = 0;
+        self.tree     = Vec::new();
+
+        if self.data.is_empty() {
+            return;
+        }
+        match Tables::read_directory(&self.data) {
+            Ok(tables) => self.tables = tables,
+            Err(err)   => eprintln!("Error reading table directory: {}", err)
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_select(
+                &mut self,
+                idx: usize
+            ) {
+        self.selected = Some(idx);
+        self.page     = 0;
+        self.tree     = Vec::new();
+
+        let Some((info, table)) = self.table() else {
+            return;
+        };
+
+        // Only the tables we understand get a tree, a table that can't be
+        // read says why.
+        let tag  = info.tag;
+        let tree = match Metadata::read_fields(&tag, table) {
+            Some(fields) => fields.map(|x|{
+                x.into_iter().map(|(label, value)|Node::new(format!("{}: {}", label, value))).collect()
+            }),
+            None => Tables::read_tree(&tag, table)
+        };
+
+        self.tree = match tree {
+            Ok(tree) => tree,
+            Err(err) => vec![Node::new(format!("Error: {}", err))]
+        };
+    }
+
+    //*************************************************************************
+    pub fn handle_page(
+                &mut self,
+                page: usize
+            ) {
+        self.page = page;
+    }
+
+    //*************************************************************************
+    fn read_tree(
+                tag:  &[u8; 4],
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        match tag {
+            b"cmap" => Tables::read_mapping(data),
+            b"maxp" => Tables::read_maximum(data),
+            b"post" => Tables::read_postscript(data),
+            b"fvar" => Tables::read_axes(data),
+            b"GSUB" => Tables::read_layout(data, 7, true),
+            b"GPOS" => Tables::read_layout(data, 9, false),
+            b"fpgm" => Tables::read_program(data),
+            b"prep" => Tables::read_program(data),
+            b"cvt " => Tables::read_values(data),
+            _       => Ok(Vec::new())
+        }
+    }
+
+    //*************************************************************************
+    fn close_levels(
+                levels: &mut Vec<Vec<Node>>,
+                count:  usize
+            ) {
+        while levels.len() > count {
+            let children = levels.pop().unwrap_or_default();
+
+            if let Some(parent) = levels.last_mut().and_then(|x|x.last_mut()) {
+                parent.children = children;
+            }
+        }
+    }
+
+    //*************************************************************************
+    fn read_program(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        // Each level holds the children of the last node one level up, the
+        // body of an FDEF or IF goes under it.
+        let mut levels: Vec<Vec<Node>> = vec![Vec::new()];
+
+        for operation in bytecode::disassemble(data)? {
+            Tables::close_levels(&mut levels, operation.depth + 1);
+
+            if let Some(level) = levels.last_mut() {
+                level.push(Node::new(format!("{:5} {}", operation.offset, operation)));
+            }
+            if operation.opens() {
+                levels.push(Vec::new());
+            }
+        }
+        Tables::close_levels(&mut levels, 1);
+
+        Ok(levels.pop().unwrap_or_default())
+    }
+
+    //*************************************************************************
+    fn read_values(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        let count  = data.len() / 2;
+        let values = (0..count)
+            .map(|i|Ok(Node::new(format!("{}: {}", i, read_u16(data, i * 2)? as i16))))
+            .collect::<Result<Vec<Node>, String>>()?;
+
+        Ok(vec![Node::with(format!("Values: {}", count), values)])
+    }
+
+    //*************************************************************************
+    fn read_mapping(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        let count = read_u16(data, 2)? as usize;
+
+        let encodings = (0..count)
+            .map(|i|{
+                let record = 4 + i * 8;
+                let offset = read_u32(data, record + 4)?;
+                Ok(Node::new(format!(
+                    "Platform {}, encoding {}: format {} at {}",
+                    read_u16(data, record)?,
+                    read_u16(data, record + 2)?,
+                    read_u16(data, offset as usize)?,
+                    offset
+                )))
+            })
+            .collect::<Result<Vec<Node>, String>>()?;
+
+        Ok(vec![
+            Node::new(format!("Version: {}", read_u16(data, 0)?)),
+            Node::with(format!("Encodings: {}", count), encodings)
+        ])
+    }
+
+    //*************************************************************************
+    fn read_maximum(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        let (value, version) = version(data, 0)?;
+        let mut result = vec![
+            Node::new(format!("Version: {}", version)),
+            Node::new(format!("Number of glyphs: {}", read_u16(data, 4)?))
+        ];
+
+        // Only TrueType outlines have the limits for the interpreter.
+        if value >= 0x00010000 {
+            for (i, label) in MAXIMUM.iter().enumerate() {
+                result.push(Node::new(format!("{}: {}", label, read_u16(data, 6 + i * 2)?)));
+            }
+        }
+
+        Ok(result)
+    }
+
+    //*************************************************************************
+    fn read_postscript(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        let (value, version) = version(data, 0)?;
+        let mut result = vec![
+            Node::new(format!("Version: {}", version)),
+            Node::new(format!("Italic angle: {}", fixed(data, 4)?)),
+            Node::new(format!("Underline position: {}", read_u16(data, 8)? as i16)),
+            Node::new(format!("Underline thickness: {}", read_u16(data, 10)? as i16)),
+            Node::new(format!("Fixed pitch: {}", read_u32(data, 12)? != 0))
+        ];
+
+        if value == 0x00020000 {
+            result.push(Node::new(format!("Number of glyphs: {}", read_u16(data, 32)?)));
+        }
+
+        Ok(result)
+    }
+
+    //*************************************************************************
+    fn read_axes(
+                data: &[u8]
+            ) -> Result<Vec<Node>, String> {
+        let offset    = read_u16(data, 4)? as usize;
+        let count     = read_u16(data, 8)? as usize;
+        let size      = read_u16(data, 10)? as usize;
+        let instances = read_u16(data, 12)?;
+
+        let axes = (0..count)
+            .map(|i|{
+                let axis = offset + i * size;
+                let tag  = data.get(axis..axis + 4).map(String::from_utf8_lossy).unwrap_or_default();
+                Ok(Node::new(format!(
+                    "{}: {} to {}, default {}, name {}",
+                    tag, fixed(data, axis + 4)?, fixed(data, axis + 12)?, fixed(data, axis + 8)?, read_u16(data, axis + 18)?
+                )))
+            })
+            .collect::<Result<Vec<Node>, String>>()?;
+
+        Ok(vec![
+            Node::new(format!("Version: {}.{}", read_u16(data, 0)?, read_u16(data, 2)?)),
+            Node::with(format!("Axes: {}", count), axes),
+            Node::new(format!("Instances: {}", instances))
+        ])
+    }
+
+    //*************************************************************************
+    fn read_layout(
+                data:         &[u8],
+                extension:    u16,
+                substitution: bool
+            ) -> Result<Vec<Node>, String> {
+        let layout = Layout::read(data, extension)?;
+
+        let scripts = layout.scripts.iter()
+            .map(|script|{
+                Node::with(
+                    script.tag.clone(),
+                    script.languages.iter()
+                        .map(|language|{
+                            let features = language.features.iter()
+                                .map(|x|format!("{}", x))
+                                .collect::<Vec<String>>()
+                                .join(" ");
+                            let required = language.required
+                                .map(|x|format!(", required {}", x))
+                                .unwrap_or_default();
+                            Node::new(format!("{}: features {}{}", language.tag, features, required))
+                        })
+                        .collect()
+                )
+            })
+            .collect();
+
+        let features = layout.features.iter()
+            .enumerate()
+            .map(|(i, feature)|{
+                let lookups = feature.lookups.iter()
+                    .map(|x|format!("{}", x))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+                Node::new(format!("{} {}: lookups {}", i, feature.tag, lookups))
+            })
+            .collect();
+
+        let lookups = layout.lookups.iter()
+            .enumerate()
+            .map(|(i, lookup)|{
+                let kind = if substitution {
+                    Kind::from(lookup.kind).to_string()
+                } else {
+                    POSITIONING.get((lookup.kind as usize).wrapping_sub(1))
+                        .map(|x|x.to_string())
+                        .unwrap_or_else(||format!("Unknown type {}", lookup.kind))
+                };
+                Node::with(
+                    format!("{} {}, flags 0x{:04X}", i, kind, lookup.flags),
+                    lookup.subtables.iter()
+                        .map(|&x|Node::new(format!("Subtable at {}, format {}", x, read_u16(data, x).unwrap_or(0))))
+                        .collect()
+                )
+            })
+            .collect();
+
+        Ok(vec![
+            Node::with(format!("Scripts: {}", layout.scripts.len()), scripts),
+            Node::with(format!("Features: {}", layout.features.len()), features),
+            Node::with(format!("Lookups: {}", layout.lookups.len()), lookups)
+        ])
+    }
+}
@@ -0,0 +1,107 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::sync::OnceLock;
+
+//*****************************************************************************
+const NAMES: &str = include_str!("../data/unicode_names.txt");
+
+const JAMO_L: [&str; 19] = [
+    "G", "GG", "N", "D", "DD", "R", "M", "B", "BB", "S", "SS", "", "J", "JJ",
+    "C", "K", "T", "P", "H"
+];
+const JAMO_V: [&str; 21] = [
+    "A", "AE", "YA", "YAE", "EO", "E", "YEO", "YE", "O", "WA", "WAE", "OE",
+    "YO", "U", "WEO", "WE", "WI", "YU", "EU", "YI", "I"
+];
+const JAMO_T: [&str; 28] = [
+    "", "G", "GG", "GS", "N", "NJ", "NH", "D", "L", "LG", "LM", "LB", "LS",
+    "LT", "LP", "LH", "M", "B", "BS", "S", "SS", "NG", "J", "C", "K", "T", "P",
+    "H"
+];
+
+//*****************************************************************************
+fn entries() -> &'static [(u32, u32, &'static str)] {
+    static ENTRIES: OnceLock<Vec<(u32, u32, &'static str)>> = OnceLock::new();
+
+    ENTRIES.get_or_init(|| {
+        let mut result = Vec::new();
+
+        for line in NAMES.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((codes, name)) = line.split_once(';') else { continue };
+
+            let (first, last) = codes.split_once("..").unwrap_or((codes, codes));
+
+            if let (Ok(first), Ok(last)) = (u32::from_str_radix(first, 16), u32::from_str_radix(last, 16)) {
+                result.push((first, last, name));
+            }
+        }
+
+        result
+    })
+}
+
+//*****************************************************************************
+fn hangul(
+            code: u32
+        ) -> String {
+    let index = code - 0xAC00;
+
+    format!(
+        "HANGUL SYLLABLE {}{}{}",
+        JAMO_L[(index / (21 * 28)) as usize],
+        JAMO_V[((index % (21 * 28)) / 28) as usize],
+        JAMO_T[(index % 28) as usize]
+    )
+}
+
+//*****************************************************************************
+pub fn name(
+            ch: char
+        ) -> String {
+    let code    = ch as u32;
+    let entries = entries();
+    let found   = entries.partition_point(|x|x.0 <= code);
+
+    if found > 0 {
+        let (first, last, name) = entries[found - 1];
+
+        if code == first && first == last {
+            return String::from(name);
+        } else if code <= last && first != last {
+            if first == 0xAC00 {
+                return hangul(code);
+            }
+            return format!("{}{:04X}", name, code);
+        }
+    }
+
+    if ch.is_control() {
+        String::from("<control>")
+    } else if (0xE000..=0xF8FF).contains(&code) || code >= 0xF0000 {
+        String::from("<private use>")
+    } else {
+        String::from("<unassigned>")
+    }
+}
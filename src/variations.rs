@@ -0,0 +1,792 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::{
+    fmt::Display,
+    io::Cursor
+};
+
+use font::{
+    opentype::{
+        self,
+        truetype::tables::{names::NameID, Names}
+    },
+    Offset
+};
+
+use crate::{sfnt::{self, read_u16, read_u32, read_u8}, Glyphs};
+
+//*****************************************************************************
+// Each axis of a region is a (start, peak, end) triple.
+type Region = Vec<(f32, f32, f32)>;
+
+//*****************************************************************************
+fn read_f2dot14(
+            data:   &[u8],
+            offset: usize
+        ) -> Result<f32, String> {
+    read_u16(data, offset).map(|x|x as i16 as f32 / 16384.0)
+}
+
+//*****************************************************************************
+fn round_f2dot14(
+            value: f32
+        ) -> f32 {
+    (value * 16384.0).round() / 16384.0
+}
+
+//*****************************************************************************
+pub fn scalar(
+            region:      &[(f32, f32, f32)],
+            coordinates: &[f32]
+        ) -> f32 {
+    let mut result = 1.0;
+
+    // Axes without a peak, or with an invalid region, don't limit the
+    // scalar.
+    for (i, &(start, peak, end)) in region.iter().enumerate() {
+        let value = coordinates.get(i).copied().unwrap_or(0.0);
+
+        if peak == 0.0 || start > peak || peak > end || (start < 0.0 && end > 0.0) || value == peak {
+            continue;
+        }
+        if value <= start || value >= end {
+            return 0.0;
+        }
+
+        result *= if value < peak {
+            (value - start) / (peak - start)
+        } else {
+            (end - value) / (end - peak)
+        };
+    }
+
+    result
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone)]
+pub struct Axis {
+    pub tag:     String,
+    pub min:     f32,
+    pub default: f32,
+    pub max:     f32
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    pub name:        String,
+    pub coordinates: Vec<f32>
+}
+
+impl Display for Instance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+//*****************************************************************************
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Delta {
+    pub offset:   Offset,
+    pub explicit: bool
+}
+
+//*****************************************************************************
+struct ItemData {
+    regions: Vec<u16>,
+    rows:    Vec<Vec<i32>>
+}
+
+//*****************************************************************************
+pub struct Store {
+    regions: Vec<Region>,
+    data:    Vec<ItemData>
+}
+
+impl Store {
+    //*************************************************************************
+    pub fn read(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<Self, String> {
+        let list       = offset + read_u32(data, offset + 2)? as usize;
+        let count      = read_u16(data, offset + 6)? as usize;
+        let axes       = read_u16(data, list)? as usize;
+        let regions    = read_u16(data, list + 2)? as usize;
+
+        let regions = (0..regions)
+            .map(|i|(0..axes)
+                .map(|j|{
+                    let position = list + 4 + (i * axes + j) * 6;
+
+                    Ok((
+                        read_f2dot14(data, position)?,
+                        read_f2dot14(data, position + 2)?,
+                        read_f2dot14(data, position + 4)?
+                    ))
+                })
+                .collect::<Result<Region, String>>()
+            )
+            .collect::<Result<Vec<Region>, String>>()?;
+
+        let data = (0..count)
+            .map(|i|Store::read_item_data(data, offset + read_u32(data, offset + 8 + i * 4)? as usize))
+            .collect::<Result<Vec<ItemData>, String>>()?;
+
+        Ok(Self { regions, data })
+    }
+
+    //*************************************************************************
+    fn read_item_data(
+                data:   &[u8],
+                offset: usize
+            ) -> Result<ItemData, String> {
+        let items      = read_u16(data, offset)? as usize;
+        let words      = read_u16(data, offset + 2)? as usize;
+        let count      = read_u16(data, offset + 4)? as usize;
+        let regions    = (0..count)
+            .map(|i|read_u16(data, offset + 6 + i * 2))
+            .collect::<Result<Vec<u16>, String>>()?;
+
+        // The first columns are wider than the rest, long words double both.
+        let long  = words & 0x8000 != 0;
+        let words = words & 0x7FFF;
+
+        let mut position = offset + 6 + count * 2;
+        let mut rows     = Vec::with_capacity(items);
+
+        for _ in 0..items {
+            let mut row = Vec::with_capacity(count);
+
+            for j in 0..count {
+                let value = if long && j < words {
+                    position += 4;
+                    read_u32(data, position - 4)? as i32
+                } else if long || j < words {
+                    position += 2;
+                    read_u16(data, position - 2)? as i16 as i32
+                } else {
+                    position += 1;
+                    read_u8(data, position - 1)? as i8 as i32
+                };
+                row.push(value);
+            }
+            rows.push(row);
+        }
+
+        Ok(ItemData { regions, rows })
+    }
+
+    //*************************************************************************
+    pub fn scalars(
+                &self,
+                outer:       usize,
+                coordinates: &[f32]
+            ) -> Option<Vec<f32>> {
+        let data = self.data.get(outer)?;
+
+        Some(
+            data.regions.iter()
+                .map(|&x|self.regions.get(x as usize).map(|x|scalar(x, coordinates)).unwrap_or(0.0))
+                .collect()
+        )
+    }
+
+    //*************************************************************************
+    pub fn delta(
+                &self,
+                outer:       usize,
+                inner:       usize,
+                coordinates: &[f32]
+            ) -> f32 {
+        let (Some(scalars), Some(row)) = (
+            self.scalars(outer, coordinates),
+            self.data.get(outer).and_then(|x|x.rows.get(inner))
+        ) else {
+            return 0.0;
+        };
+
+        row.iter()
+            .zip(scalars.iter())
+            .map(|(&delta, &scalar)|delta as f32 * scalar)
+            .sum()
+    }
+}
+
+//*****************************************************************************
+struct GlyphVariations {
+    data:    Vec<u8>,
+    axes:    usize,
+    shared:  Vec<Vec<f32>>,
+    offsets: Vec<usize>
+}
+
+impl GlyphVariations {
+    //*************************************************************************
+    fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let axes          = read_u16(data, 4)? as usize;
+        let shared_count  = read_u16(data, 6)? as usize;
+        let shared_offset = read_u32(data, 8)? as usize;
+        let glyph_count   = read_u16(data, 12)? as usize;
+        let long          = read_u16(data, 14)? & 1 != 0;
+        let array         = read_u32(data, 16)? as usize;
+
+        let shared = (0..shared_count)
+            .map(|i|(0..axes)
+                .map(|j|read_f2dot14(data, shared_offset + (i * axes + j) * 2))
+                .collect::<Result<Vec<f32>, String>>()
+            )
+            .collect::<Result<Vec<Vec<f32>>, String>>()?;
+
+        let offsets = (0..=glyph_count)
+            .map(|i|if long {
+                read_u32(data, 20 + i * 4).map(|x|array + x as usize)
+            } else {
+                read_u16(data, 20 + i * 2).map(|x|array + x as usize * 2)
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        Ok(Self { data: data.to_vec(), axes, shared, offsets })
+    }
+
+    //*************************************************************************
+    fn read_points(
+                data:     &[u8],
+                position: &mut usize
+            ) -> Result<Option<Vec<usize>>, String> {
+        let first = read_u8(data, *position)? as usize;
+        *position += 1;
+
+        // A count of zero means every point in the glyph.
+        if first == 0 {
+            return Ok(None);
+        }
+
+        let count = if first & 0x80 != 0 {
+            *position += 1;
+            ((first & 0x7F) << 8) | read_u8(data, *position - 1)? as usize
+        } else {
+            first
+        };
+
+        // Point numbers are stored as runs of increments.
+        let mut points = Vec::with_capacity(count);
+        let mut last   = 0;
+
+        while points.len() < count {
+            let control = read_u8(data, *position)?;
+            let run     = (control & 0x7F) as usize + 1;
+            *position += 1;
+
+            for _ in 0..run.min(count - points.len()) {
+                if control & 0x80 != 0 {
+                    last += read_u16(data, *position)? as usize;
+                    *position += 2;
+                } else {
+                    last += read_u8(data, *position)? as usize;
+                    *position += 1;
+                }
+                points.push(last);
+            }
+        }
+
+        Ok(Some(points))
+    }
+
+    //*************************************************************************
+    fn read_deltas(
+                data:     &[u8],
+                position: &mut usize,
+                count:    usize
+            ) -> Result<Vec<f32>, String> {
+        let mut deltas = Vec::with_capacity(count);
+
+        while deltas.len() < count {
+            let control = read_u8(data, *position)?;
+            let run     = (control & 0x3F) as usize + 1;
+            *position += 1;
+
+            for _ in 0..run {
+                let delta = match control & 0xC0 {
+                    0x80 => 0.0,
+                    0x40 => { *position += 2; read_u16(data, *position - 2)? as i16 as f32 },
+                    0xC0 => { *position += 4; read_u32(data, *position - 4)? as i32 as f32 },
+                    _    => { *position += 1; read_u8(data, *position - 1)? as i8 as f32 }
+                };
+                deltas.push(delta);
+            }
+        }
+        deltas.truncate(count);
+
+        Ok(deltas)
+    }
+
+    //*************************************************************************
+    fn infer(
+                value: f32,
+                a:     (f32, f32),
+                b:     (f32, f32)
+            ) -> f32 {
+        let ((v1, d1), (v2, d2)) = if a.0 <= b.0 { (a, b) } else { (b, a) };
+
+        if v1 == v2 {
+            if d1 == d2 { d1 } else { 0.0 }
+        } else if value <= v1 {
+            d1
+        } else if value >= v2 {
+            d2
+        } else {
+            d1 + (value - v1) * (d2 - d1) / (v2 - v1)
+        }
+    }
+
+    //*************************************************************************
+    fn interpolate(
+                tuple:  &mut [Option<Offset>],
+                points: &[Offset],
+                ends:   &[usize]
+            ) {
+        let mut start = 0;
+
+        // Untouched points move with the touched points either side of them
+        // in the same contour.
+        for &end in ends {
+            if end < start || end >= tuple.len() {
+                break;
+            }
+
+            let touched = (start..=end)
+                .filter_map(|i|tuple[i].map(|x|(i, x)))
+                .collect::<Vec<(usize, Offset)>>();
+
+            for (k, &(a, da)) in touched.iter().enumerate() {
+                let (b, db) = touched[(k + 1) % touched.len()];
+                let mut i   = if a == end { start } else { a + 1 };
+
+                while i != b {
+                    let (pa, pb, p) = (points[a], points[b], points[i]);
+
+                    tuple[i] = Some(
+                        Offset(
+                            GlyphVariations::infer(p.0, (pa.0, da.0), (pb.0, db.0)),
+                            GlyphVariations::infer(p.1, (pa.1, da.1), (pb.1, db.1))
+                        )
+                    );
+                    i = if i == end { start } else { i + 1 };
+                }
+            }
+
+            start = end + 1;
+        }
+    }
+
+    //*************************************************************************
+    fn deltas(
+                &self,
+                id:          u16,
+                coordinates: &[f32],
+                points:      &[Offset],
+                ends:        &[usize]
+            ) -> Result<Vec<Delta>, String> {
+        let mut result = vec![Delta::default(); points.len()];
+
+        let (Some(&start), Some(&end)) = (self.offsets.get(id as usize), self.offsets.get(id as usize + 1)) else {
+            return Ok(result);
+        };
+        if start >= end {
+            return Ok(result);
+        }
+
+        let data     = self.data.get(start..end).ok_or("Glyph variations run past the end of the table.")?;
+        let count    = read_u16(data, 0)?;
+        let mut next = read_u16(data, 2)? as usize;
+        let shared   = if count & 0x8000 != 0 {
+            GlyphVariations::read_points(data, &mut next)?
+        } else {
+            None
+        };
+
+        let mut header = 4;
+
+        for _ in 0..(count & 0x0FFF) {
+            let size  = read_u16(data, header)? as usize;
+            let index = read_u16(data, header + 2)?;
+            header += 4;
+
+            let read_tuple = |position: usize| (0..self.axes)
+                .map(|i|read_f2dot14(data, position + i * 2))
+                .collect::<Result<Vec<f32>, String>>();
+
+            let peak = if index & 0x8000 != 0 {
+                header += self.axes * 2;
+                read_tuple(header - self.axes * 2)?
+            } else {
+                self.shared.get((index & 0x0FFF) as usize).cloned().ok_or("Unknown shared tuple.")?
+            };
+            let region: Region = if index & 0x4000 != 0 {
+                header += self.axes * 4;
+                let starts = read_tuple(header - self.axes * 4)?;
+                let ends   = read_tuple(header - self.axes * 2)?;
+
+                (0..self.axes).map(|i|(starts[i], peak[i], ends[i])).collect()
+            } else {
+                peak.iter().map(|&x|(x.min(0.0), x, x.max(0.0))).collect()
+            };
+
+            let mut position = next;
+            next += size;
+
+            let scalar = scalar(&region, coordinates);
+            if scalar == 0.0 {
+                continue;
+            }
+
+            let numbers = if index & 0x2000 != 0 {
+                GlyphVariations::read_points(data, &mut position)?
+            } else {
+                shared.clone()
+            };
+            let count = numbers.as_ref().map(|x|x.len()).unwrap_or(points.len());
+            let x     = GlyphVariations::read_deltas(data, &mut position, count)?;
+            let y     = GlyphVariations::read_deltas(data, &mut position, count)?;
+
+            let mut tuple = vec![None; points.len()];
+
+            match numbers {
+                Some(numbers) => {
+                    for (k, &i) in numbers.iter().enumerate() {
+                        if let Some(delta) = tuple.get_mut(i) {
+                            *delta = Some(Offset(x[k], y[k]));
+                        }
+                    }
+                },
+                None => {
+                    for (i, delta) in tuple.iter_mut().enumerate() {
+                        *delta = Some(Offset(x[i], y[i]));
+                    }
+                }
+            }
+
+            for (delta, tuple) in result.iter_mut().zip(tuple.iter()) {
+                delta.explicit |= tuple.is_some();
+            }
+
+            GlyphVariations::interpolate(&mut tuple, points, ends);
+
+            for (delta, tuple) in result.iter_mut().zip(tuple.iter()) {
+                if let Some(offset) = tuple {
+                    delta.offset += *offset * scalar;
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+//*****************************************************************************
+#[derive(Default)]
+pub struct Variations {
+    axes:      Vec<Axis>,
+    instances: Vec<Instance>,
+    segments:  Vec<Vec<(f32, f32)>>,
+    glyphs:    Option<GlyphVariations>,
+    advances:  Option<(Store, Vec<(usize, usize)>)>
+}
+
+impl Variations {
+    //*************************************************************************
+    pub fn read(
+                data: &[u8]
+            ) -> Result<Self, String> {
+        let mut tape = Cursor::new(data);
+        let font     = opentype::Font::read(&mut tape).map_err(|x|format!("{:?}", x))?;
+
+        // The fvar table is read here, instance records may leave out their
+        // PostScript name which the opentype crate always expects.
+        let Some(fvar) = sfnt::table(data, b"fvar") else {
+            return Ok(Self::default());
+        };
+        let names: Option<Names> = font.take(&mut tape).map_err(|x|format!("{:?}", x))?;
+        let name = |id: u16| names.as_ref().and_then(|x|Glyphs::find_name(x, NameID::from(id)));
+
+        let fixed = |offset: usize| read_u32(fvar, offset).map(|x|x as i32 as f32 / 65536.0);
+
+        let offset        = read_u16(fvar, 4)? as usize;
+        let axis_count    = read_u16(fvar, 8)? as usize;
+        let axis_size     = read_u16(fvar, 10)? as usize;
+        let count         = read_u16(fvar, 12)? as usize;
+        let instance_size = read_u16(fvar, 14)? as usize;
+
+        let axes = (0..axis_count)
+            .map(|i|{
+                let position = offset + i * axis_size;
+
+                Ok(Axis {
+                    tag:     String::from_utf8_lossy(fvar.get(position..position + 4).ok_or("Truncated fvar axis.")?).into_owned(),
+                    min:     fixed(position + 4)?,
+                    default: fixed(position + 8)?,
+                    max:     fixed(position + 12)?
+                })
+            })
+            .collect::<Result<Vec<Axis>, String>>()?;
+
+        let start     = offset + axis_count * axis_size;
+        let instances = (0..count)
+            .map(|i|{
+                let position = start + i * instance_size;
+
+                Ok(Instance {
+                    name:        name(read_u16(fvar, position)?).unwrap_or_else(||format!("Instance {}", i)),
+                    coordinates: (0..axis_count)
+                        .map(|j|fixed(position + 4 + j * 4))
+                        .collect::<Result<Vec<f32>, String>>()?
+                })
+            })
+            .collect::<Result<Vec<Instance>, String>>()?;
+
+        let segments = sfnt::table(data, b"avar").map(Variations::read_segments).transpose()?.unwrap_or_default();
+        let glyphs   = sfnt::table(data, b"gvar").map(GlyphVariations::read).transpose()?;
+        let advances = sfnt::table(data, b"HVAR").map(Variations::read_advances).transpose()?;
+
+        Ok(Self { axes, instances, segments, glyphs, advances })
+    }
+
+    //*************************************************************************
+    fn read_segments(
+                data: &[u8]
+            ) -> Result<Vec<Vec<(f32, f32)>>, String> {
+        let axes         = read_u16(data, 6)? as usize;
+        let mut position = 8;
+        let mut result   = Vec::with_capacity(axes);
+
+        for _ in 0..axes {
+            let count = read_u16(data, position)? as usize;
+
+            result.push(
+                (0..count)
+                    .map(|i|Ok((
+                        read_f2dot14(data, position + 2 + i * 4)?,
+                        read_f2dot14(data, position + 4 + i * 4)?
+                    )))
+                    .collect::<Result<Vec<(f32, f32)>, String>>()?
+            );
+            position += 2 + count * 4;
+        }
+
+        Ok(result)
+    }
+
+    //*************************************************************************
+    fn read_advances(
+                data: &[u8]
+            ) -> Result<(Store, Vec<(usize, usize)>), String> {
+        let store  = Store::read(data, read_u32(data, 4)? as usize)?;
+        let offset = read_u32(data, 8)? as usize;
+
+        // Without a map the glyph ID picks the row of the first item data.
+        if offset == 0 {
+            return Ok((store, Vec::new()));
+        }
+
+        let format       = read_u8(data, offset)?;
+        let entry_format = read_u8(data, offset + 1)?;
+        let (count, start) = if format == 0 {
+            (read_u16(data, offset + 2)? as usize, offset + 4)
+        } else {
+            (read_u32(data, offset + 2)? as usize, offset + 6)
+        };
+        let size = ((entry_format >> 4) & 3) as usize + 1;
+        let bits = (entry_format & 0x0F) as u32 + 1;
+
+        let map = (0..count)
+            .map(|i|{
+                let mut entry = 0_u32;
+
+                for k in 0..size {
+                    entry = (entry << 8) | read_u8(data, start + i * size + k)? as u32;
+                }
+
+                Ok(((entry >> bits) as usize, (entry & ((1 << bits) - 1)) as usize))
+            })
+            .collect::<Result<Vec<(usize, usize)>, String>>()?;
+
+        Ok((store, map))
+    }
+
+    //*************************************************************************
+    pub fn axes(&self) -> &[Axis] {
+        &self.axes
+    }
+
+    //*************************************************************************
+    pub fn instances(&self) -> &[Instance] {
+        &self.instances
+    }
+
+    //*************************************************************************
+    pub fn normalize(
+                &self,
+                location: &[f32]
+            ) -> Vec<f32> {
+        self.axes.iter()
+            .enumerate()
+            .map(|(i, axis)|{
+                let value = location.get(i)
+                    .copied()
+                    .unwrap_or(axis.default)
+                    .max(axis.min)
+                    .min(axis.max);
+
+                let value = if value < axis.default {
+                    (value - axis.default) / (axis.default - axis.min)
+                } else if value > axis.default {
+                    (value - axis.default) / (axis.max - axis.default)
+                } else {
+                    0.0
+                };
+                let value = round_f2dot14(value);
+
+                // The avar table bends the straight line between the
+                // extremes.
+                let mapped = self.segments.get(i)
+                    .and_then(|map|map.windows(2).find(|x|value >= x[0].0 && value <= x[1].0))
+                    .map(|x|{
+                        let ((f0, t0), (f1, t1)) = (x[0], x[1]);
+
+                        if f1 == f0 { t0 } else { t0 + (t1 - t0) * (value - f0) / (f1 - f0) }
+                    })
+                    .unwrap_or(value);
+
+                round_f2dot14(mapped)
+            })
+            .collect()
+    }
+
+    //*************************************************************************
+    pub fn glyph_deltas(
+                &self,
+                id:          u16,
+                coordinates: &[f32],
+                points:      &[Offset],
+                ends:        &[usize]
+            ) -> Result<Option<Vec<Delta>>, String> {
+        match &self.glyphs {
+            Some(glyphs) => glyphs.deltas(id, coordinates, points, ends).map(Some),
+            None         => Ok(None)
+        }
+    }
+
+    //*************************************************************************
+    pub fn advance_delta(
+                &self,
+                id:          u16,
+                coordinates: &[f32]
+            ) -> Option<f32> {
+        let (store, map) = self.advances.as_ref()?;
+
+        let (outer, inner) = if map.is_empty() {
+            (0, id as usize)
+        } else {
+            map.get(id as usize).or(map.last()).copied()?
+        };
+
+        Some(store.delta(outer, inner, coordinates))
+    }
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //*************************************************************************
+    fn points(
+                values: &[(f32, f32)]
+            ) -> Vec<Offset> {
+        values.iter().map(|&(x, y)|Offset(x, y)).collect()
+    }
+
+    //*************************************************************************
+    #[test]
+    fn untouched_points_follow_their_neighbours() {
+        // Between two touched points the delta is interpolated, past them it
+        // is the delta of the nearer one.
+        let outline   = points(&[(0.0, 0.0), (100.0, 50.0), (200.0, 100.0), (300.0, 0.0)]);
+        let mut tuple = vec![Some(Offset(10.0, 4.0)), None, Some(Offset(30.0, 8.0)), None];
+
+        GlyphVariations::interpolate(&mut tuple, &outline, &[3]);
+
+        assert_eq!(tuple, vec![
+            Some(Offset(10.0, 4.0)),
+            Some(Offset(20.0, 6.0)),
+            Some(Offset(30.0, 8.0)),
+            Some(Offset(30.0, 4.0))
+        ]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn one_touched_point_moves_its_contour() {
+        // Contours are interpolated on their own, one without touched
+        // points isn't moved.
+        let outline   = points(&[(0.0, 0.0), (0.0, 100.0), (100.0, 0.0), (500.0, 0.0), (500.0, 100.0)]);
+        let mut tuple = vec![None, Some(Offset(5.0, -5.0)), None, None, None];
+
+        GlyphVariations::interpolate(&mut tuple, &outline, &[2, 4]);
+
+        assert_eq!(tuple, vec![
+            Some(Offset(5.0, -5.0)),
+            Some(Offset(5.0, -5.0)),
+            Some(Offset(5.0, -5.0)),
+            None,
+            None
+        ]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn touched_points_in_line_disagreeing_leave_others_still() {
+        // Two touched points at the same x with different deltas give no
+        // delta in x, the y deltas still interpolate.
+        let outline   = points(&[(50.0, 0.0), (50.0, 50.0), (50.0, 100.0)]);
+        let mut tuple = vec![Some(Offset(10.0, 0.0)), None, Some(Offset(-10.0, 20.0))];
+
+        GlyphVariations::interpolate(&mut tuple, &outline, &[2]);
+
+        assert_eq!(tuple[1], Some(Offset(0.0, 10.0)));
+    }
+
+    //*************************************************************************
+    #[test]
+    fn contour_ends_past_the_points_are_ignored() {
+        let outline   = points(&[(0.0, 0.0), (100.0, 0.0)]);
+        let mut tuple = vec![Some(Offset(1.0, 1.0)), None];
+
+        GlyphVariations::interpolate(&mut tuple, &outline, &[1, 7]);
+
+        assert_eq!(tuple[1], Some(Offset(1.0, 1.0)));
+    }
+}
@@ -19,18 +19,24 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
+use std::cell::Cell;
+
 use iced::{
+    mouse::ScrollDelta,
     widget::{
         image::Handle,
-        column, horizontal_rule, horizontal_space, image, responsive,
-        row, text, vertical_space
+        scrollable::{
+            AbsoluteOffset, Direction, Id, Scrollbar, Viewport
+        },
+        button, column, horizontal_rule, horizontal_space, image, mouse_area,
+        responsive, row, scrollable, text, vertical_space
     },
-    Element
+    Element, Length, Size, Task
 };
 
-use crate::{Lines, Message};
+use crate::{Def, Fill, Guide, Guides, Labels, Lines, Mark, Message, PlotPoint, Shift};
 
-use canvas::{Canvas, Dir, StrokeFactory};
+use canvas::{Canvas, Dir, Polygon, StrokeFactory, Winding};
 use cubic::Cubic;
 use graph::Graph;
 use quadratic::Quadratic;
@@ -45,16 +51,24 @@ mod graph;
 mod point;
 mod quadratic;
 
+//*****************************************************************************
+const SCROLL_ID: &str = "view";
+
 //*****************************************************************************
 pub struct View {
-    top:    f32,
-    bottom: f32,
-    left:   f32,
-    right:  f32,
-    graph:  Graph,
-    width:  f32,
-    height: f32,
-    handle: Handle
+    top:       f32,
+    bottom:    f32,
+    left:      f32,
+    right:     f32,
+    graph:     Graph,
+    width:     f32,
+    height:    f32,
+    handle:    Handle,
+    viewport:  Cell<Size>,
+    offset:    AbsoluteOffset,
+    cursor:    iced::Point,
+    grab:      Option<iced::Point>,
+    direction: bool
 }
 
 impl View {
@@ -66,25 +80,64 @@ impl View {
         graph.draw(&Lines::new());
 
         Self {
-            top:    0.0,
-            bottom: 0.0,
-            left:   0.0,
-            right:  0.0,
-            width:  10.0,
-            height: 10.0,
-            handle: graph.canvas().handle(),
+            top:       0.0,
+            bottom:    0.0,
+            left:      0.0,
+            right:     0.0,
+            width:     10.0,
+            height:    10.0,
+            handle:    graph.canvas().handle(),
+            viewport:  Cell::new(Size::ZERO),
+            offset:    AbsoluteOffset { x: 0.0, y: 0.0 },
+            cursor:    iced::Point::ORIGIN,
+            grab:      None,
+            direction: false,
             graph
         }
     }
 
+    //*************************************************************************
+    fn visible(&self) -> (f32, f32, f32, f32) {
+        let size  = self.viewport.get();
+        let scale = self.graph.scale();
+
+        let (left, right) = if self.width > size.width {
+            let left = self.left + self.offset.x / scale;
+            (left, left + size.width / scale)
+        } else {
+            (self.left, self.right)
+        };
+        let (top, bottom) = if self.height > size.height {
+            let top = self.top - self.offset.y / scale;
+            (top, top - size.height / scale)
+        } else {
+            (self.top, self.bottom)
+        };
+
+        (top, bottom, left, right)
+    }
+
     //*************************************************************************
     pub fn view(
                 &self
-            ) -> Element<Message> {
-        let top_left     = format!("({}, {})", self.left, self.top);
-        let top_right    = format!("({}, {})", self.right, self.top);
-        let bottom_left  = format!("({}, {})", self.left, self.bottom);
-        let bottom_right = format!("({}, {})", self.right, self.bottom);
+            ) -> Element<'_, Message> {
+        let (top, bottom, left, right) = self.visible();
+
+        let top_left     = format!("({:.0}, {:.0})", left, top);
+        let top_right    = format!("({:.0}, {:.0})", right, top);
+        let bottom_left  = format!("({:.0}, {:.0})", left, bottom);
+        let bottom_right = format!("({:.0}, {:.0})", right, bottom);
+
+        let winding = if self.direction {
+            self.graph.clockwise()
+                .iter()
+                .enumerate()
+                .map(|(i, &x)|format!("#{} {}", i, if x { "CW" } else { "CCW" }))
+                .collect::<Vec<String>>()
+                .join("  ")
+        } else {
+            String::new()
+        };
 
         let width  = self.width;
         let height = self.height;
@@ -94,38 +147,50 @@ impl View {
             row![
                 text(top_left),
                 horizontal_space(),
+                button("Fit").style(button::secondary).on_press(Message::ViewFit),
+                button("1:1").style(button::secondary).on_press(Message::ViewActual),
+                text(format!("{:.0}%", self.graph.scale() * 100.0)).width(60),
+                horizontal_space(),
                 text(top_right)
-            ].padding(4),
+            ].padding(4)
+             .spacing(8),
             responsive(move |size| {
-                if width < size.width && height < size.height {
+                self.viewport.set(size);
+
+                let picture = mouse_area(image(&self.handle))
+                    .on_scroll(Message::ViewZoom)
+                    .on_press(Message::ViewGrab(true))
+                    .on_release(Message::ViewGrab(false))
+                    .on_move(Message::ViewMove);
+
+                if width <= size.width && height <= size.height {
                     column![
                         vertical_space(),
                         row![
                             horizontal_space(),
-                            image(&self.handle),
+                            picture,
                             horizontal_space(),
                         ],
                         vertical_space(),
                     ].into()
-                } else if width - size.width > height - size.height {
-                    column![
-                        vertical_space(),
-                        image(&self.handle),
-                        vertical_space(),
-                    ].into()
-                } else if height - size.height > width - size.width {
-                    row![
-                        horizontal_space(),
-                        image(&self.handle),
-                        horizontal_space(),
-                    ].into()
                 } else {
-                    image(&self.handle).into()
+                    scrollable(picture)
+                        .id(Id::new(SCROLL_ID))
+                        .direction(Direction::Both {
+                            vertical:   Scrollbar::default(),
+                            horizontal: Scrollbar::default()
+                        })
+                        .on_scroll(Message::ViewScrolled)
+                        .width(Length::Fill)
+                        .height(Length::Fill)
+                        .into()
                 }
             }),
             row![
                 text(bottom_left),
                 horizontal_space(),
+                text(winding),
+                horizontal_space(),
                 text(bottom_right)
             ].padding(4),
             horizontal_rule(1),
@@ -140,6 +205,197 @@ impl View {
         self.graph.handle_thickness(thickness);
     }
 
+    //*************************************************************************
+    pub fn handle_anti_alias(
+                &mut self,
+                smooth: bool
+            ) {
+        self.graph.handle_anti_alias(smooth);
+    }
+
+    //*************************************************************************
+    pub fn handle_fill(
+                &mut self,
+                fill: Fill
+            ) {
+        self.graph.handle_fill(fill);
+    }
+
+    //*************************************************************************
+    pub fn handle_points(
+                &mut self,
+                points: bool
+            ) {
+        self.graph.handle_points(points);
+    }
+
+    //*************************************************************************
+    pub fn handle_labels(
+                &mut self,
+                labels: Labels
+            ) {
+        self.graph.handle_labels(labels);
+    }
+
+    //*************************************************************************
+    pub fn handle_direction(
+                &mut self,
+                direction: bool
+            ) {
+        self.direction = direction;
+        self.graph.handle_direction(direction);
+    }
+
+    //*************************************************************************
+    pub fn handle_deltas(
+                &mut self,
+                deltas: bool
+            ) {
+        self.graph.handle_deltas(deltas);
+    }
+
+    //*************************************************************************
+    pub fn handle_shifts(
+                &mut self,
+                shifts: Vec<Shift>
+            ) {
+        self.graph.handle_shifts(shifts);
+    }
+
+    //*************************************************************************
+    pub fn handle_highlight(
+                &mut self,
+                highlight: Vec<(usize, usize)>
+            ) {
+        self.graph.handle_highlight(highlight);
+    }
+
+    //*************************************************************************
+    pub fn handle_marks(
+                &mut self,
+                marks: Vec<Mark>
+            ) {
+        self.graph.handle_marks(marks);
+    }
+
+    //*************************************************************************
+    pub fn handle_overlay(
+                &mut self,
+                overlay: Vec<Def>
+            ) {
+        self.graph.handle_overlay(overlay);
+    }
+
+    //*************************************************************************
+    pub fn handle_numbered(
+                &mut self,
+                numbered: Vec<(PlotPoint, bool)>
+            ) {
+        self.graph.handle_numbered(numbered);
+    }
+
+    //*************************************************************************
+    pub fn handle_guides(
+                &mut self,
+                guides: Option<Guides>
+            ) {
+        self.graph.handle_guides(guides);
+    }
+
+    //*************************************************************************
+    pub fn handle_guide(
+                &mut self,
+                guide: Guide,
+                show:  bool
+            ) {
+        self.graph.handle_guide(guide, show);
+    }
+
+    //*************************************************************************
+    pub fn handle_ppem(
+                &mut self,
+                ppem: Option<f32>
+            ) {
+        self.graph.handle_ppem(ppem);
+    }
+
+    //*************************************************************************
+    pub fn handle_fit(&mut self) {
+        let size = self.viewport.get();
+
+        self.graph.handle_fit(size.width, size.height);
+    }
+
+    //*************************************************************************
+    pub fn handle_actual(&mut self) {
+        self.graph.handle_scale(1.0);
+    }
+
+    //*************************************************************************
+    pub fn handle_zoom(
+                &mut self,
+                delta: ScrollDelta,
+                lines: &Lines
+            ) -> Task<Message> {
+        let steps = match delta {
+            ScrollDelta::Lines { y, .. }  => y,
+            ScrollDelta::Pixels { y, .. } => y / 50.0
+        };
+        let before = self.graph.scale();
+        self.graph.handle_scale(self.graph.zoom() * 1.25_f32.powf(steps));
+        self.update(lines);
+
+        // Keep the point under the cursor where it is, the stroke border
+        // around the drawing doesn't scale.
+        let border = self.graph.border();
+        let ratio  = self.graph.scale() / before;
+        let x      = (self.cursor.x - border) * ratio + border;
+        let y      = (self.cursor.y - border) * ratio + border;
+
+        self.offset = AbsoluteOffset {
+            x: (self.offset.x + x - self.cursor.x).max(0.0),
+            y: (self.offset.y + y - self.cursor.y).max(0.0)
+        };
+        self.cursor = iced::Point::new(x, y);
+
+        scrollable::scroll_to(Id::new(SCROLL_ID), self.offset)
+    }
+
+    //*************************************************************************
+    pub fn handle_scrolled(
+                &mut self,
+                viewport: Viewport
+            ) {
+        self.offset = viewport.absolute_offset();
+    }
+
+    //*************************************************************************
+    pub fn handle_grab(
+                &mut self,
+                grab: bool
+            ) {
+        self.grab = if grab { Some(self.cursor) } else { None };
+    }
+
+    //*************************************************************************
+    pub fn handle_move(
+                &mut self,
+                cursor: iced::Point
+            ) -> Task<Message> {
+        self.cursor = cursor;
+
+        // The grabbed point of the drawing follows the cursor.
+        if let Some(grab) = self.grab {
+            let offset = AbsoluteOffset {
+                x: grab.x - cursor.x,
+                y: grab.y - cursor.y
+            };
+            scrollable::scroll_by(Id::new(SCROLL_ID), offset)
+        } else {
+            Task::none()
+        }
+    }
+
     //*************************************************************************
     pub fn update(
                 &mut self,
@@ -19,20 +19,29 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
-use std::mem;
+use std::{
+    f32::consts::PI,
+    mem
+};
 use iced::widget::image::Handle;
 
+use crate::PlotPoint;
 use super::{Cubic, Point, Quadratic};
 
+use coverage::Coverage;
 use paths::Paths;
 use stroke::Stroke;
 
+pub use polygon::Polygon;
 pub use stroke_factory::StrokeFactory;
 
 //*****************************************************************************
+mod coverage;
+mod polygon;
 mod stroke;
 mod stroke_factory;
 mod paths;
+mod text;
 
 //*****************************************************************************
 #[derive(Clone, Copy)]
@@ -47,13 +56,21 @@ pub enum Dir {
     NorthWest
 }
 
+//*****************************************************************************
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    NonZero,
+    EvenOdd
+}
+
 //*****************************************************************************
 pub struct Canvas {
     width:   usize,
     height:  usize,
     paths:   Vec<Paths>,
-    current: usize,
-    data:    Vec<u32>
+    current:  usize,
+    coverage: Option<Coverage>,
+    data:     Vec<u32>
 }
 
 impl Canvas {
@@ -67,8 +84,9 @@ impl Canvas {
 
         Self {
             width, height, data,
-            paths:   Vec::new(),
-            current: usize::MAX
+            paths:    Vec::new(),
+            current:  usize::MAX,
+            coverage: None
         }
     }
 
@@ -487,6 +505,243 @@ impl Canvas {
         }
     }
 
+    //*************************************************************************
+    pub fn dot(
+                &mut self,
+                p:     Point,
+                color: u32
+            ) {
+        let width = self.width as isize;
+
+        for y in -2..=2_isize {
+            for x in -2..=2_isize {
+                if x * x + y * y <= 4 {
+                    self.write_full(((p.y + y) * width + p.x + x) as usize, color);
+                }
+            }
+        }
+    }
+
+    //*************************************************************************
+    pub fn ring(
+                &mut self,
+                p:      Point,
+                radius: isize,
+                color:  u32
+            ) {
+        let nw = Point::new(p.x - radius, p.y - radius);
+        let ne = Point::new(p.x + radius, p.y - radius);
+        let se = Point::new(p.x + radius, p.y + radius);
+        let sw = Point::new(p.x - radius, p.y + radius);
+
+        self.line(nw, ne, color);
+        self.line(ne, se, color);
+        self.line(se, sw, color);
+        self.line(sw, nw, color);
+    }
+
+    //*************************************************************************
+    fn plot(
+                &mut self,
+                x:     isize,
+                y:     isize,
+                color: u32
+            ) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.data[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    //*************************************************************************
+    pub fn text(
+                &mut self,
+                p:     Point,
+                label: &str,
+                color: u32
+            ) {
+        // A white halo goes down first so the label reads on top of strokes,
+        // anything falling off the canvas is clipped.
+        for (pass, ink) in [0xFFFFFFFF, color].into_iter().enumerate() {
+            for (i, ch) in label.chars().enumerate() {
+                let left = p.x + (i * (text::WIDTH + 1)) as isize;
+
+                for (row, bits) in text::bitmap(ch).iter().enumerate() {
+                    for col in 0..text::WIDTH {
+                        if bits & (0x10 >> col) == 0 {
+                            continue;
+                        }
+                        let x = left + col as isize;
+                        let y = p.y + row as isize;
+
+                        if pass == 0 {
+                            for (dx, dy) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                                self.plot(x + dx, y + dy, ink);
+                            }
+                        } else {
+                            self.plot(x, y, ink);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    //*************************************************************************
+    fn span(
+                &mut self,
+                x0:    f32,
+                x1:    f32,
+                y:     usize,
+                color: u32
+            ) {
+        // Only pixels whose center falls inside the span are set.
+        let start = ((x0 - 0.5).ceil().max(0.0) as usize).min(self.width);
+        let end   = ((x1 - 0.5).ceil().max(0.0) as usize).min(self.width);
+        let row   = y * self.width;
+
+        for offset in (row + start)..(row + end) {
+            self.data[offset] = color;
+        }
+    }
+
+    //*************************************************************************
+    pub fn fill(
+                &mut self,
+                polygons: &[Polygon],
+                winding:  Winding,
+                color:    u32
+            ) {
+        let mut edges = Vec::new();
+
+        for polygon in polygons.iter() {
+            let points = polygon.points();
+
+            for (i, p0) in points.iter().enumerate() {
+                let p1 = points[(i + 1) % points.len()];
+
+                if p0.y < p1.y {
+                    edges.push((p0.x, p0.y, p1.x, p1.y, 1));
+                } else if p0.y > p1.y {
+                    edges.push((p1.x, p1.y, p0.x, p0.y, -1));
+                }
+            }
+        }
+
+        let mut crossings: Vec<(f32, i32)> = Vec::new();
+
+        for y in 0..self.height {
+            let sample = y as f32 + 0.5;
+
+            crossings.clear();
+            for &(x0, y0, x1, y1, dir) in edges.iter() {
+                if sample >= y0 && sample < y1 {
+                    crossings.push((x0 + (sample - y0) * (x1 - x0) / (y1 - y0), dir));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut count = 0;
+            for pair in crossings.windows(2) {
+                count += pair[0].1;
+
+                let inside = match winding {
+                    Winding::NonZero => count != 0,
+                    Winding::EvenOdd => count % 2 != 0
+                };
+                if inside {
+                    self.span(pair[0].0, pair[1].0, y, color);
+                }
+            }
+        }
+    }
+
+    //*************************************************************************
+    pub fn fill_smooth(
+                &mut self,
+                polygons: &[Polygon],
+                winding:  Winding,
+                color:    u32
+            ) {
+        let (width, height) = (self.width, self.height);
+        let coverage        = self.coverage.get_or_insert_with(|| Coverage::new(width, height));
+
+        for polygon in polygons.iter() {
+            coverage.polygon(polygon);
+        }
+        coverage.blend(&mut self.data, winding, color);
+    }
+
+    //*************************************************************************
+    pub fn stroke_smooth(
+                &mut self,
+                path:  &Polygon,
+                width: f32,
+                color: u32
+            ) {
+        const SIDES: usize = 16;
+
+        let (w, h)   = (self.width, self.height);
+        let coverage = self.coverage.get_or_insert_with(|| Coverage::new(w, h));
+        let radius   = width / 2.0;
+        let points   = path.points();
+
+        // The stroke is the union of a disc at every point and a quad along
+        // every step, all wound the same way so overlaps only saturate.
+        for (i, p) in points.iter().enumerate() {
+            let mut disc = Polygon::new(PlotPoint::new(p.x + radius, p.y));
+
+            for k in 1..SIDES {
+                let angle = -(k as f32) * 2.0 * PI / SIDES as f32;
+                disc.line(PlotPoint::new(p.x + radius * angle.cos(), p.y + radius * angle.sin()));
+            }
+            coverage.polygon(&disc);
+
+            if i + 1 < points.len() {
+                let q   = points[i + 1];
+                let len = ((q.x - p.x) * (q.x - p.x) + (q.y - p.y) * (q.y - p.y)).sqrt();
+
+                if len > f32::EPSILON {
+                    let nx = -(q.y - p.y) / len * radius;
+                    let ny =  (q.x - p.x) / len * radius;
+
+                    let mut quad = Polygon::new(PlotPoint::new(p.x + nx, p.y + ny));
+                    quad.line(PlotPoint::new(q.x + nx, q.y + ny));
+                    quad.line(PlotPoint::new(q.x - nx, q.y - ny));
+                    quad.line(PlotPoint::new(p.x - nx, p.y - ny));
+                    coverage.polygon(&quad);
+                }
+            }
+        }
+
+        coverage.blend(&mut self.data, Winding::NonZero, color);
+    }
+
+    //*************************************************************************
+    pub fn magnify(
+                &self,
+                cell: usize,
+                grid: u32
+            ) -> Canvas {
+        let mut result = Canvas::new(self.width * cell + 1, self.height * cell + 1);
+
+        // Each pixel becomes a cell, the cells are separated by grid lines
+        // once they are big enough for the lines not to hide them.
+        for y in 0..result.height {
+            for x in 0..result.width {
+                let edge = x % cell == 0 || y % cell == 0;
+
+                result.data[y * result.width + x] = if cell > 3 && edge {
+                    grid
+                } else if x / cell < self.width && y / cell < self.height {
+                    self.data[(y / cell) * self.width + x / cell]
+                } else {
+                    grid
+                };
+            }
+        }
+
+        result
+    }
 
     //*************************************************************************
     pub fn handle(&self) -> Handle {
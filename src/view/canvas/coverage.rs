@@ -0,0 +1,218 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::PlotPoint;
+
+use super::{Polygon, Winding};
+
+//*****************************************************************************
+pub struct Coverage {
+    width:  usize,
+    height: usize,
+    stride: usize,
+    area:   Vec<f32>,
+    x_min:  usize,
+    x_max:  usize,
+    y_min:  usize,
+    y_max:  usize
+}
+
+impl Coverage {
+    //*************************************************************************
+    pub fn new(
+                width:  usize,
+                height: usize
+            ) -> Self {
+        // Every edge can deposit area up to two cells past its right end.
+        let stride = width + 2;
+
+        Self {
+            width, height, stride,
+            area:  vec![0.0; stride * height],
+            x_min: usize::MAX,
+            x_max: 0,
+            y_min: usize::MAX,
+            y_max: 0
+        }
+    }
+
+    //*************************************************************************
+    fn touch(
+                &mut self,
+                x0: usize,
+                x1: usize,
+                y:  usize
+            ) {
+        self.x_min = self.x_min.min(x0);
+        self.x_max = self.x_max.max(x1);
+        self.y_min = self.y_min.min(y);
+        self.y_max = self.y_max.max(y);
+    }
+
+    //*************************************************************************
+    pub fn line(
+                &mut self,
+                p0: PlotPoint,
+                p1: PlotPoint
+            ) {
+        // Analytic area coverage, each row the edge crosses receives the
+        // signed area to its right split across the cells it passes through.
+        if (p0.y - p1.y).abs() <= f32::EPSILON {
+            return;
+        }
+        let (dir, p0, p1) = if p0.y < p1.y {
+            (1.0, p0, p1)
+        } else {
+            (-1.0, p1, p0)
+        };
+        let limit = self.width as f32;
+        let dxdy  = (p1.x - p0.x) / (p1.y - p0.y);
+        let mut x = p0.x;
+
+        if p0.y < 0.0 {
+            x -= p0.y * dxdy;
+        }
+
+        let y0 = p0.y.max(0.0) as usize;
+        let y1 = (p1.y.ceil().max(0.0) as usize).min(self.height);
+
+        for y in y0..y1 {
+            let row   = y * self.stride;
+            let dy    = ((y + 1) as f32).min(p1.y) - (y as f32).max(p0.y);
+            let xnext = x + dxdy * dy;
+            let d     = dy * dir;
+
+            let (x0, x1) = if x < xnext {
+                (x.clamp(0.0, limit), xnext.clamp(0.0, limit))
+            } else {
+                (xnext.clamp(0.0, limit), x.clamp(0.0, limit))
+            };
+            let x0floor = x0.floor();
+            let x0i     = x0floor as usize;
+            let x1ceil  = x1.ceil();
+            let x1i     = x1ceil as usize;
+
+            if x1i <= x0i + 1 {
+                let xmf = 0.5 * (x0 + x1) - x0floor;
+
+                self.area[row + x0i]     += d - d * xmf;
+                self.area[row + x0i + 1] += d * xmf;
+                self.touch(x0i, x0i + 1, y);
+            } else {
+                let s   = (x1 - x0).recip();
+                let x0f = x0 - x0floor;
+                let a0  = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1ceil + 1.0;
+                let am  = 0.5 * s * x1f * x1f;
+
+                self.area[row + x0i] += d * a0;
+
+                if x1i == x0i + 2 {
+                    self.area[row + x0i + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - x0f);
+                    self.area[row + x0i + 1] += d * (a1 - a0);
+
+                    for xi in (x0i + 2)..(x1i - 1) {
+                        self.area[row + xi] += d * s;
+                    }
+
+                    let a2 = a1 + (x1i - x0i - 3) as f32 * s;
+                    self.area[row + x1i - 1] += d * (1.0 - a2 - am);
+                }
+                self.area[row + x1i] += d * am;
+                self.touch(x0i, x1i, y);
+            }
+            x = xnext;
+        }
+    }
+
+    //*************************************************************************
+    pub fn polygon(
+                &mut self,
+                polygon: &Polygon
+            ) {
+        let points = polygon.points();
+
+        for (i, &p0) in points.iter().enumerate() {
+            self.line(p0, points[(i + 1) % points.len()]);
+        }
+    }
+
+    //*************************************************************************
+    fn mix(
+                dst:   u32,
+                src:   u32,
+                alpha: f32
+            ) -> u32 {
+        let mut result = 0xFF000000;
+
+        for shift in [0, 8, 16] {
+            let d = ((dst >> shift) & 0xFF) as f32;
+            let s = ((src >> shift) & 0xFF) as f32;
+            let v = d + (s - d) * alpha;
+
+            result |= (v.round() as u32) << shift;
+        }
+
+        result
+    }
+
+    //*************************************************************************
+    pub fn blend(
+                &mut self,
+                target:  &mut [u32],
+                winding: Winding,
+                color:   u32
+            ) {
+        if self.x_min > self.x_max {
+            return;
+        }
+
+        for y in self.y_min..=self.y_max {
+            let row     = y * self.stride;
+            let mut acc = 0.0;
+
+            for x in self.x_min..=self.x_max.min(self.stride - 1) {
+                acc += self.area[row + x];
+                self.area[row + x] = 0.0;
+
+                let alpha = match winding {
+                    Winding::NonZero => acc.abs().min(1.0),
+                    Winding::EvenOdd => {
+                        let folded = acc.abs() % 2.0;
+                        if folded > 1.0 { 2.0 - folded } else { folded }
+                    }
+                };
+
+                if x < self.width && alpha > 1.0 / 512.0 {
+                    let offset = y * self.width + x;
+                    target[offset] = Coverage::mix(target[offset], color, alpha);
+                }
+            }
+        }
+
+        self.x_min = usize::MAX;
+        self.x_max = 0;
+        self.y_min = usize::MAX;
+        self.y_max = 0;
+    }
+}
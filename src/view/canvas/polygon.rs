@@ -0,0 +1,134 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::PlotPoint;
+
+//*****************************************************************************
+pub struct Polygon {
+    points: Vec<PlotPoint>
+}
+
+impl Polygon {
+    //*************************************************************************
+    pub fn new(
+                start: PlotPoint
+            ) -> Self {
+        Self {
+            points: vec![start]
+        }
+    }
+
+    //*************************************************************************
+    pub fn points(&self) -> &[PlotPoint] {
+        &self.points
+    }
+
+    //*************************************************************************
+    pub fn area(&self) -> f32 {
+        let mut sum = 0.0;
+
+        for (i, p0) in self.points.iter().enumerate() {
+            let p1 = self.points[(i + 1) % self.points.len()];
+            sum += p0.x * p1.y - p1.x * p0.y;
+        }
+
+        sum / 2.0
+    }
+
+    //*************************************************************************
+    fn last(&self) -> PlotPoint {
+        self.points[self.points.len() - 1]
+    }
+
+    //*************************************************************************
+    fn steps(
+                dx: f32,
+                dy: f32
+            ) -> usize {
+        // The flattening error of a curve shrinks with the square of the
+        // number of steps, this keeps it well below half a pixel.
+        let dist = (dx * dx + dy * dy).sqrt();
+
+        (dist.sqrt().ceil() as usize).clamp(1, 1024)
+    }
+
+    //*************************************************************************
+    pub fn line(
+                &mut self,
+                p1: PlotPoint
+            ) {
+        self.points.push(p1);
+    }
+
+    //*************************************************************************
+    pub fn quadratic(
+                &mut self,
+                p1: PlotPoint,
+                p2: PlotPoint
+            ) {
+        let p0    = self.last();
+        let steps = Polygon::steps(
+            p0.x - 2.0 * p1.x + p2.x,
+            p0.y - 2.0 * p1.y + p2.y
+        );
+
+        for i in 1..=steps {
+            let t = i as f32 / steps as f32;
+            let m = 1.0 - t;
+            let a = m * m;
+            let b = 2.0 * m * t;
+            let c = t * t;
+
+            self.points.push(PlotPoint::new(
+                p0.x * a + p1.x * b + p2.x * c,
+                p0.y * a + p1.y * b + p2.y * c
+            ));
+        }
+    }
+
+    //*************************************************************************
+    pub fn cubic(
+                &mut self,
+                p1: PlotPoint,
+                p2: PlotPoint,
+                p3: PlotPoint
+            ) {
+        let p0    = self.last();
+        let steps = Polygon::steps(
+            (p0.x - 2.0 * p1.x + p2.x).abs().max((p1.x - 2.0 * p2.x + p3.x).abs()),
+            (p0.y - 2.0 * p1.y + p2.y).abs().max((p1.y - 2.0 * p2.y + p3.y).abs())
+        );
+
+        for i in 1..=steps {
+            let t = i as f32 / steps as f32;
+            let m = 1.0 - t;
+            let a = m * m * m;
+            let b = 3.0 * m * m * t;
+            let c = 3.0 * m * t * t;
+            let d = t * t * t;
+
+            self.points.push(PlotPoint::new(
+                p0.x * a + p1.x * b + p2.x * c + p3.x * d,
+                p0.y * a + p1.y * b + p2.y * c + p3.y * d
+            ));
+        }
+    }
+}
@@ -19,15 +19,41 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
-use crate::{Color, Def, Lines, PlotPoint};
-use super::{Canvas, Cubic, Point, Quadratic, StrokeFactory};
+use crate::{Color, Def, Fill, Guide, Guides, Labels, Lines, Mark, PlotPoint, Shift};
+use super::{Canvas, Cubic, Point, Polygon, Quadratic, StrokeFactory, Winding};
+
+//*****************************************************************************
+const MAX_SIZE:  f32 = 4096.0;
+const MIN_SCALE: f32 = 0.01;
+const MAX_SCALE: f32 = 64.0;
+const GRID:      u32 = 0xFFC0C0C0;
+const MARK:      f32 = 9.0;
 
 //*****************************************************************************
 pub struct Graph {
     strokes:   StrokeFactory,
     lstroke:   usize,
     astroke:   usize,
+    pstroke:   usize,
     thickness: i32,
+    fill:      Fill,
+    smooth:    bool,
+    points:    bool,
+    labels:    Labels,
+    direction: bool,
+    clockwise: Vec<bool>,
+    deltas:    bool,
+    shifts:    Vec<Shift>,
+    highlight: Vec<(usize, usize)>,
+    marks:     Vec<Mark>,
+    overlay:   Vec<Def>,
+    numbered:  Vec<(PlotPoint, bool)>,
+    guides:    Option<Guides>,
+    shown:     Vec<Guide>,
+    ppem:      Option<f32>,
+    zoom:      f32,
+    scale:     f32,
+    center:    f32,
     x_min:     f32,
     y_min:     f32,
     x_max:     f32,
@@ -45,12 +71,31 @@ impl Graph {
             strokes:   StrokeFactory::new(),
             lstroke:   usize::MAX,
             astroke:   usize::MAX,
+            pstroke:   usize::MAX,
             x_min:     0.0,
             y_min:     0.0,
             x_max:     0.0,
             y_max:     0.0,
             canvas:    Canvas::new(100, 100),
             prepare:   true,
+            fill:      Fill::Outline,
+            smooth:    false,
+            points:    false,
+            labels:    Labels::None,
+            direction: false,
+            clockwise: Vec::new(),
+            deltas:    false,
+            shifts:    Vec::new(),
+            highlight: Vec::new(),
+            marks:     Vec::new(),
+            overlay:   Vec::new(),
+            numbered:  Vec::new(),
+            guides:    None,
+            shown:     Guide::DEFAULT.to_vec(),
+            ppem:      None,
+            zoom:      1.0,
+            scale:     1.0,
+            center:    0.5,
             thickness
         }
     }
@@ -60,7 +105,26 @@ impl Graph {
     pub fn x_max(&self)  -> f32 { self.x_max }
     pub fn y_min(&self)  -> f32 { self.y_min }
     pub fn y_max(&self)  -> f32 { self.y_max }
+    pub fn zoom(&self)   -> f32 { self.zoom }
+    pub fn scale(&self)  -> f32 { self.scale }
     pub fn canvas(&self) -> &Canvas { &self.canvas }
+    pub fn clockwise(&self) -> &[bool] { &self.clockwise }
+
+    //*************************************************************************
+    fn markers(&self) -> bool {
+        self.points || self.direction || self.deltas || self.labels != Labels::None || !self.marks.is_empty()
+    }
+
+    //*************************************************************************
+    pub fn border(&self) -> f32 {
+        if self.ppem.is_some() {
+            0.0
+        } else if self.markers() {
+            (self.thickness as f32).max(MARK)
+        } else {
+            self.thickness as f32
+        }
+    }
 
     //*************************************************************************
     pub fn handle_thickness(
@@ -72,23 +136,258 @@ impl Graph {
         }
     }
 
+    //*************************************************************************
+    pub fn handle_fill(
+                &mut self,
+                fill: Fill
+            ) {
+        self.fill = fill;
+    }
+
+    //*************************************************************************
+    pub fn handle_anti_alias(
+                &mut self,
+                smooth: bool
+            ) {
+        self.smooth = smooth;
+    }
+
+    //*************************************************************************
+    pub fn handle_points(
+                &mut self,
+                points: bool
+            ) {
+        self.points = points;
+    }
+
+    //*************************************************************************
+    pub fn handle_labels(
+                &mut self,
+                labels: Labels
+            ) {
+        self.labels = labels;
+    }
+
+    //*************************************************************************
+    pub fn handle_direction(
+                &mut self,
+                direction: bool
+            ) {
+        self.direction = direction;
+    }
+
+    //*************************************************************************
+    pub fn handle_deltas(
+                &mut self,
+                deltas: bool
+            ) {
+        self.deltas = deltas;
+    }
+
+    //*************************************************************************
+    pub fn handle_shifts(
+                &mut self,
+                shifts: Vec<Shift>
+            ) {
+        self.shifts = shifts;
+    }
+
+    //*************************************************************************
+    pub fn handle_highlight(
+                &mut self,
+                highlight: Vec<(usize, usize)>
+            ) {
+        self.highlight = highlight;
+    }
+
+    //*************************************************************************
+    pub fn handle_marks(
+                &mut self,
+                marks: Vec<Mark>
+            ) {
+        self.marks = marks;
+    }
+
+    //*************************************************************************
+    pub fn handle_overlay(
+                &mut self,
+                overlay: Vec<Def>
+            ) {
+        self.overlay = overlay;
+    }
+
+    //*************************************************************************
+    pub fn handle_numbered(
+                &mut self,
+                numbered: Vec<(PlotPoint, bool)>
+            ) {
+        self.numbered = numbered;
+    }
+
+    //*************************************************************************
+    pub fn handle_guides(
+                &mut self,
+                guides: Option<Guides>
+            ) {
+        self.guides = guides;
+    }
+
+    //*************************************************************************
+    pub fn handle_guide(
+                &mut self,
+                guide: Guide,
+                show:  bool
+            ) {
+        self.shown.retain(|x|*x != guide);
+
+        if show {
+            self.shown.push(guide);
+        }
+    }
+
+    //*************************************************************************
+    pub fn handle_scale(
+                &mut self,
+                scale: f32
+            ) {
+        self.zoom = scale.clamp(MIN_SCALE, MAX_SCALE);
+    }
+
+    //*************************************************************************
+    pub fn handle_ppem(
+                &mut self,
+                ppem: Option<f32>
+            ) {
+        self.ppem = ppem;
+    }
+
+    //*************************************************************************
+    pub fn handle_fit(
+                &mut self,
+                width:  f32,
+                height: f32
+            ) {
+        let border = 2.0 * self.border() + 1.0;
+        let pad    = 2.0 * self.border() / self.scale;
+        let x_span = (self.x_max - self.x_min - pad).max(1.0);
+        let y_span = (self.y_max - self.y_min - pad).max(1.0);
+
+        self.handle_scale(
+            ((width - border) / x_span).min((height - border) / y_span)
+        );
+    }
+
     //*************************************************************************
     fn map(
                 &mut self,
                 p: PlotPoint
             ) -> Point {
         if self.prepare {
-            self.x_min = self.x_min.min(p.x - self.thickness as f32);
-            self.x_max = self.x_max.max(p.x + self.thickness as f32);
-            self.y_min = self.y_min.min(p.y - self.thickness as f32);
-            self.y_max = self.y_max.max(p.y + self.thickness as f32);
+            self.x_min = self.x_min.min(p.x);
+            self.x_max = self.x_max.max(p.x);
+            self.y_min = self.y_min.min(p.y);
+            self.y_max = self.y_max.max(p.y);
         }
-        let x = p.x - self.x_min;
-        let y = self.y_max - p.y;
+        let x = (p.x - self.x_min) * self.scale;
+        let y = (self.y_max - p.y) * self.scale;
 
         Point::new(x.trunc() as isize, y.trunc() as isize)
     }
 
+    //*************************************************************************
+    fn map_exact(
+                &self,
+                p: PlotPoint
+            ) -> PlotPoint {
+        PlotPoint::new(
+            (p.x - self.x_min) * self.scale + self.center,
+            (self.y_max - p.y) * self.scale + self.center
+        )
+    }
+
+    //*************************************************************************
+    fn pad(&mut self) {
+        // Keep the canvas within a size we can allocate and the integer
+        // curve math can handle, then leave room for the stroke.
+        let span = (self.x_max - self.x_min).max(self.y_max - self.y_min);
+        let room = MAX_SIZE - 2.0 * self.border() - 1.0;
+
+        if span * self.scale > room {
+            self.scale = room / span;
+        }
+
+        let pad = self.border() / self.scale;
+        self.x_min -= pad;
+        self.x_max += pad;
+        self.y_min -= pad;
+        self.y_max += pad;
+    }
+
+    //*************************************************************************
+    fn polygon(
+                &self,
+                contour: &[&Def]
+            ) -> Polygon {
+        let mut polygon = Polygon::new(self.map_exact(contour[0].start()));
+
+        for def in contour.iter() {
+            match def {
+                Def::Line(_, _, p1) => {
+                    polygon.line(self.map_exact(*p1));
+                },
+                Def::Quadratic(_, _, p1, p2) => {
+                    polygon.quadratic(self.map_exact(*p1), self.map_exact(*p2));
+                },
+                Def::Cubic(_, _, p1, p2, p3) => {
+                    polygon.cubic(self.map_exact(*p1), self.map_exact(*p2), self.map_exact(*p3));
+                }
+            }
+        }
+
+        polygon
+    }
+
+    //*************************************************************************
+    fn fill(
+                &mut self,
+                lines: &Lines
+            ) {
+        let winding = match self.fill {
+            Fill::Outline => return,
+            Fill::NonZero => Winding::NonZero,
+            Fill::EvenOdd => Winding::EvenOdd
+        };
+        let polygons: Vec<Polygon> = lines.closed()
+            .iter()
+            .map(|x|self.polygon(x))
+            .collect();
+
+        if self.smooth {
+            self.canvas.fill_smooth(&polygons, winding, Color::Gray.value());
+        } else {
+            self.canvas.fill(&polygons, winding, Color::Gray.value());
+        }
+    }
+
+    //*************************************************************************
+    fn segment(
+                &mut self,
+                def: &Def
+            ) {
+        if self.smooth && !self.prepare {
+            let path  = self.polygon(&[def]);
+            let width = if self.ppem.is_some() { 1.0 } else { self.thickness as f32 };
+            self.canvas.stroke_smooth(&path, width, def.color().value());
+            return;
+        }
+
+        match def {
+            Def::Line(c, p0, p1)          => self.line(*c, *p0, *p1),
+            Def::Quadratic(c, p0, p1, p2) => self.quadratic(*c, *p0, *p1, *p2),
+            Def::Cubic(c, p0, p1, p2, p3) => self.cubic(*c, *p0, *p1, *p2, *p3)
+        }
+    }
+
     //*************************************************************************
     fn line(
                 &mut self,
@@ -170,8 +469,8 @@ impl Graph {
 
     //*************************************************************************
     fn create_canvas(&mut self) {
-        let width  = (self.x_max - self.x_min + 1.0) as usize;
-        let height = (self.y_max - self.y_min + 1.0) as usize;
+        let width  = ((self.x_max - self.x_min) * self.scale + 1.0) as usize;
+        let height = ((self.y_max - self.y_min) * self.scale + 1.0) as usize;
 
         if width == 0 && height == 0 {
             self.canvas = Canvas::new(100, 100);
@@ -182,8 +481,411 @@ impl Graph {
         } else {
             self.canvas = Canvas::new(width, height);
         }
+        self.add_strokes();
+    }
+
+    //*************************************************************************
+    fn guide_lines(&self) -> Vec<Def> {
+        let mut result = Vec::new();
+
+        if let Some(guides) = self.guides.as_ref() {
+            for guide in self.shown.iter() {
+                for (x0, y0, x1, y1) in guides.lines(*guide) {
+                    result.push(
+                        Def::Line(guide.color(), PlotPoint::new(x0, y0), PlotPoint::new(x1, y1))
+                    );
+                }
+            }
+        }
+
+        result
+    }
+
+    //*************************************************************************
+    fn guides(&mut self) {
+        self.canvas.set_stroke(self.pstroke);
+
+        for def in self.guide_lines() {
+            let p0 = self.map(def.start());
+            let p1 = self.map(def.end());
+            self.canvas.line(p0, p1, def.color().value());
+        }
+    }
+
+    //*************************************************************************
+    fn add_strokes(&mut self) {
         self.lstroke = self.canvas.add_stroke(self.strokes.get(self.thickness as usize));
         self.astroke = self.canvas.add_stroke(self.strokes.get(3));
+        self.pstroke = self.canvas.add_stroke(self.strokes.get(1));
+    }
+
+    //*************************************************************************
+    fn draw_pixels(
+                &mut self,
+                lines: &Lines,
+                ppem:  f32
+            ) {
+        // Snap the bounds to whole pixels with the origin on a pixel corner.
+        let mut left   = (self.x_min * ppem).floor();
+        let mut right  = (self.x_max * ppem).ceil().max(left + 1.0);
+        let mut bottom = (self.y_min * ppem).floor();
+        let mut top    = (self.y_max * ppem).ceil().max(bottom + 1.0);
+
+        // Work out the cell size up front, the point markers need whole
+        // empty pixels around the glyph to fit into.
+        let span  = (right - left).max(top - bottom) as usize;
+        let mut cell = ((self.zoom / ppem).round() as usize).max(1);
+
+        if self.markers() {
+            let limit = (MAX_SIZE as usize - 1) / (span + 2);
+            cell = cell.min(limit).max(1);
+
+            let pad = (MARK / cell as f32).ceil();
+            left   -= pad;
+            right  += pad;
+            bottom -= pad;
+            top    += pad;
+        }
+        let width  = (right - left) as usize;
+        let height = (top - bottom) as usize;
+
+        self.x_min = left / ppem;
+        self.x_max = right / ppem;
+        self.y_min = bottom / ppem;
+        self.y_max = top / ppem;
+
+        // Rasterize at the real size, pixel edges on the integer coordinates.
+        let winding = match self.fill {
+            Fill::EvenOdd => Winding::EvenOdd,
+            _             => Winding::NonZero
+        };
+        self.scale  = ppem;
+        self.center = 0.0;
+        self.canvas = Canvas::new(width, height);
+
+        let polygons: Vec<Polygon> = lines.closed()
+            .iter()
+            .map(|x|self.polygon(x))
+            .collect();
+
+        if self.smooth {
+            self.canvas.fill_smooth(&polygons, winding, Color::Black.value());
+        } else {
+            self.canvas.fill(&polygons, winding, Color::Black.value());
+        }
+
+        // Blow every pixel up to a cell and lay the outline over the top.
+        if !self.markers() {
+            let limit = (MAX_SIZE as usize - 1) / width.max(height);
+            cell = cell.min(limit).max(1);
+        }
+
+        self.scale  = ppem * cell as f32;
+        self.center = 0.5;
+        self.canvas = self.canvas.magnify(cell, GRID);
+        self.add_strokes();
+        self.guides();
+
+        self.overlay(self.pstroke);
+
+        self.canvas.set_stroke(self.pstroke);
+        for line in lines.iter() {
+            if line.is_enabled() {
+                let def = line.def().clone().with_color(Color::Red);
+                self.segment(&def);
+            }
+        }
+        self.shifts(lines);
+        self.points(lines);
+        self.labels(lines);
+        self.direction(lines);
+        self.marks();
+    }
+
+    //*************************************************************************
+    fn overlay(
+                &mut self,
+                stroke: usize
+            ) {
+        // Drawn under the outline, in its own colors.
+        self.canvas.set_stroke(stroke);
+
+        for def in self.overlay.clone() {
+            self.segment(&def);
+        }
+    }
+
+    //*************************************************************************
+    fn handles(
+                def: &Def
+            ) -> Vec<(PlotPoint, PlotPoint)> {
+        match def {
+            Def::Line(_, _, _)            => Vec::new(),
+            Def::Quadratic(_, p0, p1, p2) => vec![(*p1, *p0), (*p1, *p2)],
+            Def::Cubic(_, p0, p1, p2, p3) => vec![(*p1, *p0), (*p2, *p3)]
+        }
+    }
+
+    //*************************************************************************
+    fn points(
+                &mut self,
+                lines: &Lines
+            ) {
+        if !self.points {
+            return;
+        }
+        let defs: Vec<&Def> = lines.iter()
+            .filter(|x|x.is_enabled())
+            .map(|x|x.def())
+            .collect();
+
+        // Handles go down first so the markers sit on top of them.
+        self.canvas.set_stroke(self.pstroke);
+        for def in defs.iter() {
+            for (off, on) in Graph::handles(def) {
+                let p0 = self.map(off);
+                let p1 = self.map(on);
+                self.canvas.line(p0, p1, Color::Gray.value());
+            }
+        }
+
+        self.canvas.set_stroke(self.astroke);
+        for def in defs.iter() {
+            for (off, _) in Graph::handles(def) {
+                let p = self.map(off);
+                self.canvas.ring(p, 3, Color::Red.value());
+            }
+        }
+        for def in defs.iter() {
+            let p0 = self.map(def.start());
+            let p1 = self.map(def.end());
+            self.canvas.dot(p0, Color::Blue.value());
+            self.canvas.dot(p1, Color::Blue.value());
+        }
+    }
+
+    //*************************************************************************
+    fn arrow(
+                &mut self,
+                def: &Def
+            ) {
+        let (middle, heading) = def.middle();
+
+        self.head(middle, heading, Color::Green);
+    }
+
+    //*************************************************************************
+    fn head(
+                &mut self,
+                tip:     PlotPoint,
+                heading: PlotPoint,
+                color:   Color
+            ) {
+        // The canvas runs y downwards.
+        let len = (heading.x * heading.x + heading.y * heading.y).sqrt();
+        if len < f32::EPSILON {
+            return;
+        }
+        let dx  =  heading.x / len;
+        let dy  = -heading.y / len;
+        let tip = self.map(tip);
+
+        for side in [-1.0, 1.0] {
+            let wing = Point::new(
+                tip.x + (-8.0 * dx + side * 4.0 * dy).round() as isize,
+                tip.y + (-8.0 * dy - side * 4.0 * dx).round() as isize
+            );
+            self.canvas.line(wing, tip, color.value());
+        }
+    }
+
+    //*************************************************************************
+    fn shown_shifts(
+                &self,
+                lines: &Lines
+            ) -> Vec<Shift> {
+        if !self.deltas {
+            return Vec::new();
+        }
+        let contours = lines.contours();
+
+        self.shifts.iter()
+            .filter(|x|contours.get(x.contour).map(|x|x.is_enabled()).unwrap_or(true))
+            .copied()
+            .collect()
+    }
+
+    //*************************************************************************
+    fn shifts(
+                &mut self,
+                lines: &Lines
+            ) {
+        // Points the font gave a delta are marked apart from the ones
+        // inferred from their neighbours.
+        let shifts = self.shown_shifts(lines);
+        let color  = |x: &Shift| if x.explicit { Color::Magenta } else { Color::Lime };
+
+        self.canvas.set_stroke(self.pstroke);
+        for shift in shifts.iter() {
+            let p0 = self.map(shift.from);
+            let p1 = self.map(shift.to);
+
+            if p0 != p1 {
+                self.canvas.line(p0, p1, color(shift).value());
+                self.head(shift.to, PlotPoint::new(shift.to.x - shift.from.x, shift.to.y - shift.from.y), color(shift));
+            }
+        }
+
+        self.canvas.set_stroke(self.astroke);
+        for shift in shifts.iter() {
+            let p = self.map(shift.from);
+
+            if shift.explicit {
+                self.canvas.dot(p, color(shift).value());
+            } else {
+                self.canvas.ring(p, 3, color(shift).value());
+            }
+        }
+    }
+
+    //*************************************************************************
+    fn highlight(
+                &mut self,
+                lines: &Lines
+            ) {
+        // Segments are picked by contour and position within it.
+        let defs = self.highlight.iter()
+            .filter_map(|&(contour, segment)|lines.contours().get(contour)?.iter().nth(segment))
+            .filter(|x|x.is_enabled())
+            .map(|x|x.def().clone().with_color(Color::Orange))
+            .collect::<Vec<Def>>();
+
+        self.canvas.set_stroke(self.lstroke);
+        for def in defs.iter() {
+            self.segment(def);
+        }
+
+        self.canvas.set_stroke(self.astroke);
+        for def in defs.iter() {
+            let p0 = self.map(def.start());
+            let p1 = self.map(def.end());
+            self.canvas.dot(p0, Color::Orange.value());
+            self.canvas.dot(p1, Color::Orange.value());
+        }
+    }
+
+    //*************************************************************************
+    fn direction(
+                &mut self,
+                lines: &Lines
+            ) {
+        // The polygon is in canvas space with y running downwards, so a
+        // positive area there is clockwise in font space.
+        let contours = lines.closed();
+
+        self.clockwise = contours.iter()
+            .map(|x|self.polygon(x).area() > 0.0)
+            .collect();
+
+        if !self.direction {
+            return;
+        }
+        self.canvas.set_stroke(self.pstroke);
+
+        for contour in contours.iter() {
+            for def in contour.iter() {
+                self.arrow(def);
+            }
+        }
+
+        self.canvas.set_stroke(self.astroke);
+
+        for (i, contour) in contours.iter().enumerate() {
+            let start = self.map(contour[0].start());
+            let label = if self.clockwise[i] {
+                format!("#{} CW", i)
+            } else {
+                format!("#{} CCW", i)
+            };
+            self.canvas.ring(start, 6, Color::Green.value());
+            self.canvas.text(Point::new(start.x - 6, start.y + 6), &label, Color::Green.value());
+        }
+    }
+
+    //*************************************************************************
+    fn outline_points(
+                def: &Def
+            ) -> Vec<(PlotPoint, bool)> {
+        // The end point is the start of the next segment in the contour.
+        match def {
+            Def::Line(_, p0, _)          => vec![(*p0, true)],
+            Def::Quadratic(_, p0, p1, _) => vec![(*p0, true), (*p1, false)],
+            Def::Cubic(_, p0, p1, p2, _) => vec![(*p0, true), (*p1, false), (*p2, false)]
+        }
+    }
+
+    //*************************************************************************
+    fn labels(
+                &mut self,
+                lines: &Lines
+            ) {
+        if self.labels == Labels::None {
+            return;
+        }
+
+        // The glyph's own points are numbered when it has them, otherwise
+        // the ends of the segments are.
+        let points = if self.numbered.is_empty() {
+            lines.closed()
+                .into_iter()
+                .flatten()
+                .flat_map(Graph::outline_points)
+                .collect()
+        } else {
+            self.numbered.clone()
+        };
+
+        for (index, (p, on_curve)) in points.into_iter().enumerate() {
+            let label = if self.labels == Labels::Index {
+                format!("{}", index)
+            } else {
+                format!("{} ({:.0},{:.0})", index, p.x, p.y)
+            };
+            let color = if on_curve { Color::Blue } else { Color::Red };
+            let at    = self.map(p);
+
+            self.canvas.text(Point::new(at.x + 6, at.y - 10), &label, color.value());
+        }
+    }
+
+    //*************************************************************************
+    fn marks(&mut self) {
+        // Directions are drawn as arrows out of the point they start at.
+        let marks = self.marks.clone();
+
+        self.canvas.set_stroke(self.pstroke);
+        for mark in marks.iter() {
+            if let Some(heading) = mark.heading {
+                let tip = PlotPoint::new(mark.at.x + heading.x, mark.at.y + heading.y);
+                let p0  = self.map(mark.at);
+                let p1  = self.map(tip);
+                self.canvas.line(p0, p1, mark.color.value());
+                self.head(tip, heading, mark.color);
+            }
+        }
+
+        self.canvas.set_stroke(self.astroke);
+        for mark in marks.iter() {
+            let at = match mark.heading {
+                Some(heading) => self.map(PlotPoint::new(mark.at.x + heading.x, mark.at.y + heading.y)),
+                None          => {
+                    let at = self.map(mark.at);
+                    self.canvas.ring(at, 6, mark.color.value());
+                    at
+                }
+            };
+            self.canvas.text(Point::new(at.x + 8, at.y + 2), &mark.label, mark.color.value());
+        }
     }
 
     //*************************************************************************
@@ -196,30 +898,65 @@ impl Graph {
         self.y_min = 0.0;
         self.y_max = 0.0;
         self.prepare = true;
+        self.scale   = self.zoom;
 
         for line in lines.iter() {
             if line.is_enabled() {
-                match line.def() {
-                    Def::Line(c, p0, p1)          => self.line(*c, *p0, *p1),
-                    Def::Quadratic(c, p0, p1, p2) => self.quadratic(*c, *p0, *p1, *p2),
-                    Def::Cubic(c, p0, p1, p2, p3) => self.cubic(*c, *p0, *p1, *p2, *p3)
+                self.segment(line.def());
+
+                // The off curve points can sit outside the curve.
+                if self.markers() {
+                    for (off, _) in Graph::handles(line.def()) {
+                        self.map(off);
+                    }
                 }
             }
         }
 
+        for def in self.guide_lines() {
+            self.map(def.start());
+            self.map(def.end());
+        }
+
+        for def in self.overlay.clone() {
+            self.segment(&def);
+        }
+
+        for shift in self.shown_shifts(lines) {
+            self.map(shift.from);
+            self.map(shift.to);
+        }
+
+        for mark in self.marks.clone() {
+            self.map(mark.at);
+            if let Some(heading) = mark.heading {
+                self.map(PlotPoint::new(mark.at.x + heading.x, mark.at.y + heading.y));
+            }
+        }
+
         self.prepare = false;
+
+        if let Some(ppem) = self.ppem {
+            self.draw_pixels(lines, ppem);
+            return;
+        }
+        self.pad();
         self.create_canvas();
+        self.fill(lines);
+        self.guides();
+        self.overlay(self.lstroke);
 
         self.canvas.set_stroke(self.lstroke);
         for line in lines.iter() {
             if line.is_enabled() {
-                match line.def() {
-                    Def::Line(c, p0, p1)          => self.line(*c, *p0, *p1),
-                    Def::Quadratic(c, p0, p1, p2) => self.quadratic(*c, *p0, *p1, *p2),
-                    Def::Cubic(c, p0, p1, p2, p3) => self.cubic(*c, *p0, *p1, *p2, *p3)
-                }
+                self.segment(line.def());
             }
         }
-        self.canvas.set_stroke(self.astroke);
+        self.highlight(lines);
+        self.shifts(lines);
+        self.points(lines);
+        self.labels(lines);
+        self.direction(lines);
+        self.marks();
     }
 }
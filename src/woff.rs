@@ -0,0 +1,80 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+use crate::sfnt::{self, read_u16, read_u32};
+
+//*****************************************************************************
+pub const SIGNATURE: &[u8; 4] = b"wOFF";
+
+//*****************************************************************************
+const HEADER_SIZE: usize = 44;
+const ENTRY_SIZE:  usize = 20;
+
+//*****************************************************************************
+pub fn decode(
+            data: &[u8]
+        ) -> Result<Vec<u8>, String> {
+    let flavor     = read_u32(data, 4)?;
+    let num_tables = read_u16(data, 12)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+
+    for i in 0..num_tables {
+        let entry       = HEADER_SIZE + i * ENTRY_SIZE;
+        let tag         = data.get(entry..entry + 4).ok_or("Truncated WOFF table directory.")?;
+        let offset      = read_u32(data, entry + 4)? as usize;
+        let comp_length = read_u32(data, entry + 8)? as usize;
+        let orig_length = read_u32(data, entry + 12)? as usize;
+        let checksum    = read_u32(data, entry + 16)?;
+        let name        = String::from_utf8_lossy(tag);
+        let stored      = data.get(offset..offset + comp_length)
+            .ok_or_else(||format!("WOFF table {} runs past the end of the file.", name))?;
+
+        // Tables that didn't get smaller are stored as is.
+        let table = if comp_length < orig_length {
+            // The table grows as it inflates, but never much past the
+            // length it claims.
+            let mut table = Vec::new();
+            ZlibDecoder::new(stored)
+                .take(orig_length as u64 + 1)
+                .read_to_end(&mut table)
+                .map_err(|x|format!("Unable to inflate WOFF table {}: {}", name, x))?;
+
+            if table.len() != orig_length {
+                return Err(format!("WOFF table {} has the wrong length.", name));
+            }
+            table
+        } else {
+            stored.to_vec()
+        };
+
+        tables.push(([tag[0], tag[1], tag[2], tag[3]], checksum, table));
+    }
+
+    let tables = tables.iter()
+        .map(|(tag, checksum, table)|(*tag, *checksum, table.as_slice()))
+        .collect::<Vec<_>>();
+
+    sfnt::build(flavor, &tables)
+}
@@ -0,0 +1,771 @@
+/*
+Copyright 2025 Joshua E Gentry
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the “Software”), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is furnished
+to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use std::io::Read;
+
+use brotli_decompressor::Decompressor;
+
+use crate::sfnt::{self, read_u16, read_u32};
+
+//*****************************************************************************
+pub const SIGNATURE: &[u8; 4] = b"wOF2";
+
+//*****************************************************************************
+const HEADER_SIZE: usize    = 48;
+const COLLECTION:  u32      = 0x74746366;
+const GLYF:        [u8; 4]  = *b"glyf";
+const LOCA:        [u8; 4]  = *b"loca";
+const HEAD:        [u8; 4]  = *b"head";
+const HMTX:        [u8; 4]  = *b"hmtx";
+const HHEA:        [u8; 4]  = *b"hhea";
+const MAXP:        [u8; 4]  = *b"maxp";
+
+// Glyph flags used while rebuilding the glyf table.
+const ON_CURVE:      u8 = 0x01;
+const X_SHORT:       u8 = 0x02;
+const Y_SHORT:       u8 = 0x04;
+const X_SAME:        u8 = 0x10;
+const Y_SAME:        u8 = 0x20;
+const OVERLAP:       u8 = 0x40;
+const ARG_WORDS:     u16 = 0x0001;
+const SCALE:         u16 = 0x0008;
+const MORE:          u16 = 0x0020;
+const XY_SCALE:      u16 = 0x0040;
+const TWO_BY_TWO:    u16 = 0x0080;
+const INSTRUCTIONS:  u16 = 0x0100;
+
+// Tags with a one byte code in the table directory.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post",
+    b"cvt ", b"fpgm", b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT",
+    b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH",
+    b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop",
+    b"trak", b"Zapf", b"Silf", b"Glat", b"Gloc", b"Feat", b"Sill"
+];
+
+//*****************************************************************************
+struct Stream<'a> {
+    data:   &'a [u8],
+    offset: usize
+}
+
+impl<'a> Stream<'a> {
+    //*************************************************************************
+    fn new(
+                data: &'a [u8]
+            ) -> Self {
+        Self { data, offset: 0 }
+    }
+
+    //*************************************************************************
+    fn bytes(
+                &mut self,
+                count: usize
+            ) -> Result<&'a [u8], String> {
+        let bytes = self.data.get(self.offset..self.offset + count)
+            .ok_or_else(||format!("Unexpected end of data at offset {}.", self.offset))?;
+        self.offset += count;
+
+        Ok(bytes)
+    }
+
+    //*************************************************************************
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    //*************************************************************************
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    //*************************************************************************
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(self.u16()? as i16)
+    }
+
+    //*************************************************************************
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    //*************************************************************************
+    fn base_128(&mut self) -> Result<u32, String> {
+        let mut value = 0_u32;
+
+        for i in 0..5 {
+            let byte = self.u8()?;
+
+            if (i == 0 && byte == 0x80) || value & 0xFE000000 != 0 {
+                return Err("Invalid UIntBase128 value.".to_string());
+            }
+            value = (value << 7) | (byte & 0x7F) as u32;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err("UIntBase128 value is too long.".to_string())
+    }
+
+    //*************************************************************************
+    fn uint_255(&mut self) -> Result<u16, String> {
+        match self.u8()? {
+            253  => self.u16(),
+            254  => Ok(self.u8()? as u16 + 506),
+            255  => Ok(self.u8()? as u16 + 253),
+            code => Ok(code as u16)
+        }
+    }
+}
+
+//*****************************************************************************
+struct Entry {
+    tag:         [u8; 4],
+    transformed: bool,
+    offset:      usize,
+    length:      usize
+}
+
+//*****************************************************************************
+struct Glyphs {
+    glyf:         Vec<u8>,
+    loca:         Vec<u8>,
+    index_format: u16,
+    x_mins:       Vec<i16>
+}
+
+//*****************************************************************************
+fn read_directory(
+            input:      &mut Stream,
+            num_tables: usize
+        ) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::with_capacity(num_tables);
+    let mut offset  = 0;
+
+    for _ in 0..num_tables {
+        let flags = input.u8()?;
+        let tag   = match flags & 0x3F {
+            0x3F => {
+                let tag = input.bytes(4)?;
+                [tag[0], tag[1], tag[2], tag[3]]
+            },
+            code => *KNOWN_TAGS[code as usize]
+        };
+
+        // Version 0 is the null transform for everything but glyf and loca,
+        // for those it's version 3.
+        let version     = flags >> 6;
+        let transformed = if tag == GLYF || tag == LOCA { version != 3 } else { version != 0 };
+        let orig_length = input.base_128()? as usize;
+        let length      = if transformed { input.base_128()? as usize } else { orig_length };
+
+        entries.push(Entry { tag, transformed, offset, length });
+        offset += length;
+    }
+
+    Ok(entries)
+}
+
+//*****************************************************************************
+fn read_fonts(
+            input:      &mut Stream,
+            flavor:     u32,
+            num_tables: usize
+        ) -> Result<Vec<(u32, Vec<usize>)>, String> {
+    if flavor != COLLECTION {
+        return Ok(vec![(flavor, (0..num_tables).collect())]);
+    }
+
+    let _version  = input.u32()?;
+    let num_fonts = input.uint_255()? as usize;
+    let mut fonts = Vec::with_capacity(num_fonts);
+
+    for _ in 0..num_fonts {
+        let count   = input.uint_255()? as usize;
+        let flavor  = input.u32()?;
+        let indices = (0..count)
+            .map(|_|input.uint_255().map(|x|x as usize))
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        if let Some(index) = indices.iter().find(|&&x|x >= num_tables) {
+            return Err(format!("WOFF2 collection refers to missing table {}.", index));
+        }
+        fonts.push((flavor, indices));
+    }
+
+    Ok(fonts)
+}
+
+//*****************************************************************************
+fn triplet(
+            flag:  u8,
+            input: &mut Stream
+        ) -> Result<(i32, i32), String> {
+    let sign = |bit: u8, value: i32| if bit & 1 != 0 { value } else { -value };
+    let flag = flag & 0x7F;
+    let f    = flag as i32;
+
+    Ok(match flag {
+        0..=9 => {
+            (0, sign(flag, ((f & 14) << 7) + input.u8()? as i32))
+        },
+        10..=19 => {
+            (sign(flag, (((f - 10) & 14) << 7) + input.u8()? as i32), 0)
+        },
+        20..=83 => {
+            let b0 = f - 20;
+            let b1 = input.u8()? as i32;
+            (sign(flag, 1 + (b0 & 0x30) + (b1 >> 4)),
+             sign(flag >> 1, 1 + ((b0 & 0x0C) << 2) + (b1 & 0x0F)))
+        },
+        84..=119 => {
+            let b0 = f - 84;
+            let b  = input.bytes(2)?;
+            (sign(flag, 1 + ((b0 / 12) << 8) + b[0] as i32),
+             sign(flag >> 1, 1 + (((b0 % 12) >> 2) << 8) + b[1] as i32))
+        },
+        120..=123 => {
+            let b = input.bytes(3)?;
+            (sign(flag, ((b[0] as i32) << 4) + (b[1] as i32 >> 4)),
+             sign(flag >> 1, ((b[1] as i32 & 0x0F) << 8) + b[2] as i32))
+        },
+        _ => {
+            let b = input.bytes(4)?;
+            (sign(flag, ((b[0] as i32) << 8) + b[1] as i32),
+             sign(flag >> 1, ((b[2] as i32) << 8) + b[3] as i32))
+        }
+    })
+}
+
+//*****************************************************************************
+fn write_coordinate(
+            output: &mut Vec<u8>,
+            delta:  i32
+        ) -> Result<(), String> {
+    if delta != 0 && delta.abs() < 256 {
+        output.push(delta.unsigned_abs() as u8);
+    } else if delta != 0 {
+        let delta = i16::try_from(delta).map_err(|_|format!("A point moves {} units, too far for a glyph.", delta))?;
+        output.extend_from_slice(&delta.to_be_bytes());
+    }
+
+    Ok(())
+}
+
+//*****************************************************************************
+fn coordinate_flags(
+            delta: i32,
+            short: u8,
+            same:  u8
+        ) -> u8 {
+    if delta == 0 {
+        same
+    } else if delta.abs() < 256 {
+        if delta > 0 { short | same } else { short }
+    } else {
+        0
+    }
+}
+
+//*****************************************************************************
+fn reconstruct_glyf(
+            data: &[u8]
+        ) -> Result<Glyphs, String> {
+    let mut header   = Stream::new(data);
+    let _reserved    = header.u16()?;
+    let options      = header.u16()?;
+    let num_glyphs   = header.u16()? as usize;
+    let index_format = header.u16()?;
+    let mut sizes    = [0_usize; 7];
+
+    for size in sizes.iter_mut() {
+        *size = header.u32()? as usize;
+    }
+
+    let mut contours     = Stream::new(header.bytes(sizes[0])?);
+    let mut points       = Stream::new(header.bytes(sizes[1])?);
+    let mut flags        = Stream::new(header.bytes(sizes[2])?);
+    let mut glyphs       = Stream::new(header.bytes(sizes[3])?);
+    let mut composites   = Stream::new(header.bytes(sizes[4])?);
+    let mut boxes        = Stream::new(header.bytes(sizes[5])?);
+    let mut instructions = Stream::new(header.bytes(sizes[6])?);
+    let box_bitmap       = boxes.bytes(((num_glyphs + 31) >> 5) << 2)?;
+    let overlap_bitmap   = if options & 1 != 0 {
+        Some(header.bytes((num_glyphs + 7) >> 3)?)
+    } else {
+        None
+    };
+
+    let bit = |bitmap: &[u8], i: usize| bitmap[i >> 3] & (0x80 >> (i & 7)) != 0;
+
+    let mut glyf    = Vec::new();
+    let mut offsets = Vec::with_capacity(num_glyphs + 1);
+    let mut x_mins  = Vec::with_capacity(num_glyphs);
+
+    for i in 0..num_glyphs {
+        offsets.push(glyf.len());
+
+        let count = contours.i16()?;
+        let start = glyf.len();
+
+        if count == 0 {
+            x_mins.push(0);
+            continue;
+        }
+
+        // The glyph header, the bounding box is patched in once it's known.
+        glyf.extend_from_slice(&count.to_be_bytes());
+        glyf.extend_from_slice(&[0; 8]);
+
+        let stored = if bit(box_bitmap, i) {
+            Some([boxes.i16()?, boxes.i16()?, boxes.i16()?, boxes.i16()?])
+        } else {
+            None
+        };
+
+        let bounds = if count > 0 {
+            let mut ends  = Vec::with_capacity(count as usize);
+            let mut total = 0_usize;
+
+            // Every contour needs a point, or its end would repeat the
+            // last one's.
+            for _ in 0..count {
+                let length = points.uint_255()? as usize;
+                total += length;
+                let end = total.checked_sub(1)
+                    .filter(|_|length > 0)
+                    .and_then(|x|u16::try_from(x).ok())
+                    .ok_or_else(||format!("Glyph {} has a bad number of points.", i))?;
+                ends.push(end);
+            }
+
+            let mut x   = 0_i32;
+            let mut y   = 0_i32;
+            let mut xys = Vec::with_capacity(total);
+            let mut ons = Vec::with_capacity(total);
+
+            for _ in 0..total {
+                let flag     = flags.u8()?;
+                let (dx, dy) = triplet(flag, &mut glyphs)?;
+
+                x = x.wrapping_add(dx);
+                y = y.wrapping_add(dy);
+                xys.push((x, y));
+                ons.push(flag & 0x80 == 0);
+            }
+
+            let length = glyphs.uint_255()? as usize;
+
+            for end in ends.iter() {
+                glyf.extend_from_slice(&end.to_be_bytes());
+            }
+            glyf.extend_from_slice(&(length as u16).to_be_bytes());
+            glyf.extend_from_slice(instructions.bytes(length)?);
+
+            let mut x_bytes = Vec::new();
+            let mut y_bytes = Vec::new();
+            let mut last    = (0, 0);
+
+            for (j, (&(x, y), &on)) in xys.iter().zip(ons.iter()).enumerate() {
+                let (dx, dy) = (x.wrapping_sub(last.0), y.wrapping_sub(last.1));
+                let mut flag = coordinate_flags(dx, X_SHORT, X_SAME)
+                             | coordinate_flags(dy, Y_SHORT, Y_SAME);
+
+                if on {
+                    flag |= ON_CURVE;
+                }
+                if j == 0 && overlap_bitmap.is_some_and(|x|bit(x, i)) {
+                    flag |= OVERLAP;
+                }
+
+                glyf.push(flag);
+                write_coordinate(&mut x_bytes, dx)?;
+                write_coordinate(&mut y_bytes, dy)?;
+                last = (x, y);
+            }
+            glyf.extend_from_slice(&x_bytes);
+            glyf.extend_from_slice(&y_bytes);
+
+            stored.unwrap_or_else(||{
+                let x_min = xys.iter().map(|x|x.0).min().unwrap_or(0);
+                let y_min = xys.iter().map(|x|x.1).min().unwrap_or(0);
+                let x_max = xys.iter().map(|x|x.0).max().unwrap_or(0);
+                let y_max = xys.iter().map(|x|x.1).max().unwrap_or(0);
+                [x_min as i16, y_min as i16, x_max as i16, y_max as i16]
+            })
+        } else {
+            let mut have_instructions = false;
+
+            loop {
+                let component = composites.u16()?;
+                let mut size  = 2 + if component & ARG_WORDS != 0 { 4 } else { 2 };
+
+                if component & SCALE != 0 {
+                    size += 2;
+                } else if component & XY_SCALE != 0 {
+                    size += 4;
+                } else if component & TWO_BY_TWO != 0 {
+                    size += 8;
+                }
+
+                glyf.extend_from_slice(&component.to_be_bytes());
+                glyf.extend_from_slice(composites.bytes(size)?);
+                have_instructions |= component & INSTRUCTIONS != 0;
+
+                if component & MORE == 0 {
+                    break;
+                }
+            }
+
+            if have_instructions {
+                let length = glyphs.uint_255()? as usize;
+                glyf.extend_from_slice(&(length as u16).to_be_bytes());
+                glyf.extend_from_slice(instructions.bytes(length)?);
+            }
+
+            stored.ok_or_else(||format!("Composite glyph {} has no bounding box.", i))?
+        };
+
+        x_mins.push(bounds[0]);
+        for (j, value) in bounds.iter().enumerate() {
+            glyf[start + 2 + j * 2..start + 4 + j * 2].copy_from_slice(&value.to_be_bytes());
+        }
+        glyf.resize((glyf.len() + 3) & !3, 0);
+    }
+    offsets.push(glyf.len());
+
+    // Short offsets only reach 0x1FFFE, a larger glyf table needs the long
+    // ones.
+    let index_format = if index_format == 0 && glyf.len() <= 0x1FFFE { 0 } else { 1 };
+    let mut loca     = Vec::with_capacity(offsets.len() * 4);
+
+    for offset in offsets {
+        if index_format == 0 {
+            loca.extend_from_slice(&((offset / 2) as u16).to_be_bytes());
+        } else {
+            loca.extend_from_slice(&(offset as u32).to_be_bytes());
+        }
+    }
+
+    Ok(Glyphs { glyf, loca, index_format, x_mins })
+}
+
+//*****************************************************************************
+fn reconstruct_hmtx(
+            data:   &[u8],
+            x_mins: &[i16],
+            hhea:   &[u8],
+            maxp:   &[u8]
+        ) -> Result<Vec<u8>, String> {
+    let num_metrics = read_u16(hhea, 34)? as usize;
+    let num_glyphs  = read_u16(maxp, 4)? as usize;
+    let mut input   = Stream::new(data);
+    let flags       = input.u8()?;
+
+    if num_metrics > num_glyphs || x_mins.len() < num_glyphs {
+        return Err("The hmtx table doesn't match the glyph count.".to_string());
+    }
+
+    let advances = (0..num_metrics)
+        .map(|_|input.u16())
+        .collect::<Result<Vec<u16>, String>>()?;
+
+    // Missing side bearings are the same as the glyph's minimum x.
+    let mut bearings = Vec::with_capacity(num_glyphs);
+
+    for (i, &x_min) in x_mins.iter().take(num_glyphs).enumerate() {
+        let absent = if i < num_metrics { flags & 1 != 0 } else { flags & 2 != 0 };
+        bearings.push(if absent { x_min } else { input.i16()? });
+    }
+
+    let mut output = Vec::with_capacity(num_metrics * 2 + num_glyphs * 2);
+
+    for (i, bearing) in bearings.iter().enumerate() {
+        if let Some(advance) = advances.get(i) {
+            output.extend_from_slice(&advance.to_be_bytes());
+        }
+        output.extend_from_slice(&bearing.to_be_bytes());
+    }
+
+    Ok(output)
+}
+
+//*****************************************************************************
+fn build_font(
+            stream:  &[u8],
+            entries: &[Entry],
+            flavor:  u32,
+            indices: &[usize]
+        ) -> Result<Vec<u8>, String> {
+    let table = |index: usize| {
+        let entry = &entries[index];
+        stream.get(entry.offset..entry.offset + entry.length)
+            .ok_or_else(||format!("WOFF2 table {} runs past the end of the data.", String::from_utf8_lossy(&entry.tag)))
+    };
+    let find = |tag: [u8; 4]| indices.iter().copied().find(|&x|entries[x].tag == tag);
+
+    let glyphs = match find(GLYF) {
+        Some(index) if entries[index].transformed => Some(reconstruct_glyf(table(index)?)?),
+        _                                         => None
+    };
+
+    let mut tables = Vec::with_capacity(indices.len());
+
+    for &index in indices {
+        let entry = &entries[index];
+
+        let data = match (&glyphs, entry.tag) {
+            (Some(glyphs), GLYF) => glyphs.glyf.clone(),
+            (Some(glyphs), LOCA) => glyphs.loca.clone(),
+            (Some(glyphs), HEAD) => {
+                let mut head = table(index)?.to_vec();
+                if let Some(format) = head.get_mut(50..52) {
+                    format.copy_from_slice(&glyphs.index_format.to_be_bytes());
+                }
+                head
+            },
+            (Some(glyphs), HMTX) if entry.transformed => {
+                let hhea = find(HHEA).ok_or("The hmtx table needs an hhea table.")?;
+                let maxp = find(MAXP).ok_or("The hmtx table needs a maxp table.")?;
+                reconstruct_hmtx(table(index)?, &glyphs.x_mins, table(hhea)?, table(maxp)?)?
+            },
+            _ if entry.transformed => {
+                return Err(format!("Unsupported transform of table {}.", String::from_utf8_lossy(&entry.tag)));
+            },
+            _ => table(index)?.to_vec()
+        };
+
+        tables.push((entry.tag, data));
+    }
+    tables.sort_by_key(|x|x.0);
+
+    let tables = tables.iter()
+        .map(|(tag, data)|(*tag, sfnt::checksum(tag, data), data.as_slice()))
+        .collect::<Vec<_>>();
+
+    sfnt::build(flavor, &tables)
+}
+
+//*****************************************************************************
+pub fn decode(
+            data: &[u8]
+        ) -> Result<Vec<Vec<u8>>, String> {
+    let flavor          = read_u32(data, 4)?;
+    let num_tables      = read_u16(data, 12)? as usize;
+    let compressed_size = read_u32(data, 20)? as usize;
+
+    let mut input = Stream::new(data);
+    input.bytes(HEADER_SIZE)?;
+
+    let entries = read_directory(&mut input, num_tables)?;
+    let fonts   = read_fonts(&mut input, flavor, num_tables)?;
+
+    // Every table is in one Brotli stream following the directories.
+    // The lengths aren't trusted with an allocation up front, only with
+    // where the stream ends.
+    let total      = entries.iter().map(|x|x.length as u64).sum();
+    let mut stream = Vec::new();
+    Decompressor::new(input.bytes(compressed_size)?, 4096)
+        .take(total)
+        .read_to_end(&mut stream)
+        .map_err(|x|format!("Unable to decompress WOFF2 data: {}", x))?;
+
+    fonts.iter()
+        .map(|(flavor, indices)|build_font(&stream, &entries, *flavor, indices))
+        .collect()
+}
+
+//*****************************************************************************
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //*************************************************************************
+    // The (x, y, on curve) points of a contour.
+    type Contour = Vec<(i32, i32, bool)>;
+
+    //*************************************************************************
+    // A transformed glyf table of simple glyphs.
+    fn transform(
+                glyphs:       &[Vec<Contour>],
+                index_format: u16
+            ) -> Vec<u8> {
+        let mut streams = [Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        streams[5] = vec![0; ((glyphs.len() + 31) >> 5) << 2];
+
+        for glyph in glyphs {
+            streams[0].extend_from_slice(&(glyph.len() as i16).to_be_bytes());
+            if glyph.is_empty() {
+                continue;
+            }
+
+            let mut last = (0, 0);
+            for contour in glyph {
+                streams[1].push(contour.len() as u8);
+                for &(x, y, on) in contour {
+                    let (dx, dy) = (x - last.0, y - last.1);
+                    let flag     = 124 + (dx >= 0) as u8 + 2 * (dy >= 0) as u8;
+                    streams[2].push(if on { flag } else { flag | 0x80 });
+                    streams[3].extend_from_slice(&(dx.unsigned_abs() as u16).to_be_bytes());
+                    streams[3].extend_from_slice(&(dy.unsigned_abs() as u16).to_be_bytes());
+                    last = (x, y);
+                }
+            }
+            streams[3].push(0);
+        }
+
+        let mut data = vec![0, 0, 0, 0];
+        data.extend_from_slice(&(glyphs.len() as u16).to_be_bytes());
+        data.extend_from_slice(&index_format.to_be_bytes());
+        for stream in streams.iter() {
+            data.extend_from_slice(&(stream.len() as u32).to_be_bytes());
+        }
+        for stream in streams.iter() {
+            data.extend_from_slice(stream);
+        }
+        data
+    }
+
+    //*************************************************************************
+    // The contours of a glyph read back out of the rebuilt tables.
+    fn contours(
+                glyphs: &Glyphs,
+                index:  usize
+            ) -> Vec<Contour> {
+        let offset = |i: usize| if glyphs.index_format == 0 {
+            read_u16(&glyphs.loca, i * 2).unwrap() as usize * 2
+        } else {
+            read_u32(&glyphs.loca, i * 4).unwrap() as usize
+        };
+        let (start, end) = (offset(index), offset(index + 1));
+        if start == end {
+            return Vec::new();
+        }
+
+        let glyph = &glyphs.glyf[start..end];
+        let count = read_u16(glyph, 0).unwrap() as usize;
+        let ends  = (0..count).map(|i|read_u16(glyph, 10 + i * 2).unwrap() as usize).collect::<Vec<_>>();
+        let total = ends.last().map_or(0, |x|x + 1);
+
+        let mut position = 10 + count * 2;
+        position += 2 + read_u16(glyph, position).unwrap() as usize;
+        let flags = &glyph[position..position + total];
+        position += total;
+
+        let mut read = |flag: u8, short: u8, same: u8|{
+            if flag & short != 0 {
+                position += 1;
+                let value = glyph[position - 1] as i32;
+                if flag & same != 0 { value } else { -value }
+            } else if flag & same != 0 {
+                0
+            } else {
+                position += 2;
+                read_u16(glyph, position - 2).unwrap() as i16 as i32
+            }
+        };
+        let xs = flags.iter().map(|&x|read(x, X_SHORT, X_SAME)).collect::<Vec<_>>();
+        let ys = flags.iter().map(|&x|read(x, Y_SHORT, Y_SAME)).collect::<Vec<_>>();
+
+        let mut result = vec![Vec::new()];
+        let mut last   = (0, 0);
+        for i in 0..total {
+            last = (last.0 + xs[i], last.1 + ys[i]);
+            result.last_mut().unwrap().push((last.0, last.1, flags[i] & ON_CURVE != 0));
+            if ends.contains(&i) && i + 1 < total {
+                result.push(Vec::new());
+            }
+        }
+        result
+    }
+
+    //*************************************************************************
+    #[test]
+    fn glyphs_survive_the_transform() {
+        let glyphs = vec![
+            Vec::new(),
+            vec![vec![(10, 0, true), (10, 700, true), (500, 700, false), (500, 0, true)]],
+            vec![
+                vec![(-300, -200, true), (0, 900, false), (300, -200, true)],
+                vec![(-100, 0, true), (0, 400, true), (100, 0, true)]
+            ]
+        ];
+        let rebuilt = reconstruct_glyf(&transform(&glyphs, 0)).unwrap();
+
+        assert_eq!(rebuilt.index_format, 0);
+        assert_eq!(rebuilt.loca.len(), (glyphs.len() + 1) * 2);
+        assert_eq!(rebuilt.x_mins, vec![0, 10, -300]);
+        for (i, glyph) in glyphs.iter().enumerate() {
+            assert_eq!(&contours(&rebuilt, i), glyph);
+        }
+
+        // The bounding box is worked out from the points.
+        let start = read_u16(&rebuilt.loca, 4).unwrap() as usize * 2;
+        let bounds = (0..4).map(|i|read_u16(&rebuilt.glyf, start + 2 + i * 2).unwrap() as i16).collect::<Vec<_>>();
+        assert_eq!(bounds, vec![-300, -200, 300, 900]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn large_glyf_tables_use_long_offsets() {
+        // Points far apart take five bytes each, so these glyphs pass the
+        // 0x1FFFE bytes short offsets reach.
+        let glyph  = vec![(0..250).map(|i|(i * 1000 % 30000, i * 700 % 20000, i % 2 == 0)).collect::<Vec<_>>()];
+        let glyphs = vec![glyph; 120];
+        let rebuilt = reconstruct_glyf(&transform(&glyphs, 0)).unwrap();
+
+        assert!(rebuilt.glyf.len() > 0x1FFFE);
+        assert_eq!(rebuilt.index_format, 1);
+        assert_eq!(rebuilt.loca.len(), (glyphs.len() + 1) * 4);
+        assert_eq!(read_u32(&rebuilt.loca, glyphs.len() * 4).unwrap() as usize, rebuilt.glyf.len());
+        assert_eq!(contours(&rebuilt, glyphs.len() - 1), glyphs[glyphs.len() - 1]);
+    }
+
+    //*************************************************************************
+    #[test]
+    fn empty_contours_are_an_error() {
+        let glyphs = vec![vec![Vec::new(), vec![(0, 0, true), (100, 100, true)]]];
+        let error  = reconstruct_glyf(&transform(&glyphs, 0)).err().unwrap();
+        assert_eq!(error, "Glyph 0 has a bad number of points.");
+
+        let glyphs = vec![
+            vec![vec![(0, 0, true)]],
+            vec![vec![(0, 0, true), (100, 100, true)], Vec::new(), vec![(50, 0, true)]]
+        ];
+        let error  = reconstruct_glyf(&transform(&glyphs, 0)).err().unwrap();
+        assert_eq!(error, "Glyph 1 has a bad number of points.");
+    }
+
+    //*************************************************************************
+    #[test]
+    fn moving_too_far_is_an_error() {
+        let glyphs = vec![vec![vec![(0, 0, true), (40000, 0, true)]]];
+        let error  = reconstruct_glyf(&transform(&glyphs, 0)).err().unwrap();
+        assert_eq!(error, "A point moves 40000 units, too far for a glyph.");
+    }
+}